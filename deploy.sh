@@ -7,8 +7,8 @@
 # 使用 Cargo 构建项目，`-r` 参数表示以 release 模式构建
 cargo build -r
 
-# 停止名为 `salvo-admin` 的 Docker 容器
-docker stop salvo-admin
+# 停止名为 `salvo-admin` 的 Docker 容器,等待35秒让服务处理完正在进行的请求(大于server.shutdown_timeout)
+docker stop -t 35 salvo-admin
 
 # 强制删除名为 `salvo-admin` 的 Docker 容器
 docker rm -f salvo-admin
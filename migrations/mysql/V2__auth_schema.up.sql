@@ -0,0 +1,24 @@
+-- 权限标识、双因素认证和Argon2id密码需要的表结构变更
+-- 明文密码会在用户下次登录成功后自动转为Argon2id密文
+
+alter table sys_user modify password varchar(255) not null comment '密码(Argon2id PHC格式)';
+
+alter table sys_menu modify api_url varchar(255) default '' not null comment '接口URL(支持{param}、*和**通配符,可以在前面指定HTTP方法,例如: GET /api/system/post/{id})';
+alter table sys_menu add column perms varchar(100) default '' not null comment '权限标识(例如: system:user:add)' after api_url;
+
+alter table sys_role add column mfa_required tinyint default 0 not null comment '是否要求双因素认证(0:否,1:是)' after status;
+
+create table sys_user_mfa
+(
+    id             bigint auto_increment comment '主键'
+        primary key,
+    user_id        bigint                                 not null comment '用户id',
+    secret         varchar(64)                            not null comment 'TOTP密钥(Base32)',
+    status         tinyint      default 0                 not null comment '状态(0:待验证,1:已启用)',
+    recovery_codes varchar(1024) default ''               not null comment '恢复码的SHA-256摘要,逗号分隔,使用后移除',
+    last_used_step bigint       default 0                 not null comment '最后一次使用的时间步,防止动态码被重放',
+    create_time    datetime     default CURRENT_TIMESTAMP not null comment '创建时间',
+    update_time    datetime     default CURRENT_TIMESTAMP not null on update CURRENT_TIMESTAMP comment '修改时间',
+    constraint user_id
+        unique (user_id)
+) comment '用户双因素认证';
@@ -0,0 +1,6 @@
+-- 回滚权限标识和双因素认证的表结构
+-- 密码字段保持varchar(255),已经转为Argon2id的密文无法再缩短
+
+drop table if exists sys_user_mfa;
+alter table sys_role drop column mfa_required;
+alter table sys_menu drop column perms;
@@ -0,0 +1,10 @@
+INSERT INTO sys_dept (id, parent_id, ancestors, dept_name, sort, leader, phone, email, status, del_flag) VALUES (1, 0, '0', '测试科技', 1, 'admin', '18613030352', '1002219331@qq.com', 1, 1);
+INSERT INTO sys_dept (id, parent_id, ancestors, dept_name, sort, leader, phone, email, status, del_flag) VALUES (2, 1, '0,1', '深圳总公司', 1, '1', '1', 'xx@qq.com', 1, 1);
+INSERT INTO sys_dept (id, parent_id, ancestors, dept_name, sort, leader, phone, email, status, del_flag) VALUES (3, 1, '0,1', '长沙分公司', 2, '1', '1', 'xx@qq.com', 1, 1);
+INSERT INTO sys_dept (id, parent_id, ancestors, dept_name, sort, leader, phone, email, status, del_flag) VALUES (4, 2, '0,1,2', '研发部门', 1, '1', '1', 'xx@qq.com', 1, 1);
+INSERT INTO sys_dept (id, parent_id, ancestors, dept_name, sort, leader, phone, email, status, del_flag) VALUES (5, 2, '0,1,2', '市场部门', 2, '1', '1', 'xx@qq.com', 1, 1);
+INSERT INTO sys_dept (id, parent_id, ancestors, dept_name, sort, leader, phone, email, status, del_flag) VALUES (6, 2, '0,1,2', '测试部门', 3, '1', '1', 'xx@qq.com', 1, 1);
+INSERT INTO sys_dept (id, parent_id, ancestors, dept_name, sort, leader, phone, email, status, del_flag) VALUES (7, 2, '0,1,2', '财务部门', 4, '1', '1', 'xx@qq.com', 1, 1);
+INSERT INTO sys_dept (id, parent_id, ancestors, dept_name, sort, leader, phone, email, status, del_flag) VALUES (8, 2, '0,1,2', '运维部门', 5, '1', '1', 'xx@qq.com', 1, 1);
+INSERT INTO sys_dept (id, parent_id, ancestors, dept_name, sort, leader, phone, email, status, del_flag) VALUES (9, 3, '0,1,3', '市场部门1', 6, '1', '1', 'xx@qq.com', 1, 1);
+INSERT INTO sys_dept (id, parent_id, ancestors, dept_name, sort, leader, phone, email, status, del_flag) VALUES (10, 3, '0,1,3', '财务部门1', 1, '1', '1', 'xx@qq.com', 1, 1);
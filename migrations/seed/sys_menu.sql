@@ -0,0 +1,207 @@
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (1, '首页', 1, 1, 1, 0, '/home', '', 'DashboardOutlined', '首页');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (2, '权限管理', 1, 1, 2, 0, '/system', '', 'SettingOutlined', '权限管理');
+
+-- 配置用户信息权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (3, '用户管理', 2, 1, 1, 2, '/system/user', '', 'UserOutlined', '用户信息管理');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (4, '添加用户信息', 3, 1, 1, 3, '', '/api/system/user/addUser', '', '添加用户信息');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (5, '删除用户信息', 3, 1, 2, 3, '', '/api/system/user/deleteUser', '', '删除用户信息');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (6, '更新用户信息', 3, 1, 3, 3, '', '/api/system/user/updateUser', '', '更新用户信息');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (7, '更新用户信息状态', 3, 1, 4, 3, '', '/api/system/user/updateUserStatus', '', '更新用户信息状态');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (8, '更新用户密码', 3, 1, 4, 3, '', '/api/system/user/updateUserPassword', '', '更新用户密码');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (9, '查询用户信息详情', 3, 1, 5, 3, '', '/api/system/user/queryUserDetail', '', '查询用户信息详情');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (10, '查询用户信息列表', 3, 1, 6, 3, '', '/api/system/user/queryUserList', '', '查询用户信息列表');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (11, '用户登录', 3, 1, 7, 3, '', '/api/system/user/login', '', '用户登录');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (12, '查询用户菜单列表', 3, 1, 8, 3, '', '/api/system/user/queryUserMenu', '', '查询用户菜单列表');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (13, '查询用户角色信息', 3, 1, 9, 3, '', '/api/system/user/queryUserRole', '', '查询用户角色信息');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (14, '更新用户角色信息', 3, 1, 10, 3, '', '/api/system/user/updateUserRole', '', '更新用户角色信息');
+
+-- 配置角色信息权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (15, '角色管理', 2, 1, 2, 2, '/system/role', '', 'UsergroupAddOutlined', '角色信息管理');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (16, '添加角色信息', 3, 1, 1, 15, '', '/api/system/role/addRole', '', '添加角色信息');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (17, '删除角色信息', 3, 1, 2, 15, '', '/api/system/role/deleteRole', '', '删除角色信息');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (18, '更新角色信息', 3, 1, 3, 15, '', '/api/system/role/updateRole', '', '更新角色信息');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (19, '更新角色信息状态', 3, 1, 4, 15, '', '/api/system/role/updateRoleStatus', '', '更新角色信息状态');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (20, '查询角色信息详情', 3, 1, 5, 15, '', '/api/system/role/queryRoleDetail', '', '查询角色信息详情');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (21, '查询角色信息列表', 3, 1, 6, 15, '', '/api/system/role/queryRoleList', '', '查询角色信息列表');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (22, '查询角色菜单列表', 3, 1, 7, 15, '', '/api/system/role/queryRoleMenu', '', '查询角色菜单列表');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (23, '更新角色菜单信息', 3, 1, 8, 15, '', '/api/system/role/updateRoleMenu', '', '更新角色菜单信息');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (24, '查询已分配用户角色', 3, 1, 8, 15, '', '/api/system/role/queryAllocatedList', '', '查询已分配用户角色');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (25, '查询未分配用户角色', 3, 1, 8, 15, '', '/api/system/role/queryUnallocatedList', '', '查询未分配用户角色');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (26, '取消授权用户', 3, 1, 8, 15, '', '/api/system/role/cancelAuthUser', '', '取消授权用户');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (27, '批量取消授权用户', 3, 1, 8, 15, '', '/api/system/role/batchCancelAuthUser', '', '批量取消授权用户');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (28, '批量选择用户授权', 3, 1, 8, 15, '', '/api/system/role/batchAuthUser', '', '批量选择用户授权');
+
+-- 配置菜单信息权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (29, '菜单管理', 2, 1, 3, 2, '/system/menu', '', 'MenuOutlined', '菜单信息管理');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (30, '添加菜单', 3, 1, 1, 29, '', '/api/system/menu/addMenu', '', '添加菜单');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (31, '删除菜单', 3, 1, 2, 29, '', '/api/system/menu/deleteMenu', '', '删除菜单');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (32, '更新菜单', 3, 1, 3, 29, '', '/api/system/menu/updateMenu', '', '更新菜单');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (33, '更新菜单状态', 3, 1, 4, 29, '', '/api/system/menu/updateMenuStatus', '', '更新菜单状态');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (34, '查询菜单详情', 3, 1, 5, 29, '', '/api/system/menu/queryMenuDetail', '', '查询菜单详情');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (35, '查询菜单列表', 3, 1, 6, 29, '', '/api/system/menu/queryMenuList', '', '查询菜单列表');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (36, '查询菜单树', 3, 1, 6, 29, '', '/api/system/menu/queryMenuListSimple', '', '查询菜单树');
+
+-- 配置部门权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (37, '部门管理', 2, 1, 4, 2, '/system/dept', '', 'ApartmentOutlined', '部门管理');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (38, '添加部门', 3, 1, 1, 37, '', '/api/system/dept/addDept', '', '添加部门');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (39, '删除部门', 3, 1, 2, 37, '', '/api/system/dept/deleteDept', '', '删除部门');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (40, '更新部门', 3, 1, 3, 37, '', '/api/system/dept/updateDept', '', '更新部门');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (41, '更新部门状态', 3, 1, 4, 37, '', '/api/system/dept/updateDeptStatus', '', '更新部门状态');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (42, '查询部门详情', 3, 1, 5, 37, '', '/api/system/dept/queryDeptDetail', '', '查询部门详情');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (43, '查询部门列', 3, 1, 6, 37, '', '/api/system/dept/queryDeptList', '', '查询部门列');
+
+-- 配置岗位权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (44, '岗位管理', 2, 1, 5, 2, '/system/post', '', 'AuditOutlined', '岗位管理');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (45, '添加岗位', 3, 1, 1, 44, '', '/api/system/post/addPost', '', '添加岗位');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (46, '删除岗位', 3, 1, 2, 44, '', '/api/system/post/deletePost', '', '删除岗位');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (47, '更新岗位', 3, 1, 3, 44, '', '/api/system/post/updatePost', '', '更新岗位');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (48, '更新岗位状态', 3, 1, 4, 44, '', '/api/system/post/updatePostStatus', '', '更新岗位状态');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (49, '查询岗位详情', 3, 1, 5, 44, '', '/api/system/post/queryPostDetail', '', '查询岗位详情');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (50, '查询岗位列', 3, 1, 6, 44, '', '/api/system/post/queryPostList', '', '查询岗位列');
+
+-- 配置字典类型权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (51, '字典类型', 2, 1, 6, 2, '/system/dictType', '', 'TableOutlined', '字典类型管理');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (52, '添加字典类型', 3, 1, 1, 51, '', '/api/system/dictType/addDictType', '', '添加字典类型');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (53, '删除字典类型', 3, 1, 2, 51, '', '/api/system/dictType/deleteDictType', '', '删除字典类型');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (54, '更新字典类型', 3, 1, 3, 51, '', '/api/system/dictType/updateDictType', '', '更新字典类型');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (55, '更新字典类型状态', 3, 1, 4, 51, '', '/api/system/dictType/updateDictTypeStatus', '', '更新字典类型状态');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (56, '查询字典类型详情', 3, 1, 5, 51, '', '/api/system/dictType/queryDictTypeDetail', '', '查询字典类型详情');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (57, '查询字典类型列', 3, 1, 6, 51, '', '/api/system/dictType/queryDictTypeList', '', '查询字典类型列');
+
+-- 配置字典数据权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, visible, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (58, '字典数据', 2, 1, 0,7, 2, '/system/dictData', '', 'UngroupOutlined', '字典数据管理');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (59, '添加字典数据', 3, 1, 1, 58, '', '/api/system/dictData/addDictData', '', '添加字典数据');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (60, '删除字典数据', 3, 1, 2, 58, '', '/api/system/dictData/deleteDictData', '', '删除字典数据');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (61, '更新字典数据', 3, 1, 3, 58, '', '/api/system/dictData/updateDictData', '', '更新字典数据');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (62, '更新字典数据状态', 3, 1, 4, 58, '', '/api/system/dictData/updateDictDataStatus', '', '更新字典数据状态');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (63, '查询字典数据详情', 3, 1, 5, 58, '', '/api/system/dictData/queryDictDataDetail', '', '查询字典数据详情');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (64, '查询字典数据列', 3, 1, 6, 58, '', '/api/system/dictData/queryDictDataList', '', '查询字典数据列');
+
+-- 配置通知公告权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (65, '通知公告', 2, 1, 8, 2, '/system/notice', '', 'MessageOutlined', '通知公告管理');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (66, '添加通知公告', 3, 1, 1, 65, '', '/api/system/notice/addNotice', '', '添加通知公告');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (67, '删除通知公告', 3, 1, 2, 65, '', '/api/system/notice/deleteNotice', '', '删除通知公告');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (68, '更新通知公告', 3, 1, 3, 65, '', '/api/system/notice/updateNotice', '', '更新通知公告');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (69, '更新通知公告状态', 3, 1, 4, 65, '', '/api/system/notice/updateNoticeStatus', '', '更新通知公告状态');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (70, '查询通知公告详情', 3, 1, 5, 65, '', '/api/system/notice/queryNoticeDetail', '', '查询通知公告详情');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (71, '查询通知公告列', 3, 1, 6, 65, '', '/api/system/notice/queryNoticeList', '', '查询通知公告列');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (72, '日志管理', 1, 1, 2, 0, '/log', '', 'FilterOutlined', '日志管理');
+
+-- 配置系统访问记录权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (73, '登录日志', 2, 1, 9, 72, '/log/loginLog', '', 'DeleteOutlined', '系统访问记录管理');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (74, '添加系统访问记录', 3, 1, 1, 73, '', '/api/system/loginLog/addLoginLog', '', '添加系统访问记录');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (75, '删除系统访问记录', 3, 1, 2, 73, '', '/api/system/loginLog/deleteLoginLog', '', '删除系统访问记录');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (76, '清空系统登录日志', 3, 1, 3, 73, '', '/api/system/loginLog/cleanLoginLog', '', '清空系统登录日志');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (77, '更新系统访问记录状态', 3, 1, 4, 73, '', '/api/system/loginLog/updateLoginLogStatus', '', '更新系统访问记录状态');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (78, '查询系统访问记录详情', 3, 1, 5, 73, '', '/api/system/loginLog/queryLoginLogDetail', '', '查询系统访问记录详情');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (79, '查询系统访问记录列', 3, 1, 6, 73, '', '/api/system/loginLog/queryLoginLogList', '', '查询系统访问记录列');
+
+-- 配置操作日志记录权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (80, '操作日志', 2, 1, 10, 72, '/log/operateLog', '', 'ClearOutlined', '操作日志记录管理');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (81, '添加操作日志记录', 3, 1, 1, 80, '', '/api/system/operateLog/addOperateLog', '', '添加操作日志记录');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (82, '删除操作日志记录', 3, 1, 2, 80, '', '/api/system/operateLog/deleteOperateLog', '', '删除操作日志记录');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (83, '清空操作日志记录', 3, 1, 3, 80, '', '/api/system/operateLog/cleanOperateLog', '', '清空操作日志记录');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (84, '更新操作日志记录状态', 3, 1, 4, 80, '', '/api/system/operateLog/updateOperateLogStatus', '', '更新操作日志记录状态');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (85, '查询操作日志记录详情', 3, 1, 5, 80, '', '/api/system/operateLog/queryOperateLogDetail', '', '查询操作日志记录详情');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (86, '查询操作日志记录列', 3, 1, 6, 80, '', '/api/system/operateLog/queryOperateLogList', '', '查询操作日志记录列');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (87, '其他', 1, 1, 3, 0, '/other', '', 'AudioOutlined', '其他');
+
+-- 配置在线用户权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (88, '在线用户', 2, 1, 11, 72, '/log/online', '', 'TeamOutlined', '在线用户管理');
+
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (89, '查询在线用户列表', 3, 1, 1, 88, '', '/api/system/online/queryOnlineList', '', '查询在线用户列表');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (90, '强制退出', 3, 1, 2, 88, '', '/api/system/online/forceLogout', '', '强制退出');
+
+-- 配置解锁用户权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, menu_icon, remark) VALUES (91, '解锁用户', 3, 1, 11, 3, '', '/api/system/user/unlockUser', '', '解锁登录失败次数过多被锁定的用户');
+
+-- 配置角色数据权限
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, perms, menu_icon, remark) VALUES (92, '查询角色数据权限', 3, 1, 9, 15, '', '/api/system/role/queryRoleDept', 'system:role:queryDept', '', '查询角色数据权限');
+INSERT INTO sys_menu (id, menu_name, menu_type, status, sort, parent_id, menu_url, api_url, perms, menu_icon, remark) VALUES (93, '更新角色数据权限', 3, 1, 9, 15, '', '/api/system/role/updateRoleDept', 'system:role:authDept', '', '更新角色数据权限');
+
+-- 配置按钮的权限标识,接口通过require_perm校验权限标识,不再依赖接口URL
+update sys_menu set perms = 'system:user:add' where api_url = '/api/system/user/addUser';
+update sys_menu set perms = 'system:user:remove' where api_url = '/api/system/user/deleteUser';
+update sys_menu set perms = 'system:user:edit' where api_url = '/api/system/user/updateUser';
+update sys_menu set perms = 'system:user:status' where api_url = '/api/system/user/updateUserStatus';
+update sys_menu set perms = 'system:user:query' where api_url = '/api/system/user/queryUserDetail';
+update sys_menu set perms = 'system:user:list' where api_url = '/api/system/user/queryUserList';
+update sys_menu set perms = 'system:user:queryRole' where api_url = '/api/system/user/queryUserRole';
+update sys_menu set perms = 'system:user:authRole' where api_url = '/api/system/user/updateUserRole';
+update sys_menu set perms = 'system:role:add' where api_url = '/api/system/role/addRole';
+update sys_menu set perms = 'system:role:remove' where api_url = '/api/system/role/deleteRole';
+update sys_menu set perms = 'system:role:edit' where api_url = '/api/system/role/updateRole';
+update sys_menu set perms = 'system:role:status' where api_url = '/api/system/role/updateRoleStatus';
+update sys_menu set perms = 'system:role:query' where api_url = '/api/system/role/queryRoleDetail';
+update sys_menu set perms = 'system:role:list' where api_url = '/api/system/role/queryRoleList';
+update sys_menu set perms = 'system:role:queryMenu' where api_url = '/api/system/role/queryRoleMenu';
+update sys_menu set perms = 'system:role:authMenu' where api_url = '/api/system/role/updateRoleMenu';
+update sys_menu set perms = 'system:role:allocatedList' where api_url = '/api/system/role/queryAllocatedList';
+update sys_menu set perms = 'system:role:unallocatedList' where api_url = '/api/system/role/queryUnallocatedList';
+update sys_menu set perms = 'system:role:cancelAuth' where api_url = '/api/system/role/cancelAuthUser';
+update sys_menu set perms = 'system:role:cancelAuth' where api_url = '/api/system/role/batchCancelAuthUser';
+update sys_menu set perms = 'system:role:auth' where api_url = '/api/system/role/batchAuthUser';
+update sys_menu set perms = 'system:menu:add' where api_url = '/api/system/menu/addMenu';
+update sys_menu set perms = 'system:menu:remove' where api_url = '/api/system/menu/deleteMenu';
+update sys_menu set perms = 'system:menu:edit' where api_url = '/api/system/menu/updateMenu';
+update sys_menu set perms = 'system:menu:status' where api_url = '/api/system/menu/updateMenuStatus';
+update sys_menu set perms = 'system:menu:query' where api_url = '/api/system/menu/queryMenuDetail';
+update sys_menu set perms = 'system:menu:list' where api_url = '/api/system/menu/queryMenuList';
+update sys_menu set perms = 'system:menu:tree' where api_url = '/api/system/menu/queryMenuListSimple';
+update sys_menu set perms = 'system:dept:add' where api_url = '/api/system/dept/addDept';
+update sys_menu set perms = 'system:dept:remove' where api_url = '/api/system/dept/deleteDept';
+update sys_menu set perms = 'system:dept:edit' where api_url = '/api/system/dept/updateDept';
+update sys_menu set perms = 'system:dept:status' where api_url = '/api/system/dept/updateDeptStatus';
+update sys_menu set perms = 'system:dept:query' where api_url = '/api/system/dept/queryDeptDetail';
+update sys_menu set perms = 'system:dept:list' where api_url = '/api/system/dept/queryDeptList';
+update sys_menu set perms = 'system:post:add' where api_url = '/api/system/post/addPost';
+update sys_menu set perms = 'system:post:remove' where api_url = '/api/system/post/deletePost';
+update sys_menu set perms = 'system:post:edit' where api_url = '/api/system/post/updatePost';
+update sys_menu set perms = 'system:post:status' where api_url = '/api/system/post/updatePostStatus';
+update sys_menu set perms = 'system:post:query' where api_url = '/api/system/post/queryPostDetail';
+update sys_menu set perms = 'system:post:list' where api_url = '/api/system/post/queryPostList';
+update sys_menu set perms = 'system:dictType:add' where api_url = '/api/system/dictType/addDictType';
+update sys_menu set perms = 'system:dictType:remove' where api_url = '/api/system/dictType/deleteDictType';
+update sys_menu set perms = 'system:dictType:edit' where api_url = '/api/system/dictType/updateDictType';
+update sys_menu set perms = 'system:dictType:status' where api_url = '/api/system/dictType/updateDictTypeStatus';
+update sys_menu set perms = 'system:dictType:query' where api_url = '/api/system/dictType/queryDictTypeDetail';
+update sys_menu set perms = 'system:dictType:list' where api_url = '/api/system/dictType/queryDictTypeList';
+update sys_menu set perms = 'system:dictData:add' where api_url = '/api/system/dictData/addDictData';
+update sys_menu set perms = 'system:dictData:remove' where api_url = '/api/system/dictData/deleteDictData';
+update sys_menu set perms = 'system:dictData:edit' where api_url = '/api/system/dictData/updateDictData';
+update sys_menu set perms = 'system:dictData:status' where api_url = '/api/system/dictData/updateDictDataStatus';
+update sys_menu set perms = 'system:dictData:query' where api_url = '/api/system/dictData/queryDictDataDetail';
+update sys_menu set perms = 'system:dictData:list' where api_url = '/api/system/dictData/queryDictDataList';
+update sys_menu set perms = 'system:notice:add' where api_url = '/api/system/notice/addNotice';
+update sys_menu set perms = 'system:notice:remove' where api_url = '/api/system/notice/deleteNotice';
+update sys_menu set perms = 'system:notice:edit' where api_url = '/api/system/notice/updateNotice';
+update sys_menu set perms = 'system:notice:status' where api_url = '/api/system/notice/updateNoticeStatus';
+update sys_menu set perms = 'system:notice:query' where api_url = '/api/system/notice/queryNoticeDetail';
+update sys_menu set perms = 'system:notice:list' where api_url = '/api/system/notice/queryNoticeList';
+update sys_menu set perms = 'system:loginLog:add' where api_url = '/api/system/loginLog/addLoginLog';
+update sys_menu set perms = 'system:loginLog:remove' where api_url = '/api/system/loginLog/deleteLoginLog';
+update sys_menu set perms = 'system:loginLog:clean' where api_url = '/api/system/loginLog/cleanLoginLog';
+update sys_menu set perms = 'system:loginLog:status' where api_url = '/api/system/loginLog/updateLoginLogStatus';
+update sys_menu set perms = 'system:loginLog:query' where api_url = '/api/system/loginLog/queryLoginLogDetail';
+update sys_menu set perms = 'system:loginLog:list' where api_url = '/api/system/loginLog/queryLoginLogList';
+update sys_menu set perms = 'system:operateLog:add' where api_url = '/api/system/operateLog/addOperateLog';
+update sys_menu set perms = 'system:operateLog:remove' where api_url = '/api/system/operateLog/deleteOperateLog';
+update sys_menu set perms = 'system:operateLog:clean' where api_url = '/api/system/operateLog/cleanOperateLog';
+update sys_menu set perms = 'system:operateLog:status' where api_url = '/api/system/operateLog/updateOperateLogStatus';
+update sys_menu set perms = 'system:operateLog:query' where api_url = '/api/system/operateLog/queryOperateLogDetail';
+update sys_menu set perms = 'system:operateLog:list' where api_url = '/api/system/operateLog/queryOperateLogList';
+update sys_menu set perms = 'system:online:list' where api_url = '/api/system/online/queryOnlineList';
+update sys_menu set perms = 'system:online:forceLogout' where api_url = '/api/system/online/forceLogout';
+update sys_menu set perms = 'system:user:unlock' where api_url = '/api/system/user/unlockUser';
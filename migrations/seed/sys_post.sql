@@ -0,0 +1,4 @@
+INSERT INTO sys_post (id, post_code, post_name, sort, status, remark) VALUES (1, 'ceo', '董事长', 1, 1, '');
+INSERT INTO sys_post (id, post_code, post_name, sort, status, remark) VALUES (2, 'se', '项目经理', 2, 1, '');
+INSERT INTO sys_post (id, post_code, post_name, sort, status, remark) VALUES (3, 'hr', '人力资源', 3, 1, '');
+INSERT INTO sys_post (id, post_code, post_name, sort, status, remark) VALUES (4, 'user', '普通员工', 1, 1, '');
@@ -0,0 +1,2 @@
+INSERT INTO sys_user (id, mobile, user_name, nick_name, email, password, status, remark) VALUES (1, '18613030111', 'admin','admin', 'xx@qq.com','$argon2id$v=19$m=19456,t=2,p=1$B8dyhFfKp+cV9WS6nVv3iA$7AB+PcoZI9lJLmRu3pdiB3lJCekY+7DgNliPeZMuyDw', 1,  '超级管理员');
+INSERT INTO sys_user (id, mobile, user_name, nick_name, email, password, status, remark) VALUES (2, '18613030222', 'test', 'test', '123@qq.com','$argon2id$v=19$m=19456,t=2,p=1$v1kAk+OJvFmqlT5b3DoQ9g$eCt8bTuPR+8mujFmcOj/Vx9ZtbUmXBXcH0ZJ0ZmhXw0', 1, '演示权限');
@@ -0,0 +1,2 @@
+insert into sys_user_post values ('1', '1');
+insert into sys_user_post values ('2', '2');
@@ -0,0 +1 @@
+INSERT INTO sys_user_role (user_id, role_id) VALUES (1, 1);
@@ -0,0 +1,5 @@
+-- 回滚权限标识和双因素认证的表结构
+
+drop table if exists sys_user_mfa;
+alter table sys_role drop column mfa_required;
+alter table sys_menu drop column perms;
@@ -0,0 +1,18 @@
+-- 权限标识和双因素认证需要的表结构变更,和migrations/mysql/V2__auth_schema.up.sql保持一致
+-- SQLite不限制varchar的长度,密码字段不需要修改
+
+alter table sys_menu add column perms varchar(100) default '' not null; -- 权限标识(例如: system:user:add)
+
+alter table sys_role add column mfa_required tinyint default 0 not null; -- 是否要求双因素认证(0:否,1:是)
+
+create table sys_user_mfa
+(
+    id             integer primary key autoincrement,              -- 主键
+    user_id        bigint                                 not null unique, -- 用户id
+    secret         varchar(64)                            not null, -- TOTP密钥(Base32)
+    status         tinyint       default 0                not null, -- 状态(0:待验证,1:已启用)
+    recovery_codes varchar(1024) default ''               not null, -- 恢复码的SHA-256摘要,逗号分隔,使用后移除
+    last_used_step bigint        default 0                not null, -- 最后一次使用的时间步,防止动态码被重放
+    create_time    datetime      default CURRENT_TIMESTAMP not null, -- 创建时间
+    update_time    datetime      default CURRENT_TIMESTAMP not null  -- 修改时间
+);
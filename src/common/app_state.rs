@@ -0,0 +1,52 @@
+use crate::common::app_config::AppConfig;
+use crate::common::error::{AppError, AppResult};
+use crate::utils::ip_util::IpResolver;
+use crate::utils::jwt_key_util::JwtKeys;
+use crate::utils::operate_log_util::OperateLogWriter;
+use crate::utils::permission_util::PermissionCache;
+use crate::utils::rate_limit_util::RateLimiter;
+use crate::utils::session_store_util::SessionStore;
+use rbatis::RBatis;
+use salvo::Depot;
+use std::sync::Arc;
+
+/*
+ *应用状态: 数据库、redis、配置和缓存,在main中创建后注入到Depot
+ *路由只依赖传入的状态,同一个进程中可以用不同的数据库启动多个实例
+ */
+pub struct AppState {
+    pub rb: RBatis,                           //数据库
+    pub redis: SessionStore,                  //redis或内存存储
+    pub config: AppConfig,                    //应用配置
+    pub jwt_keys: JwtKeys,                    //jwt签名密钥
+    pub ip_resolver: IpResolver,              //客户端IP和归属地解析
+    pub rate_limiter: RateLimiter,            //限流
+    pub operate_log_writer: OperateLogWriter, //操作日志后台写入任务
+    pub permission_cache: PermissionCache,    //已编译的用户权限
+    pub password_rehash: bool,                //登录成功后是否把明文或者旧参数的密码重新加密
+}
+
+impl AppState {
+    /// 数据库和会话存储由调用方创建,其它状态根据配置生成,并启动操作日志的后台写入任务
+    pub fn new(rb: RBatis, redis: SessionStore, config: AppConfig) -> Result<Self, String> {
+        let jwt_keys = JwtKeys::from_config(&config.jwt).map_err(|e| format!("加载jwt密钥失败: {}", e))?;
+        Ok(AppState {
+            jwt_keys,
+            ip_resolver: IpResolver::from_config(&config.ip),
+            rate_limiter: RateLimiter::new(&config.rate_limit, &redis),
+            operate_log_writer: OperateLogWriter::start(config.operate_log.clone(), rb.clone()),
+            permission_cache: PermissionCache::default(),
+            password_rehash: true,
+            rb,
+            redis,
+            config,
+        })
+    }
+}
+
+/*
+ *从Depot中获取应用状态
+ */
+pub fn app_state(depot: &Depot) -> AppResult<Arc<AppState>> {
+    depot.obtain::<Arc<AppState>>().cloned().map_err(|_| AppError::BusinessError("获取应用状态异常"))
+}
@@ -1,5 +1,6 @@
 use crate::common::result::BaseResponse;
 use redis::RedisError;
+use salvo::oapi::{Components, EndpointOutRegister, Operation, ToSchema};
 use salvo::prelude::Json;
 use salvo::{Depot, Request, Response, Writer};
 use thiserror::Error;
@@ -17,6 +18,9 @@ pub enum AppError {
     #[error("")]
     JwtTokenError(String),
 
+    #[error("密码加密错误: {0}")]
+    PasswordHashError(String),
+
     #[error("解析请求参数错误: {0}")]
     ParseError(#[from] salvo::http::ParseError),
 
@@ -28,17 +32,22 @@ pub enum AppError {
 
     #[error("业务异常: {0}")]
     BusinessError(&'static str),
+
+    #[error("{0}")]
+    LoginLockedError(String),
 }
 pub type AppResult<T> = Result<T, AppError>;
 
 #[async_trait]
 impl Writer for AppError {
-    async fn write(mut self, _req: &mut Request, _: &mut Depot, res: &mut Response) {
+    async fn write(mut self, _req: &mut Request, depot: &mut Depot, res: &mut Response) {
         // let user_id = depot.get::<i64>("userId").copied().unwrap();
         // let username = depot.get::<String>("username").unwrap();
         // log::info!("query user user_id params {:?}", user_id);
         // log::info!("query user username params {:?}", username);
 
+        //操作日志据此判断操作是否失败
+        depot.insert("errorMsg", self.to_string());
         res.render(Json(BaseResponse {
             msg: self.to_string(),
             code: 0,
@@ -46,3 +55,12 @@ impl Writer for AppError {
         }))
     }
 }
+
+//接口文档中错误也以统一返回vo输出,msg为失败原因
+impl EndpointOutRegister for AppError {
+    fn register(components: &mut Components, operation: &mut Operation) {
+        let schema = BaseResponse::<String>::to_schema(components);
+        let response = salvo::oapi::Response::new("统一返回vo,失败时msg为失败原因").add_content("application/json", schema);
+        operation.responses.insert("200", response);
+    }
+}
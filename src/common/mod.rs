@@ -1,2 +1,4 @@
+pub mod app_config;
+pub mod app_state;
 pub mod error;
 pub mod result;
@@ -1,19 +1,20 @@
 use crate::common::error::AppResult;
 use salvo::prelude::Json;
 use salvo::Response;
+use salvo::oapi::ToSchema;
 use serde::Serialize;
 use std::fmt::Debug;
 use rbatis::rbdc::DateTime;
 
 // 统一返回vo
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, ToSchema)]
 pub struct BaseResponse<T> {
     pub code: i32,
     pub msg: String,
     pub data: Option<T>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, ToSchema)]
 pub struct ResponsePage<T> {
     pub code: i32,
     pub msg: &'static str,
@@ -0,0 +1,6 @@
+#[cfg(feature = "postgres")]
+pub mod postgres_driver;
+#[cfg(feature = "postgres")]
+pub mod postgres_tls;
+#[cfg(any(feature = "sqlite", test))]
+pub mod sqlite_driver;
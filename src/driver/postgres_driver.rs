@@ -0,0 +1,463 @@
+use crate::driver::postgres_tls::{client_config, split_url, PgSslMode};
+use bytes::BytesMut;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use rbatis::rbdc::db::{ConnectOptions, Connection, Driver, ExecResult, MetaData, Row};
+use rbatis::rbdc::Error;
+use rbs::Value;
+use rust_decimal::Decimal;
+use rustls::ClientConfig;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio_postgres::config::SslMode;
+use tokio_postgres::types::{to_sql_checked, FromSql, IsNull, Kind, ToSql, Type};
+use tokio_postgres::{Client, Config, NoTls, Statement};
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+type ToSqlResult = Result<IsNull, Box<dyn std::error::Error + Sync + Send>>;
+type FromSqlResult<T> = Result<T, Box<dyn std::error::Error + Sync + Send>>;
+
+/*
+ *基于tokio-postgres的rbatis驱动
+ *连接地址格式: postgres://用户名:密码@主机:端口/库名?sslmode=verify-full&sslrootcert=CA证书路径,TLS参数见postgres_tls
+ *参数按预编译语句推断出的类型转换,所以MySQL风格的'1'和1可以混用
+ *支持的字段类型见supported,其它类型读写时返回错误
+ */
+#[derive(Debug)]
+pub struct PgDriver {}
+
+#[derive(Debug, Default)]
+pub struct PgConnectOptions {
+    config: Config,                   //去掉TLS参数后的连接配置
+    tls: Option<Arc<ClientConfig>>, //sslmode=disable时为None
+}
+
+pub struct PgConnection {
+    client: Client,
+}
+
+#[derive(Debug)]
+pub struct PgRow {
+    columns: Arc<Vec<(String, String)>>, //字段名和类型名
+    values: Vec<Value>,
+}
+
+#[derive(Debug)]
+pub struct PgMetaData {
+    columns: Arc<Vec<(String, String)>>,
+}
+
+/// rbatis的参数,写入时才知道目标类型
+#[derive(Debug, Clone)]
+struct PgParam(Value);
+
+/// 按字段类型读取的值
+#[derive(Debug)]
+struct PgValue(Value);
+
+/// 数据库返回的错误只有db error,类型转换的错误只有序号,需要取出具体的错误信息
+fn to_error(e: tokio_postgres::Error) -> Error {
+    if let Some(x) = e.as_db_error() {
+        return Error::from(format!("{}: {}", x.code().code(), x.message()));
+    }
+    match std::error::Error::source(&e) {
+        Some(x) => Error::from(format!("{}: {}", e, x)),
+        None => Error::from(e.to_string()),
+    }
+}
+
+/// rbatis使用?作为占位符,转换为PostgreSQL的$1、$2,忽略引号中的?
+fn exchange(sql: &str) -> String {
+    let mut result = String::with_capacity(sql.len() + 8);
+    let mut quote: Option<char> = None;
+    let mut index = 0;
+    for c in sql.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if c == '?' => {
+                index += 1;
+                result.push_str(&format!("${}", index));
+                continue;
+            }
+            None => {}
+        }
+        result.push(c);
+    }
+    result
+}
+
+fn is_insert(sql: &str) -> bool {
+    let sql = sql.trim_start().to_lowercase();
+    sql.starts_with("insert") && !sql.contains(" returning ")
+}
+
+fn as_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Bool(x) => Some(*x as i64),
+        Value::String(x) => x.trim().parse().ok(),
+        Value::F32(x) => Some(*x as i64),
+        Value::F64(x) => Some(*x as i64),
+        x => x.as_i64(),
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(x) => x.trim().parse().ok(),
+        x => x.as_f64(),
+    }
+}
+
+fn as_string(value: &Value) -> String {
+    match value {
+        Value::String(x) => x.clone(),
+        Value::Bool(x) => x.to_string(),
+        Value::I32(x) => x.to_string(),
+        Value::I64(x) => x.to_string(),
+        Value::U32(x) => x.to_string(),
+        Value::U64(x) => x.to_string(),
+        Value::F32(x) => x.to_string(),
+        Value::F64(x) => x.to_string(),
+        x => x.to_string(),
+    }
+}
+
+/// JSON字段的参数: 字符串按JSON文本解析,Map和Array直接转换
+fn as_json(value: &Value) -> Option<serde_json::Value> {
+    match value {
+        Value::String(x) => serde_json::from_str(x).ok(),
+        x => serde_json::to_value(x).ok(),
+    }
+}
+
+/// 驱动能读写的字段类型,数组和域类型按元素类型判断
+fn supported(ty: &Type) -> bool {
+    match ty.kind() {
+        Kind::Array(x) | Kind::Domain(x) => supported(x),
+        Kind::Enum(_) => true,
+        _ => matches!(
+            *ty,
+            Type::BOOL
+                | Type::INT2
+                | Type::INT4
+                | Type::INT8
+                | Type::OID
+                | Type::FLOAT4
+                | Type::FLOAT8
+                | Type::NUMERIC
+                | Type::TEXT
+                | Type::VARCHAR
+                | Type::BPCHAR
+                | Type::NAME
+                | Type::UNKNOWN
+                | Type::JSON
+                | Type::JSONB
+                | Type::TIMESTAMP
+                | Type::TIMESTAMPTZ
+                | Type::DATE
+                | Type::TIME
+                | Type::BYTEA
+        ),
+    }
+}
+
+/// 日期时间支持rbatis的DateTime(RFC3339)和yyyy-MM-dd HH:mm:ss两种格式
+fn as_datetime(value: &str) -> Option<NaiveDateTime> {
+    if let Ok(x) = DateTime::parse_from_rfc3339(value) {
+        return Some(x.naive_local());
+    }
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"].iter().find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+}
+
+impl ToSql for PgParam {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> ToSqlResult {
+        let value = match &self.0 {
+            Value::Null => return Ok(IsNull::Yes),
+            Value::Ext(_, x) => return PgParam(*x.clone()).to_sql(ty, out),
+            x => x,
+        };
+        let invalid = || format!("参数{}不能转换为{}", value, ty);
+        match ty.kind() {
+            Kind::Domain(x) => return self.to_sql(x, out),
+            //数组的元素按元素类型逐个转换
+            Kind::Array(_) => {
+                return match value {
+                    Value::Array(x) => x.iter().cloned().map(PgParam).collect::<Vec<PgParam>>().to_sql(ty, out),
+                    _ => Err(invalid().into()),
+                }
+            }
+            //枚举的二进制格式就是文本
+            Kind::Enum(_) => return as_string(value).to_sql(&Type::TEXT, out),
+            _ => {}
+        }
+        match *ty {
+            Type::BOOL => match value {
+                Value::Bool(x) => x.to_sql(ty, out),
+                x => (as_i64(x).ok_or_else(invalid)? != 0).to_sql(ty, out),
+            },
+            Type::INT2 => i16::try_from(as_i64(value).ok_or_else(invalid)?)?.to_sql(ty, out),
+            Type::INT4 => i32::try_from(as_i64(value).ok_or_else(invalid)?)?.to_sql(ty, out),
+            Type::INT8 => as_i64(value).ok_or_else(invalid)?.to_sql(ty, out),
+            Type::FLOAT4 => (as_f64(value).ok_or_else(invalid)? as f32).to_sql(ty, out),
+            Type::FLOAT8 => as_f64(value).ok_or_else(invalid)?.to_sql(ty, out),
+            Type::NUMERIC => Decimal::from_str(as_string(value).trim()).map_err(|_| invalid())?.to_sql(ty, out),
+            Type::JSON | Type::JSONB => as_json(value).ok_or_else(invalid)?.to_sql(ty, out),
+            Type::TIMESTAMP => as_datetime(&as_string(value)).ok_or_else(invalid)?.to_sql(ty, out),
+            Type::TIMESTAMPTZ => as_datetime(&as_string(value)).ok_or_else(invalid)?.and_utc().to_sql(ty, out),
+            Type::DATE => NaiveDate::parse_from_str(&as_string(value), "%Y-%m-%d").map_err(|_| invalid())?.to_sql(ty, out),
+            Type::TIME => NaiveTime::parse_from_str(&as_string(value), "%H:%M:%S%.f").map_err(|_| invalid())?.to_sql(ty, out),
+            Type::BYTEA => match value {
+                Value::Binary(x) => x.to_sql(ty, out),
+                x => as_string(x).into_bytes().to_sql(ty, out),
+            },
+            Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME | Type::UNKNOWN => as_string(value).to_sql(ty, out),
+            _ => Err(format!("不支持的参数类型: {}", ty).into()),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        supported(ty)
+    }
+
+    to_sql_checked!();
+}
+
+/// 时间使用yyyy-MM-dd HH:mm:ss格式的字符串,和MySQL驱动一样可以反序列化为DateTime,NUMERIC和JSON也和MySQL驱动的返回值一致
+impl<'a> FromSql<'a> for PgValue {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> FromSqlResult<PgValue> {
+        match ty.kind() {
+            Kind::Domain(x) => return PgValue::from_sql(x, raw),
+            Kind::Array(_) => {
+                let values = Vec::<Option<PgValue>>::from_sql(ty, raw)?;
+                return Ok(PgValue(Value::Array(values.into_iter().map(|x| x.map(|x| x.0).unwrap_or(Value::Null)).collect())));
+            }
+            Kind::Enum(_) => return Ok(PgValue(Value::String(String::from_sql(&Type::TEXT, raw)?))),
+            _ => {}
+        }
+        let value = match *ty {
+            Type::BOOL => Value::Bool(bool::from_sql(ty, raw)?),
+            Type::INT2 => Value::I32(i16::from_sql(ty, raw)? as i32),
+            Type::INT4 => Value::I32(i32::from_sql(ty, raw)?),
+            Type::INT8 => Value::I64(i64::from_sql(ty, raw)?),
+            Type::OID => Value::U32(u32::from_sql(ty, raw)?),
+            Type::FLOAT4 => Value::F32(f32::from_sql(ty, raw)?),
+            Type::FLOAT8 => Value::F64(f64::from_sql(ty, raw)?),
+            Type::NUMERIC => Value::Ext("Decimal", Box::new(Value::String(Decimal::from_sql(ty, raw)?.to_string()))),
+            Type::JSON | Type::JSONB => serde_json::from_value(serde_json::Value::from_sql(ty, raw)?)?,
+            Type::TIMESTAMP => Value::String(NaiveDateTime::from_sql(ty, raw)?.format("%Y-%m-%d %H:%M:%S").to_string()),
+            Type::TIMESTAMPTZ => Value::String(DateTime::<Utc>::from_sql(ty, raw)?.to_rfc3339()),
+            Type::DATE => Value::String(NaiveDate::from_sql(ty, raw)?.to_string()),
+            Type::TIME => Value::String(NaiveTime::from_sql(ty, raw)?.format("%H:%M:%S").to_string()),
+            Type::BYTEA => Value::Binary(Vec::<u8>::from_sql(ty, raw)?),
+            Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME | Type::UNKNOWN => Value::String(String::from_sql(ty, raw)?),
+            _ => return Err(format!("不支持的字段类型: {}", ty).into()),
+        };
+        Ok(PgValue(value))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        supported(ty)
+    }
+}
+
+/// 读取一个字段,不支持的类型返回错误
+fn from_pg(row: &tokio_postgres::Row, i: usize) -> Result<Value, tokio_postgres::Error> {
+    Ok(row.try_get::<_, Option<PgValue>>(i)?.map(|x| x.0).unwrap_or(Value::Null))
+}
+
+/// 连接的后台任务,负责收发数据
+fn spawn_connection(connection: impl Future<Output = Result<(), tokio_postgres::Error>> + Send + 'static) {
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            log::error!("postgres connection error: {}", e);
+        }
+    });
+}
+
+impl PgConnectOptions {
+    async fn open(&self) -> Result<PgConnection, Error> {
+        let client = match &self.tls {
+            Some(tls) => {
+                let (client, connection) = self.config.connect(MakeRustlsConnect::new(tls.as_ref().clone())).await.map_err(to_error)?;
+                spawn_connection(connection);
+                client
+            }
+            None => {
+                let (client, connection) = self.config.connect(NoTls).await.map_err(to_error)?;
+                spawn_connection(connection);
+                client
+            }
+        };
+        Ok(PgConnection { client })
+    }
+}
+
+impl ConnectOptions for PgConnectOptions {
+    fn connect(&self) -> BoxFuture<'_, Result<Box<dyn Connection>, Error>> {
+        Box::pin(async move { Ok(Box::new(self.open().await?) as Box<dyn Connection>) })
+    }
+
+    fn set_uri(&mut self, uri: &str) -> Result<(), Error> {
+        if !uri.starts_with("postgres://") && !uri.starts_with("postgresql://") {
+            return Err(Error::from(format!("invalid postgres url: {}", uri)));
+        }
+        let (url, options) = split_url(uri)?;
+        let mut config = Config::from_str(&url).map_err(to_error)?;
+        config.ssl_mode(match options.mode {
+            PgSslMode::Disable => SslMode::Disable,
+            PgSslMode::Prefer => SslMode::Prefer,
+            _ => SslMode::Require,
+        });
+        self.tls = client_config(&options)?;
+        self.config = config;
+        Ok(())
+    }
+}
+
+impl Driver for PgDriver {
+    fn name(&self) -> &str {
+        "postgres"
+    }
+
+    fn connect(&self, url: &str) -> BoxFuture<'_, Result<Box<dyn Connection>, Error>> {
+        let url = url.to_string();
+        Box::pin(async move {
+            let mut opt = PgConnectOptions::default();
+            opt.set_uri(&url)?;
+            Ok(Box::new(opt.open().await?) as Box<dyn Connection>)
+        })
+    }
+
+    fn connect_opt<'a>(&'a self, opt: &'a dyn ConnectOptions) -> BoxFuture<'a, Result<Box<dyn Connection>, Error>> {
+        Box::pin(async move {
+            let opt = opt.downcast_ref::<PgConnectOptions>().ok_or_else(|| Error::from("downcast_ref failure"))?;
+            Ok(Box::new(opt.open().await?) as Box<dyn Connection>)
+        })
+    }
+
+    fn default_option(&self) -> Box<dyn ConnectOptions> {
+        Box::new(PgConnectOptions::default())
+    }
+}
+
+impl PgConnection {
+    async fn prepare(&self, sql: &str, params: Vec<Value>) -> Result<(Statement, Vec<PgParam>), Error> {
+        let stmt = self.client.prepare(&exchange(sql)).await.map_err(to_error)?;
+        if stmt.params().len() != params.len() {
+            return Err(Error::from(format!("参数个数不匹配: 需要{}个,实际{}个", stmt.params().len(), params.len())));
+        }
+        Ok((stmt, params.into_iter().map(PgParam).collect()))
+    }
+
+    async fn query(&self, sql: &str, params: Vec<Value>) -> Result<Vec<tokio_postgres::Row>, Error> {
+        let (stmt, params) = self.prepare(sql, params).await?;
+        let params: Vec<&(dyn ToSql + Sync)> = params.iter().map(|x| x as &(dyn ToSql + Sync)).collect();
+        self.client.query(&stmt, &params).await.map_err(to_error)
+    }
+
+    async fn get_rows(&self, sql: &str, params: Vec<Value>) -> Result<Vec<Box<dyn Row>>, Error> {
+        let rows = self.query(sql, params).await?;
+        let columns = match rows.first() {
+            Some(row) => Arc::new(row.columns().iter().map(|x| (x.name().to_string(), x.type_().name().to_string())).collect::<Vec<_>>()),
+            None => return Ok(Vec::new()),
+        };
+        let mut result: Vec<Box<dyn Row>> = Vec::with_capacity(rows.len());
+        for row in rows {
+            let values = (0..columns.len()).map(|i| from_pg(&row, i)).collect::<Result<Vec<Value>, _>>().map_err(to_error)?;
+            result.push(Box::new(PgRow { columns: columns.clone(), values }));
+        }
+        Ok(result)
+    }
+
+    /// insert语句没有自增id的返回值,追加returning *后读取id字段,批量插入时和MySQL一样返回第一个id
+    async fn execute(&self, sql: &str, params: Vec<Value>) -> Result<ExecResult, Error> {
+        if !is_insert(sql) {
+            let (stmt, params) = self.prepare(sql, params).await?;
+            let params: Vec<&(dyn ToSql + Sync)> = params.iter().map(|x| x as &(dyn ToSql + Sync)).collect();
+            let rows_affected = self.client.execute(&stmt, &params).await.map_err(to_error)?;
+            return Ok(ExecResult {
+                rows_affected,
+                last_insert_id: Value::Null,
+            });
+        }
+
+        let rows = self.query(&format!("{} returning *", sql.trim_end().trim_end_matches(';')), params).await?;
+        let last_insert_id = match rows.first() {
+            Some(row) => match row.columns().iter().position(|x| x.name() == "id") {
+                Some(i) => from_pg(row, i).map_err(to_error)?,
+                None => Value::Null,
+            },
+            None => Value::Null,
+        };
+        Ok(ExecResult {
+            rows_affected: rows.len() as u64,
+            last_insert_id,
+        })
+    }
+}
+
+impl Connection for PgConnection {
+    fn get_rows(&mut self, sql: &str, params: Vec<Value>) -> BoxFuture<'_, Result<Vec<Box<dyn Row>>, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move { PgConnection::get_rows(self, &sql, params).await })
+    }
+
+    fn exec(&mut self, sql: &str, params: Vec<Value>) -> BoxFuture<'_, Result<ExecResult, Error>> {
+        let sql = sql.to_string();
+        Box::pin(async move { self.execute(&sql, params).await })
+    }
+
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move { self.client.simple_query("select 1").await.map(|_| ()).map_err(to_error) })
+    }
+
+    fn close(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl Row for PgRow {
+    fn meta_data(&self) -> Box<dyn MetaData> {
+        Box::new(PgMetaData { columns: self.columns.clone() })
+    }
+
+    fn get(&mut self, i: usize) -> Result<Value, Error> {
+        self.values.get_mut(i).map(std::mem::take).ok_or_else(|| Error::from(format!("column index out of range: {}", i)))
+    }
+}
+
+impl MetaData for PgMetaData {
+    fn column_len(&self) -> usize {
+        self.columns.len()
+    }
+
+    fn column_name(&self, i: usize) -> String {
+        self.columns[i].0.clone()
+    }
+
+    fn column_type(&self, i: usize) -> String {
+        self.columns[i].1.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::driver::postgres_driver::{as_datetime, exchange, is_insert};
+
+    #[test]
+    fn test_exchange() {
+        assert_eq!(
+            exchange("select * from sys_user where id = ? and remark = '?' and status = ?"),
+            "select * from sys_user where id = $1 and remark = '?' and status = $2"
+        );
+        assert!(is_insert("  INSERT into sys_user (id) values (?)"));
+        assert!(!is_insert("insert into sys_user (id) values (?) returning id"));
+        assert!(!is_insert("update sys_user set status = ?"));
+        assert_eq!(as_datetime("2026-10-19T08:05:17+08:00").unwrap().to_string(), "2026-10-19 08:05:17");
+        assert_eq!(as_datetime("2026-10-19 08:05:17").unwrap().to_string(), "2026-10-19 08:05:17");
+    }
+}
@@ -0,0 +1,218 @@
+use rbatis::rbdc::db::{ConnectOptions, Connection, Driver, ExecResult, MetaData, Row};
+use rbatis::rbdc::Error;
+use rbs::Value;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::{Value as SqliteValue, ValueRef};
+use rusqlite::{params_from_iter, OpenFlags};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/*
+ *基于rusqlite的rbatis驱动,用于单机部署和集成测试
+ *连接地址格式: sqlite://文件路径,文件不存在时自动创建
+ *rusqlite是同步接口,打开连接和执行语句都放到spawn_blocking的线程中,不阻塞异步运行时
+ */
+#[derive(Debug)]
+pub struct SqliteDriver {}
+
+#[derive(Debug, Default)]
+pub struct SqliteConnectOptions {
+    path: String, //数据库文件路径
+}
+
+pub struct SqliteConnection {
+    conn: Arc<Mutex<rusqlite::Connection>>,
+}
+
+#[derive(Debug)]
+pub struct SqliteRow {
+    columns: Arc<Vec<String>>,
+    values: Vec<Value>,
+}
+
+#[derive(Debug)]
+pub struct SqliteMetaData {
+    columns: Arc<Vec<String>>,
+}
+
+fn to_error(e: rusqlite::Error) -> Error {
+    Error::from(e.to_string())
+}
+
+/// 在阻塞线程池中执行同步的数据库操作
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T, Error> + Send + 'static) -> Result<T, Error> {
+    tokio::task::spawn_blocking(f).await.map_err(|e| Error::from(e.to_string()))?
+}
+
+/// rbatis的参数转换为sqlite的值,日期等扩展类型使用内部的字符串
+fn to_sqlite(value: Value) -> SqliteValue {
+    match value {
+        Value::Null => SqliteValue::Null,
+        Value::Bool(x) => SqliteValue::Integer(x as i64),
+        Value::I32(x) => SqliteValue::Integer(x as i64),
+        Value::I64(x) => SqliteValue::Integer(x),
+        Value::U32(x) => SqliteValue::Integer(x as i64),
+        Value::U64(x) => SqliteValue::Integer(x as i64),
+        Value::F32(x) => SqliteValue::Real(x as f64),
+        Value::F64(x) => SqliteValue::Real(x),
+        Value::String(x) => SqliteValue::Text(x),
+        Value::Binary(x) => SqliteValue::Blob(x),
+        Value::Ext(_, x) => to_sqlite(*x),
+        x => SqliteValue::Text(x.to_string()),
+    }
+}
+
+fn from_sqlite(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(x) => Value::I64(x),
+        ValueRef::Real(x) => Value::F64(x),
+        ValueRef::Text(x) => Value::String(String::from_utf8_lossy(x).to_string()),
+        ValueRef::Blob(x) => Value::Binary(x.to_vec()),
+    }
+}
+
+/// 和MySQL的find_in_set一致: 返回value在逗号分隔的list中的位置,从1开始,找不到返回0
+fn find_in_set(value: &str, list: &str) -> i64 {
+    list.split(',').position(|x| x == value).map(|x| x as i64 + 1).unwrap_or_default()
+}
+
+/// MySQL会把find_in_set的数字参数隐式转换为字符串
+fn text_arg(value: ValueRef) -> String {
+    match value {
+        ValueRef::Integer(x) => x.to_string(),
+        ValueRef::Real(x) => x.to_string(),
+        ValueRef::Text(x) => String::from_utf8_lossy(x).to_string(),
+        _ => String::new(),
+    }
+}
+
+impl SqliteConnectOptions {
+    async fn open(&self) -> Result<SqliteConnection, Error> {
+        let path = self.path.clone();
+        let conn = blocking(move || SqliteConnectOptions::open_blocking(&path)).await?;
+        Ok(SqliteConnection { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    fn open_blocking(path: &str) -> Result<rusqlite::Connection, Error> {
+        let conn = rusqlite::Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE).map_err(to_error)?;
+        conn.busy_timeout(Duration::from_secs(5)).map_err(to_error)?;
+        conn.create_scalar_function("find_in_set", 2, FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC, |ctx| {
+            let value = text_arg(ctx.get_raw(0));
+            let list = text_arg(ctx.get_raw(1));
+            Ok(find_in_set(&value, &list))
+        })
+        .map_err(to_error)?;
+        Ok(conn)
+    }
+}
+
+impl ConnectOptions for SqliteConnectOptions {
+    fn connect(&self) -> BoxFuture<'_, Result<Box<dyn Connection>, Error>> {
+        Box::pin(async move { Ok(Box::new(self.open().await?) as Box<dyn Connection>) })
+    }
+
+    fn set_uri(&mut self, uri: &str) -> Result<(), Error> {
+        self.path = uri.strip_prefix("sqlite://").ok_or_else(|| Error::from(format!("invalid sqlite url: {}", uri)))?.to_string();
+        Ok(())
+    }
+}
+
+impl Driver for SqliteDriver {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    fn connect(&self, url: &str) -> BoxFuture<'_, Result<Box<dyn Connection>, Error>> {
+        let url = url.to_string();
+        Box::pin(async move {
+            let mut opt = SqliteConnectOptions::default();
+            opt.set_uri(&url)?;
+            Ok(Box::new(opt.open().await?) as Box<dyn Connection>)
+        })
+    }
+
+    fn connect_opt<'a>(&'a self, opt: &'a dyn ConnectOptions) -> BoxFuture<'a, Result<Box<dyn Connection>, Error>> {
+        Box::pin(async move {
+            let opt = opt.downcast_ref::<SqliteConnectOptions>().ok_or_else(|| Error::from("downcast_ref failure"))?;
+            Ok(Box::new(opt.open().await?) as Box<dyn Connection>)
+        })
+    }
+
+    fn default_option(&self) -> Box<dyn ConnectOptions> {
+        Box::new(SqliteConnectOptions::default())
+    }
+}
+
+impl SqliteConnection {
+    fn query(conn: &Mutex<rusqlite::Connection>, sql: &str, params: Vec<Value>) -> Result<Vec<Box<dyn Row>>, Error> {
+        let conn = conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn.prepare(sql).map_err(to_error)?;
+        let columns = Arc::new(stmt.column_names().into_iter().map(str::to_string).collect::<Vec<String>>());
+        let mut rows = stmt.query(params_from_iter(params.into_iter().map(to_sqlite))).map_err(to_error)?;
+        let mut result: Vec<Box<dyn Row>> = Vec::new();
+        while let Some(row) = rows.next().map_err(to_error)? {
+            let values = (0..columns.len()).map(|i| row.get_ref(i).map(from_sqlite)).collect::<Result<Vec<Value>, _>>().map_err(to_error)?;
+            result.push(Box::new(SqliteRow { columns: columns.clone(), values }));
+        }
+        Ok(result)
+    }
+
+    fn execute(conn: &Mutex<rusqlite::Connection>, sql: &str, params: Vec<Value>) -> Result<ExecResult, Error> {
+        let conn = conn.lock().unwrap_or_else(|e| e.into_inner());
+        let rows_affected = conn.execute(sql, params_from_iter(params.into_iter().map(to_sqlite))).map_err(to_error)?;
+        Ok(ExecResult {
+            rows_affected: rows_affected as u64,
+            last_insert_id: Value::I64(conn.last_insert_rowid()),
+        })
+    }
+}
+
+impl Connection for SqliteConnection {
+    fn get_rows(&mut self, sql: &str, params: Vec<Value>) -> BoxFuture<'_, Result<Vec<Box<dyn Row>>, Error>> {
+        let (conn, sql) = (self.conn.clone(), sql.to_string());
+        Box::pin(blocking(move || SqliteConnection::query(&conn, &sql, params)))
+    }
+
+    fn exec(&mut self, sql: &str, params: Vec<Value>) -> BoxFuture<'_, Result<ExecResult, Error>> {
+        let (conn, sql) = (self.conn.clone(), sql.to_string());
+        Box::pin(blocking(move || SqliteConnection::execute(&conn, &sql, params)))
+    }
+
+    fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        let conn = self.conn.clone();
+        Box::pin(blocking(move || SqliteConnection::query(&conn, "select 1", Vec::new()).map(|_| ())))
+    }
+
+    fn close(&mut self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl Row for SqliteRow {
+    fn meta_data(&self) -> Box<dyn MetaData> {
+        Box::new(SqliteMetaData { columns: self.columns.clone() })
+    }
+
+    fn get(&mut self, i: usize) -> Result<Value, Error> {
+        self.values.get_mut(i).map(std::mem::take).ok_or_else(|| Error::from(format!("column index out of range: {}", i)))
+    }
+}
+
+impl MetaData for SqliteMetaData {
+    fn column_len(&self) -> usize {
+        self.columns.len()
+    }
+
+    fn column_name(&self, i: usize) -> String {
+        self.columns[i].clone()
+    }
+
+    fn column_type(&self, _i: usize) -> String {
+        String::new()
+    }
+}
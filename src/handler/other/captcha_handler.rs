@@ -0,0 +1,33 @@
+use crate::common::app_state::app_state;
+use crate::common::error::{AppError, AppResult};
+use crate::common::result::ok_result_data;
+use crate::utils::captcha_util::{generate_code, new_captcha_id, render_png, save_captcha};
+use crate::vo::other::captcha_vo::CaptchaResp;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use salvo::prelude::*;
+
+/*
+ *获取图片验证码
+ */
+#[handler]
+pub async fn query_captcha(depot: &mut Depot, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let config = &state.config.captcha;
+
+    let captcha_id = new_captcha_id();
+    let code = generate_code(config.length);
+    let png = render_png(&code, config.width, config.height)?;
+
+    let mut conn = state.redis.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    save_captcha(&mut conn, &captcha_id, &code, config.expire).await?;
+
+    ok_result_data(
+        res,
+        CaptchaResp {
+            captcha_id,
+            image: format!("data:image/png;base64,{}", STANDARD.encode(png)),
+            expire: config.expire,
+        },
+    )
+}
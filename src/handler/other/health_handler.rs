@@ -0,0 +1,57 @@
+use crate::common::app_state::app_state;
+use crate::utils::health_util::{STATUS_DOWN, STATUS_UP};
+use crate::vo::other::health_vo::{ComponentHealth, HealthResp};
+use salvo::http::StatusCode;
+use salvo::prelude::*;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::Instant;
+
+/*
+ *存活检查: 进程能够处理请求即返回200,不检查依赖
+ */
+#[handler]
+pub async fn health_live(res: &mut Response) {
+    res.render(Json(HealthResp {
+        status: STATUS_UP.to_string(),
+        checks: BTreeMap::new(),
+    }));
+}
+
+/*
+ *就绪检查: 数据库和redis都可用时返回200,否则返回503
+ */
+#[handler]
+pub async fn health_ready(depot: &mut Depot, res: &mut Response) {
+    let mut checks = BTreeMap::new();
+    match app_state(depot) {
+        Ok(state) => {
+            let config = &state.config.health;
+            checks.insert("db", check(config.check_db(&state.rb)).await);
+            checks.insert("redis", check(config.check_redis(&state.redis)).await);
+        }
+        Err(e) => {
+            checks.insert("state", check(async { Err(e.to_string()) }).await);
+        }
+    }
+
+    let ready = checks.values().all(|x| x.status == STATUS_UP);
+    if !ready {
+        log::warn!("health check failed: {:?}", checks);
+        res.status_code(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    res.render(Json(HealthResp {
+        status: if ready { STATUS_UP } else { STATUS_DOWN }.to_string(),
+        checks,
+    }));
+}
+
+async fn check<F: Future<Output = Result<(), String>>>(future: F) -> ComponentHealth {
+    let start = Instant::now();
+    let result = future.await;
+    ComponentHealth {
+        status: if result.is_ok() { STATUS_UP } else { STATUS_DOWN }.to_string(),
+        duration_ms: start.elapsed().as_millis() as u64,
+        error: result.err(),
+    }
+}
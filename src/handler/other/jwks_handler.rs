@@ -0,0 +1,13 @@
+use crate::common::app_state::app_state;
+use crate::common::error::AppResult;
+use salvo::prelude::*;
+
+/*
+ *查询jwt公钥(JWKS),供其它服务验证本服务签发的token
+ *HS256算法时返回空的keys
+ */
+#[handler]
+pub async fn query_jwks(depot: &mut Depot, res: &mut Response) -> AppResult<()> {
+    res.render(Json(app_state(depot)?.jwt_keys.jwks()));
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::common::app_state::app_state;
+use crate::utils::ip_util::{client_ip, Cidr};
+use crate::utils::metrics_util::{MetricsConfig, METRICS};
+use crate::utils::password_util::constant_time_eq;
+use salvo::http::header::AUTHORIZATION;
+use salvo::http::StatusCode;
+use salvo::prelude::*;
+use std::net::IpAddr;
+
+/*
+ *Prometheus监控指标,不在/api下,不经过登录和菜单权限校验
+ *只允许metrics.allow_ips中的地址访问,其它地址需要携带metrics.token
+ */
+#[handler]
+pub async fn query_metrics(req: &mut Request, depot: &mut Depot, res: &mut Response) {
+    let state = match app_state(depot) {
+        Ok(state) => state,
+        Err(_) => {
+            res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
+            return;
+        }
+    };
+    let config = &state.config.metrics;
+    if !config.enabled {
+        res.status_code(StatusCode::NOT_FOUND);
+        return;
+    }
+
+    let ipaddr = client_ip(depot, req).ipaddr;
+    let authorization = req.header::<String>(AUTHORIZATION).unwrap_or_default();
+    if !is_allowed(config, &ipaddr, &authorization) {
+        log::warn!("reject metrics request from {}", ipaddr);
+        res.status_code(StatusCode::FORBIDDEN);
+        return;
+    }
+
+    METRICS.collect(&state.rb, &state.redis, &state.operate_log_writer).await;
+    res.render(METRICS.render());
+}
+
+fn is_allowed(config: &MetricsConfig, ipaddr: &str, authorization: &str) -> bool {
+    if let Ok(ip) = ipaddr.parse::<IpAddr>() {
+        if config.allow_ips.iter().filter_map(|x| Cidr::parse(x)).any(|x| x.contains(&ip)) {
+            return true;
+        }
+    }
+    match authorization.strip_prefix("Bearer ") {
+        Some(token) if !config.token.is_empty() => constant_time_eq(token.trim().as_bytes(), config.token.as_bytes()),
+        _ => false,
+    }
+}
@@ -1 +1,4 @@
-
+pub mod captcha_handler;
+pub mod health_handler;
+pub mod jwks_handler;
+pub mod metrics_handler;
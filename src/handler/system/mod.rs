@@ -8,3 +8,5 @@ pub mod sys_operate_log_handler;
 pub mod sys_post_handler;
 pub mod sys_role_handler;
 pub mod sys_user_handler;
+pub mod sys_user_mfa_handler;
+pub mod sys_user_online_handler;
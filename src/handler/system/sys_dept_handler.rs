@@ -2,11 +2,12 @@
 // author：刘飞华
 // date：2025/01/08 13:51:14
 
+use crate::common::app_state::app_state;
 use crate::common::error::{AppError, AppResult};
-use crate::common::result::{ok_result, ok_result_data};
+use crate::common::result::{BaseResponse, ok_result, ok_result_data};
 use crate::model::system::sys_dept_model::{check_dept_exist_user, select_children_dept_by_id, select_dept_count, select_normal_children_dept_by_id, Dept};
+use crate::utils::data_scope_util::current_data_scope;
 use crate::vo::system::sys_dept_vo::*;
-use crate::RB;
 use rbatis::rbatis_codegen::ops::AsProxy;
 use rbatis::rbdc::DateTime;
 use rbs::value;
@@ -17,12 +18,16 @@ use salvo::{Request, Response};
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn add_sys_dept(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "添加部门表", request_body = DeptReq)]
+pub async fn add_sys_dept(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DeptReq>().await?;
     log::info!("add sys_dept params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    //只能在有数据权限的部门下添加部门
+    current_data_scope(depot).await?.check_dept(item.parent_id)?;
+
+    let rb = &state.rb;
 
     if Dept::select_by_dept_name(rb, &item.dept_name, item.parent_id).await?.is_some() {
         return Err(AppError::BusinessError("部门名称已存在"));
@@ -48,12 +53,15 @@ pub async fn add_sys_dept(req: &mut Request, res: &mut Response) -> AppResult<()
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn delete_sys_dept(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "删除部门表", request_body = DeleteDeptReq)]
+pub async fn delete_sys_dept(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DeleteDeptReq>().await?;
     log::info!("delete sys_dept params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    current_data_scope(depot).await?.check_dept(item.id)?;
+
+    let rb = &state.rb;
     if select_dept_count(rb, &item.id).await? > 0 {
         return Err(AppError::BusinessError("存在下级部门,不允许删除"));
     }
@@ -70,9 +78,10 @@ pub async fn delete_sys_dept(req: &mut Request, res: &mut Response) -> AppResult
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_dept(req: &mut Request, res: &mut Response) -> AppResult<()> {
-    let rb = &mut RB.clone();
+#[endpoint(summary = "更新部门表", request_body = DeptReq)]
+pub async fn update_sys_dept(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let rb = &state.rb;
     let mut item = req.parse_json::<DeptReq>().await?;
     log::info!("update sys_dept params: {:?}", &item);
 
@@ -81,6 +90,10 @@ pub async fn update_sys_dept(req: &mut Request, res: &mut Response) -> AppResult
         return Err(AppError::BusinessError("上级部门不能是自己"));
     }
 
+    let scope = current_data_scope(depot).await?;
+    scope.check_dept(id.unwrap_or_default())?;
+    scope.check_dept(item.parent_id)?;
+
     let old_ancestors = match Dept::select_by_id(rb, &id.unwrap_or_default()).await? {
         None => return Err(AppError::BusinessError("部门不存在")),
         Some(dept) => dept.ancestors.unwrap_or_default(),
@@ -130,12 +143,18 @@ pub async fn update_sys_dept(req: &mut Request, res: &mut Response) -> AppResult
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_dept_status(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新部门表状态", request_body = UpdateDeptStatusReq)]
+pub async fn update_sys_dept_status(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UpdateDeptStatusReq>().await?;
     log::info!("update sys_dept_status params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let scope = current_data_scope(depot).await?;
+    for id in &item.ids {
+        scope.check_dept(*id)?;
+    }
+
+    let rb = &state.rb;
     if item.status == 1 {
         for id in item.ids.clone() {
             if let Some(x) = Dept::select_by_id(rb, &id).await? {
@@ -164,12 +183,15 @@ pub async fn update_sys_dept_status(req: &mut Request, res: &mut Response) -> Ap
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_dept_detail(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询部门表详情", request_body = QueryDeptDetailReq, responses((status_code = 200, body = BaseResponse<DeptResp>)))]
+pub async fn query_sys_dept_detail(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryDeptDetailReq>().await?;
     log::info!("query sys_dept_detail params: {:?}", &item);
 
-    Dept::select_by_id(&mut RB.clone(), &item.id).await?.map_or_else(
+    current_data_scope(depot).await?.check_dept(item.id)?;
+
+    Dept::select_by_id(&state.rb, &item.id).await?.map_or_else(
         || Err(AppError::BusinessError("部门不存在")),
         |x| {
             let data: DeptResp = x.into();
@@ -183,14 +205,16 @@ pub async fn query_sys_dept_detail(req: &mut Request, res: &mut Response) -> App
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_dept_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询部门表列表", request_body = QueryDeptListReq, responses((status_code = 200, body = BaseResponse<Vec<DeptResp>>)))]
+pub async fn query_sys_dept_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryDeptListReq>().await?;
     log::info!("query sys_dept_list params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
+    let scope = current_data_scope(depot).await?;
 
-    Dept::select_page_dept_list(rb, &item)
+    Dept::select_page_dept_list(rb, &item, &scope)
         .await
         .map(|x| ok_result_data(res, x.into_iter().map(|x| x.into()).collect::<Vec<DeptResp>>()))?
 }
@@ -2,11 +2,11 @@
 // author：刘飞华
 // date：2025/01/08 13:51:14
 
+use crate::common::app_state::app_state;
 use crate::common::error::{AppError, AppResult};
-use crate::common::result::{ok_result, ok_result_data, ok_result_page};
+use crate::common::result::{BaseResponse, ResponsePage, ok_result, ok_result_data, ok_result_page};
 use crate::model::system::sys_dict_data_model::DictData;
 use crate::vo::system::sys_dict_data_vo::*;
-use crate::RB;
 use rbatis::plugin::page::PageRequest;
 use rbatis::rbdc::DateTime;
 use rbs::value;
@@ -18,13 +18,14 @@ use salvo::{Request, Response};
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn add_sys_dict_data(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "添加字典数据表", request_body = DictDataReq)]
+pub async fn add_sys_dict_data(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DictDataReq>().await?;
 
     log::info!("add sys_dict_data params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     if DictData::select_by_dict_label(rb, &item.dict_type, &item.dict_label).await?.is_some() {
         return Err(AppError::BusinessError("字典标签已存在"));
     }
@@ -41,12 +42,13 @@ pub async fn add_sys_dict_data(req: &mut Request, res: &mut Response) -> AppResu
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn delete_sys_dict_data(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "删除字典数据表", request_body = DeleteDictDataReq)]
+pub async fn delete_sys_dict_data(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DeleteDictDataReq>().await?;
     log::info!("delete sys_dict_data params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     DictData::delete_by_map(rb, value! {"id": &item.ids}).await.map(|_| ok_result(res))?
 }
@@ -56,12 +58,13 @@ pub async fn delete_sys_dict_data(req: &mut Request, res: &mut Response) -> AppR
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_dict_data(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新字典数据表", request_body = DictDataReq)]
+pub async fn update_sys_dict_data(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DictDataReq>().await?;
     log::info!("update sys_dict_data params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     let id = item.id;
 
@@ -91,8 +94,9 @@ pub async fn update_sys_dict_data(req: &mut Request, res: &mut Response) -> AppR
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_dict_data_status(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新字典数据表状态", request_body = UpdateDictDataStatusReq)]
+pub async fn update_sys_dict_data_status(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UpdateDictDataStatusReq>().await?;
     log::info!("update sys_dict_data_status params: {:?}", &item);
 
@@ -101,7 +105,7 @@ pub async fn update_sys_dict_data_status(req: &mut Request, res: &mut Response)
     let mut param = vec![value!(item.status),value!(DateTime::now())];
     param.extend(item.ids.iter().map(|&id| value!(id)));
 
-    RB.clone().exec(&update_sql, param).await.map(|_| ok_result(res))?
+    state.rb.exec(&update_sql, param).await.map(|_| ok_result(res))?
 }
 
 /*
@@ -109,12 +113,13 @@ pub async fn update_sys_dict_data_status(req: &mut Request, res: &mut Response)
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_dict_data_detail(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询字典数据表详情", request_body = QueryDictDataDetailReq, responses((status_code = 200, body = BaseResponse<DictDataResp>)))]
+pub async fn query_sys_dict_data_detail(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryDictDataDetailReq>().await?;
     log::info!("query sys_dict_data_detail params: {:?}", &item);
 
-    DictData::select_by_id(&mut RB.clone(), &item.id).await?.map_or_else(
+    DictData::select_by_id(&state.rb, &item.id).await?.map_or_else(
         || Err(AppError::BusinessError("字典数据不存在")),
         |x| {
             let data: DictDataResp = x.into();
@@ -128,13 +133,14 @@ pub async fn query_sys_dict_data_detail(req: &mut Request, res: &mut Response) -
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_dict_data_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询字典数据表列表", request_body = QueryDictDataListReq, responses((status_code = 200, body = ResponsePage<Vec<DictDataResp>>)))]
+pub async fn query_sys_dict_data_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryDictDataListReq>().await?;
     log::info!("query sys_dict_data_list params: {:?}", &item);
 
     let page = &PageRequest::new(item.page_no, item.page_size);
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     DictData::select_dict_data_list(rb, page, &item)
         .await
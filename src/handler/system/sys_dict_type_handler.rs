@@ -2,12 +2,12 @@
 // author：刘飞华
 // date：2025/01/08 13:51:14
 
+use crate::common::app_state::app_state;
 use crate::common::error::{AppError, AppResult};
-use crate::common::result::{ok_result, ok_result_data, ok_result_page};
+use crate::common::result::{BaseResponse, ResponsePage, ok_result, ok_result_data, ok_result_page};
 use crate::model::system::sys_dict_data_model::{count_dict_data_by_type, update_dict_data_type};
 use crate::model::system::sys_dict_type_model::DictType;
 use crate::vo::system::sys_dict_type_vo::*;
-use crate::RB;
 use rbatis::plugin::page::PageRequest;
 use rbatis::rbdc::DateTime;
 use rbs::value;
@@ -19,12 +19,13 @@ use salvo::{Request, Response};
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn add_sys_dict_type(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "添加字典类型表", request_body = DictTypeReq)]
+pub async fn add_sys_dict_type(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DictTypeReq>().await?;
     log::info!("add sys_dict_type params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     if DictType::select_by_dict_type(rb, &item.dict_type).await?.is_some() {
         return Err(AppError::BusinessError("字典类型已存在"));
     }
@@ -37,12 +38,13 @@ pub async fn add_sys_dict_type(req: &mut Request, res: &mut Response) -> AppResu
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn delete_sys_dict_type(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "删除字典类型表", request_body = DeleteDictTypeReq)]
+pub async fn delete_sys_dict_type(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DeleteDictTypeReq>().await?;
     log::info!("delete sys_dict_type params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     let ids = item.ids.clone();
     for id in ids {
         let p = match DictType::select_by_id(rb, &id).await? {
@@ -64,12 +66,13 @@ pub async fn delete_sys_dict_type(req: &mut Request, res: &mut Response) -> AppR
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_dict_type(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新字典类型表", request_body = DictTypeReq)]
+pub async fn update_sys_dict_type(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DictTypeReq>().await?;
     log::info!("update sys_dict_type params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     let id = item.id;
     if DictType::select_by_id(rb, &id.unwrap_or_default()).await?.is_none() {
         return Err(AppError::BusinessError("字典类型不存在"));
@@ -94,11 +97,12 @@ pub async fn update_sys_dict_type(req: &mut Request, res: &mut Response) -> AppR
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_dict_type_status(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新字典类型表状态", request_body = UpdateDictTypeStatusReq)]
+pub async fn update_sys_dict_type_status(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UpdateDictTypeStatusReq>().await?;
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     log::info!("update sys_dict_type_status params: {:?}", &item);
 
     let update_sql = format!("update sys_dict_type set status = ? ,update_time = ? where id in ({})", item.ids.iter().map(|_| "?").collect::<Vec<&str>>().join(", "));
@@ -114,13 +118,14 @@ pub async fn update_sys_dict_type_status(req: &mut Request, res: &mut Response)
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_dict_type_detail(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询字典类型表详情", request_body = QueryDictTypeDetailReq, responses((status_code = 200, body = BaseResponse<DictTypeResp>)))]
+pub async fn query_sys_dict_type_detail(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryDictTypeDetailReq>().await?;
 
     log::info!("query sys_dict_type_detail params: {:?}", &item);
 
-    DictType::select_by_id(&mut RB.clone(), &item.id).await?.map_or_else(
+    DictType::select_by_id(&state.rb, &item.id).await?.map_or_else(
         || Err(AppError::BusinessError("字典类型不存在")),
         |x| {
             let data: DictTypeResp = x.into();
@@ -134,13 +139,14 @@ pub async fn query_sys_dict_type_detail(req: &mut Request, res: &mut Response) -
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_dict_type_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询字典类型表列表", request_body = QueryDictTypeListReq, responses((status_code = 200, body = ResponsePage<Vec<DictTypeResp>>)))]
+pub async fn query_sys_dict_type_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryDictTypeListReq>().await?;
     log::info!("query sys_dict_type_list params: {:?}", &item);
 
     let page = &PageRequest::new(item.page_no, item.page_size);
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     DictType::select_dict_type_list(rb, page, &item)
         .await
@@ -2,11 +2,11 @@
 // author：刘飞华
 // date：2025/01/08 13:51:14
 
+use crate::common::app_state::app_state;
 use crate::common::error::{AppError, AppResult};
-use crate::common::result::{ok_result, ok_result_data, ok_result_page};
+use crate::common::result::{BaseResponse, ResponsePage, ok_result, ok_result_data, ok_result_page};
 use crate::model::system::sys_login_log_model::LoginLog;
 use crate::vo::system::sys_login_log_vo::*;
-use crate::RB;
 use rbatis::plugin::page::PageRequest;
 use rbs::value;
 use salvo::prelude::*;
@@ -16,12 +16,13 @@ use salvo::{Request, Response};
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn delete_sys_login_log(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "删除系统访问记录", request_body = DeleteLoginLogReq)]
+pub async fn delete_sys_login_log(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DeleteLoginLogReq>().await?;
     log::info!("delete sys_login_log params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     LoginLog::delete_by_map(rb, value! {"id": &item.ids}).await.map(|_| ok_result(res))?
 }
@@ -31,12 +32,13 @@ pub async fn delete_sys_login_log(req: &mut Request, res: &mut Response) -> AppR
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_login_log_detail(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询系统访问记录详情", request_body = QueryLoginLogDetailReq, responses((status_code = 200, body = BaseResponse<LoginLogResp>)))]
+pub async fn query_sys_login_log_detail(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryLoginLogDetailReq>().await?;
     log::info!("query sys_login_log_detail params: {:?}", &item);
 
-    LoginLog::select_by_id(&mut RB.clone(), &item.id).await?.map_or_else(
+    LoginLog::select_by_id(&state.rb, &item.id).await?.map_or_else(
         || Err(AppError::BusinessError("系统访问记录不存在")),
         |x| {
             let data: LoginLogResp = x.into();
@@ -50,13 +52,14 @@ pub async fn query_sys_login_log_detail(req: &mut Request, res: &mut Response) -
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_login_log_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询系统访问记录列表", request_body = QueryLoginLogListReq, responses((status_code = 200, body = ResponsePage<Vec<LoginLogResp>>)))]
+pub async fn query_sys_login_log_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryLoginLogListReq>().await?;
     log::info!("query sys_login_log_list params: {:?}", &item);
 
     let page = &PageRequest::new(item.page_no, item.page_size);
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     LoginLog::select_login_log_list(rb, page, &item)
         .await
@@ -3,12 +3,13 @@
 // date：2025/01/08 13:51:14
 
 use rbatis::rbdc::DateTime;
+use crate::common::app_state::app_state;
 use crate::common::error::{AppError, AppResult};
-use crate::common::result::{ok_result, ok_result_data};
+use crate::common::result::{BaseResponse, ok_result, ok_result_data};
 use crate::model::system::sys_menu_model::{select_count_menu_by_parent_id, Menu};
 use crate::model::system::sys_role_menu_model::select_count_menu_by_menu_id;
+use crate::utils::permission_util::refresh_online_permissions;
 use crate::vo::system::sys_menu_vo::*;
-use crate::RB;
 use rbs::value;
 use salvo::prelude::*;
 use salvo::{Request, Response};
@@ -18,12 +19,13 @@ use salvo::{Request, Response};
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn add_sys_menu(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "添加菜单信息", request_body = MenuReq)]
+pub async fn add_sys_menu(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<MenuReq>().await?;
     log::info!("add sys_menu params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     if Menu::select_by_menu_name(rb, &item.menu_name).await?.is_some() {
         return Err(AppError::BusinessError("菜单名称已存在"));
     }
@@ -42,12 +44,13 @@ pub async fn add_sys_menu(req: &mut Request, res: &mut Response) -> AppResult<()
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn delete_sys_menu(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "删除菜单信息", request_body = DeleteMenuReq)]
+pub async fn delete_sys_menu(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DeleteMenuReq>().await?;
     log::info!("delete sys_menu params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     if select_count_menu_by_parent_id(rb, &item.id).await? > 0 {
         return Err(AppError::BusinessError("存在子菜单,不允许删除"));
@@ -57,7 +60,14 @@ pub async fn delete_sys_menu(req: &mut Request, res: &mut Response) -> AppResult
         return Err(AppError::BusinessError("菜单已分配,不允许删除"));
     }
 
-    Menu::delete_by_map(rb, value! {"id": &item.id}).await.map(|_| ok_result(res))?
+    Menu::delete_by_map(rb, value! {"id": &item.id}).await?;
+
+    //超级管理员拥有全部菜单的权限
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    refresh_online_permissions(&state.rb, &mut conn).await?;
+
+    ok_result(res)
 }
 
 /*
@@ -65,17 +75,19 @@ pub async fn delete_sys_menu(req: &mut Request, res: &mut Response) -> AppResult
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_menu(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新菜单信息", request_body = MenuReq)]
+pub async fn update_sys_menu(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<MenuReq>().await?;
     log::info!("update sys_menu params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     let id = item.id;
-    if Menu::select_by_id(rb, &id.unwrap_or_default()).await?.is_none() {
-        return Err(AppError::BusinessError("菜单信息不存在"));
-    }
+    let old = match Menu::select_by_id(rb, &id.unwrap_or_default()).await? {
+        None => return Err(AppError::BusinessError("菜单信息不存在")),
+        Some(x) => x,
+    };
 
     if let Some(x) = Menu::select_by_menu_name(rb, &item.menu_name).await? {
         if x.id != id {
@@ -94,7 +106,16 @@ pub async fn update_sys_menu(req: &mut Request, res: &mut Response) -> AppResult
 
     let mut data = Menu::from(item);
     data.update_time = Some(DateTime::now());
-    Menu::update_by_map(rb, &data, value! {"id": &id}).await.map(|_| ok_result(res))?
+    Menu::update_by_map(rb, &data, value! {"id": &id}).await?;
+
+    //接口地址、权限标识或者状态变更时,在线用户的权限立即生效
+    if old.api_url != data.api_url || old.perms != data.perms || old.status != data.status {
+        let pool = &state.redis;
+        let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+        refresh_online_permissions(&state.rb, &mut conn).await?;
+    }
+
+    ok_result(res)
 }
 
 /*
@@ -102,8 +123,9 @@ pub async fn update_sys_menu(req: &mut Request, res: &mut Response) -> AppResult
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_menu_status(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新菜单信息状态", request_body = UpdateMenuStatusReq)]
+pub async fn update_sys_menu_status(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UpdateMenuStatusReq>().await?;
 
     log::info!("update sys_menu_status params: {:?}", &item);
@@ -113,7 +135,14 @@ pub async fn update_sys_menu_status(req: &mut Request, res: &mut Response) -> Ap
     let mut param = vec![value!(item.status),value!(DateTime::now())];
     param.extend(item.ids.iter().map(|&id| value!(id)));
 
-    RB.clone().exec(&update_sql, param).await.map(|_| ok_result(res))?
+    state.rb.exec(&update_sql, param).await?;
+
+    //菜单状态影响所有用户,在线用户的权限立即生效
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    refresh_online_permissions(&state.rb, &mut conn).await?;
+
+    ok_result(res)
 }
 
 /*
@@ -121,12 +150,13 @@ pub async fn update_sys_menu_status(req: &mut Request, res: &mut Response) -> Ap
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_menu_detail(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询菜单信息详情", request_body = QueryMenuDetailReq, responses((status_code = 200, body = BaseResponse<MenuResp>)))]
+pub async fn query_sys_menu_detail(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryMenuDetailReq>().await?;
     log::info!("query sys_menu_detail params: {:?}", &item);
 
-    Menu::select_by_id(&mut RB.clone(), &item.id).await?.map_or_else(
+    Menu::select_by_id(&state.rb, &item.id).await?.map_or_else(
         || Err(AppError::BusinessError("菜单信息不存在")),
         |x| {
             let data: MenuResp = x.into();
@@ -140,12 +170,13 @@ pub async fn query_sys_menu_detail(req: &mut Request, res: &mut Response) -> App
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_menu_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询菜单信息列表", request_body = QueryMenuListReq, responses((status_code = 200, body = BaseResponse<Vec<MenuResp>>)))]
+pub async fn query_sys_menu_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryMenuListReq>().await?;
     log::info!("query sys_menu_list params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     Menu::select_all(rb).await.map(|x| ok_result_data(res, x.into_iter().map(|x| x.into()).collect::<Vec<MenuResp>>()))?
 }
@@ -155,11 +186,12 @@ pub async fn query_sys_menu_list(req: &mut Request, res: &mut Response) -> AppRe
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_menu_list_simple(res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询菜单信息(排除按钮)", responses((status_code = 200, body = BaseResponse<Vec<MenuListSimpleDataResp>>)))]
+pub async fn query_sys_menu_list_simple(depot: &mut Depot, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let mut list: Vec<MenuListSimpleDataResp> = Vec::new();
 
-    for x in Menu::select_menu_list(&mut RB.clone()).await? {
+    for x in Menu::select_menu_list(&state.rb).await? {
         list.push(MenuListSimpleDataResp {
             id: x.id,               //主键
             menu_name: x.menu_name, //菜单名称
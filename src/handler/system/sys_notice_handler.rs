@@ -2,11 +2,11 @@
 // author：刘飞华
 // date：2025/01/08 13:51:14
 
+use crate::common::app_state::app_state;
 use crate::common::error::{AppError, AppResult};
-use crate::common::result::{ok_result, ok_result_data, ok_result_page};
+use crate::common::result::{BaseResponse, ResponsePage, ok_result, ok_result_data, ok_result_page};
 use crate::model::system::sys_notice_model::Notice;
 use crate::vo::system::sys_notice_vo::*;
-use crate::RB;
 use rbatis::plugin::page::PageRequest;
 use rbatis::rbdc::DateTime;
 use rbs::value;
@@ -18,12 +18,13 @@ use salvo::{Request, Response};
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn add_sys_notice(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "添加通知公告表", request_body = NoticeReq)]
+pub async fn add_sys_notice(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<NoticeReq>().await?;
     log::info!("add sys_notice params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     if Notice::exists_by_title(rb, &item.notice_title).await? {
         return Err(AppError::BusinessError("公告标题已存在"));
@@ -37,13 +38,14 @@ pub async fn add_sys_notice(req: &mut Request, res: &mut Response) -> AppResult<
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn delete_sys_notice(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "删除通知公告表", request_body = DeleteNoticeReq)]
+pub async fn delete_sys_notice(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DeleteNoticeReq>().await?;
 
     log::info!("delete sys_notice params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     Notice::delete_by_map(rb, value! {"id": &item.ids}).await.map(|_| ok_result(res))?
 }
@@ -53,12 +55,13 @@ pub async fn delete_sys_notice(req: &mut Request, res: &mut Response) -> AppResu
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_notice(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新通知公告表", request_body = NoticeReq)]
+pub async fn update_sys_notice(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<NoticeReq>().await?;
     log::info!("update sys_notice params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     let id = item.id;
 
     if Notice::select_by_id(rb, &id.unwrap_or_default()).await?.is_none() {
@@ -79,8 +82,9 @@ pub async fn update_sys_notice(req: &mut Request, res: &mut Response) -> AppResu
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_notice_status(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新通知公告表状态", request_body = UpdateNoticeStatusReq)]
+pub async fn update_sys_notice_status(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UpdateNoticeStatusReq>().await?;
     log::info!("update sys_notice_status params: {:?}", &item);
 
@@ -89,7 +93,7 @@ pub async fn update_sys_notice_status(req: &mut Request, res: &mut Response) ->
     let mut param = vec![value!(item.status),value!(DateTime::now())];
     param.extend(item.ids.iter().map(|&id| value!(id)));
 
-    RB.clone().exec(&update_sql, param).await.map(|_| ok_result(res))?
+    state.rb.exec(&update_sql, param).await.map(|_| ok_result(res))?
 }
 
 /*
@@ -97,13 +101,14 @@ pub async fn update_sys_notice_status(req: &mut Request, res: &mut Response) ->
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_notice_detail(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询通知公告表详情", request_body = QueryNoticeDetailReq, responses((status_code = 200, body = BaseResponse<NoticeResp>)))]
+pub async fn query_sys_notice_detail(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryNoticeDetailReq>().await?;
 
     log::info!("query sys_notice_detail params: {:?}", &item);
 
-    Notice::select_by_id(&mut RB.clone(), &item.id).await?.map_or_else(
+    Notice::select_by_id(&state.rb, &item.id).await?.map_or_else(
         || Err(AppError::BusinessError("通知公告表不存在")),
         |x| {
             let notice: NoticeResp = x.into();
@@ -117,13 +122,14 @@ pub async fn query_sys_notice_detail(req: &mut Request, res: &mut Response) -> A
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_notice_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询通知公告表列表", request_body = QueryNoticeListReq, responses((status_code = 200, body = ResponsePage<Vec<NoticeResp>>)))]
+pub async fn query_sys_notice_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryNoticeListReq>().await?;
     log::info!("query sys_notice_list params: {:?}", &item);
 
     let page = &PageRequest::new(item.page_no, item.page_size);
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     Notice::select_sys_notice_list(rb, page, &item)
         .await
@@ -2,11 +2,11 @@
 // author：刘飞华
 // date：2025/01/08 13:51:14
 
+use crate::common::app_state::app_state;
 use crate::common::error::{AppError, AppResult};
-use crate::common::result::{ok_result, ok_result_data, ok_result_page};
+use crate::common::result::{BaseResponse, ResponsePage, ok_result, ok_result_data, ok_result_page};
 use crate::model::system::sys_operate_log_model::OperateLog;
 use crate::vo::system::sys_operate_log_vo::*;
-use crate::RB;
 use rbatis::PageRequest;
 use rbs::value;
 use salvo::prelude::*;
@@ -16,12 +16,13 @@ use salvo::{Request, Response};
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn delete_sys_operate_log(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "删除操作日志记录", request_body = DeleteOperateLogReq)]
+pub async fn delete_sys_operate_log(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DeleteOperateLogReq>().await?;
     log::info!("delete sys_operate_log params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     OperateLog::delete_by_map(rb, value! {"id": &item.ids}).await.map(|_| ok_result(res))?
 }
@@ -31,13 +32,14 @@ pub async fn delete_sys_operate_log(req: &mut Request, res: &mut Response) -> Ap
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_operate_log_detail(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询操作日志记录详情", request_body = QueryOperateLogDetailReq, responses((status_code = 200, body = BaseResponse<OperateLogResp>)))]
+pub async fn query_sys_operate_log_detail(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryOperateLogDetailReq>().await?;
 
     log::info!("query sys_operate_log_detail params: {:?}", &item);
 
-    OperateLog::select_by_id(&mut RB.clone(), &item.id).await?.map_or_else(
+    OperateLog::select_by_id(&state.rb, &item.id).await?.map_or_else(
         || Err(AppError::BusinessError("操作日志不存在")),
         |x| {
             let data: OperateLogResp = x.into();
@@ -51,13 +53,14 @@ pub async fn query_sys_operate_log_detail(req: &mut Request, res: &mut Response)
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_operate_log_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询操作日志记录列表", request_body = QueryOperateLogListReq, responses((status_code = 200, body = ResponsePage<Vec<OperateLogResp>>)))]
+pub async fn query_sys_operate_log_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryOperateLogListReq>().await?;
     log::info!("query sys_operate_log_list params: {:?}", &item);
 
     let page = &PageRequest::new(item.page_no, item.page_size);
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     OperateLog::select_page_by_name(rb, page, &item)
         .await
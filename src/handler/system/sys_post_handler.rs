@@ -2,12 +2,12 @@
 // author：刘飞华
 // date：2025/01/08 13:51:14
 
+use crate::common::app_state::app_state;
 use crate::common::error::{AppError, AppResult};
-use crate::common::result::{ok_result, ok_result_data, ok_result_page};
+use crate::common::result::{BaseResponse, ResponsePage, ok_result, ok_result_data, ok_result_page};
 use crate::model::system::sys_post_model::Post;
 use crate::model::system::sys_user_post_model::count_user_post_by_id;
 use crate::vo::system::sys_post_vo::*;
-use crate::RB;
 use rbatis::plugin::page::PageRequest;
 use rbatis::rbdc::DateTime;
 use rbs::value;
@@ -19,12 +19,13 @@ use salvo::{Request, Response};
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn add_sys_post(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "添加岗位信息表", request_body = PostReq)]
+pub async fn add_sys_post(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<PostReq>().await?;
     log::info!("add sys_post params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     if Post::select_by_name(rb, &item.post_name).await?.is_some() {
         return Err(AppError::BusinessError("岗位名称已存在"));
@@ -42,13 +43,14 @@ pub async fn add_sys_post(req: &mut Request, res: &mut Response) -> AppResult<()
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn delete_sys_post(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "删除岗位信息表", request_body = DeletePostReq)]
+pub async fn delete_sys_post(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DeletePostReq>().await?;
     log::info!("delete sys_post params: {:?}", &item);
 
     let ids = item.ids.clone();
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     for id in ids {
         match Post::select_by_id(rb, &id).await? {
             None => return Err(AppError::BusinessError("不能删除")),
@@ -68,12 +70,13 @@ pub async fn delete_sys_post(req: &mut Request, res: &mut Response) -> AppResult
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_post(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新岗位信息表", request_body = PostReq)]
+pub async fn update_sys_post(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<PostReq>().await?;
     log::info!("update sys_post params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     let id = item.id;
 
@@ -103,8 +106,9 @@ pub async fn update_sys_post(req: &mut Request, res: &mut Response) -> AppResult
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_post_status(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新岗位信息表状态", request_body = UpdatePostStatusReq)]
+pub async fn update_sys_post_status(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UpdatePostStatusReq>().await?;
     log::info!("update sys_post_status params: {:?}", &item);
 
@@ -113,7 +117,7 @@ pub async fn update_sys_post_status(req: &mut Request, res: &mut Response) -> Ap
     let mut param = vec![value!(item.status),value!(DateTime::now())];
     param.extend(item.ids.iter().map(|&id| value!(id)));
 
-    RB.clone().exec(&update_sql, param).await.map(|_| ok_result(res))?
+    state.rb.exec(&update_sql, param).await.map(|_| ok_result(res))?
 }
 
 /*
@@ -121,12 +125,13 @@ pub async fn update_sys_post_status(req: &mut Request, res: &mut Response) -> Ap
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_post_detail(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询岗位信息表详情", request_body = QueryPostDetailReq, responses((status_code = 200, body = BaseResponse<PostResp>)))]
+pub async fn query_sys_post_detail(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryPostDetailReq>().await?;
     log::info!("query sys_post_detail params: {:?}", &item);
 
-    Post::select_by_id(&mut RB.clone(), &item.id).await?.map_or_else(
+    Post::select_by_id(&state.rb, &item.id).await?.map_or_else(
         || Err(AppError::BusinessError("岗位不存在")),
         |x| {
             let data: PostResp = x.into();
@@ -140,13 +145,14 @@ pub async fn query_sys_post_detail(req: &mut Request, res: &mut Response) -> App
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_post_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询岗位信息表列表", request_body = QueryPostListReq, responses((status_code = 200, body = ResponsePage<Vec<PostResp>>)))]
+pub async fn query_sys_post_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryPostListReq>().await?;
     log::info!("query sys_post_list params: {:?}", &item);
 
     let page = &PageRequest::new(item.page_no, item.page_size);
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     Post::select_post_list(rb, page, &item)
         .await
@@ -2,17 +2,20 @@
 // author：刘飞华
 // date：2025/01/08 13:51:14
 
+use crate::common::app_state::app_state;
 use crate::common::error::{AppError, AppResult};
-use crate::common::result::{ok_result, ok_result_data, ok_result_page};
+use crate::common::result::{BaseResponse, ResponsePage, ok_result, ok_result_data, ok_result_page};
+use crate::model::system::sys_dept_model::Dept;
 use crate::model::system::sys_menu_model::Menu;
 use crate::model::system::sys_role_dept_model::RoleDept;
 use crate::model::system::sys_role_menu_model::{query_menu_by_role, RoleMenu};
 use crate::model::system::sys_role_model::Role;
 use crate::model::system::sys_user_model::{count_allocated_list, count_unallocated_list, select_allocated_list, select_unallocated_list};
 use crate::model::system::sys_user_role_model::{count_user_role_by_role_id, delete_user_role_by_role_id_user_id, UserRole};
+use crate::utils::data_scope_util::{check_role_scope, check_user_scope, current_data_scope, DATA_SCOPE_ALL, DATA_SCOPE_CUSTOM, DATA_SCOPE_DEPT_AND_CHILD};
+use crate::utils::permission_util::{refresh_role_permissions, refresh_user_permissions};
 use crate::vo::system::sys_role_vo::*;
 use crate::vo::system::sys_user_vo::UserResp;
-use crate::RB;
 use rbatis::plugin::page::PageRequest;
 use rbatis::rbdc::datetime::DateTime;
 use rbs::value;
@@ -23,12 +26,13 @@ use salvo::{Request, Response};
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn add_sys_role(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "添加角色信息", request_body = RoleReq)]
+pub async fn add_sys_role(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<RoleReq>().await?;
     log::info!("add sys_role params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     if Role::select_by_role_name(rb, &item.role_name).await?.is_some() {
         return Err(AppError::BusinessError("角色名称已存在"));
     }
@@ -45,8 +49,9 @@ pub async fn add_sys_role(req: &mut Request, res: &mut Response) -> AppResult<()
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn delete_sys_role(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "删除角色信息", request_body = DeleteRoleReq)]
+pub async fn delete_sys_role(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DeleteRoleReq>().await?;
     log::info!("delete sys_role params: {:?}", &item);
 
@@ -56,7 +61,7 @@ pub async fn delete_sys_role(req: &mut Request, res: &mut Response) -> AppResult
         return Err(AppError::BusinessError("不允许操作超级管理员角色"));
     }
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     for id in ids {
         if let None = Role::select_by_id(rb, &id).await? {
             return Err(AppError::BusinessError("角色不存在,不能删除"));
@@ -78,12 +83,13 @@ pub async fn delete_sys_role(req: &mut Request, res: &mut Response) -> AppResult
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_role(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新角色信息", request_body = RoleReq)]
+pub async fn update_sys_role(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<RoleReq>().await?;
     log::info!("update sys_role params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     let id = item.id;
 
@@ -109,7 +115,14 @@ pub async fn update_sys_role(req: &mut Request, res: &mut Response) -> AppResult
 
     let mut data = Role::from(item);
     data.update_time = Some(DateTime::now());
-    Role::update_by_map(rb, &data, value! {"id": &id}).await.map(|_| ok_result(res))?
+    Role::update_by_map(rb, &data, value! {"id": &id}).await?;
+
+    //角色状态可能变更,拥有该角色的在线用户的权限立即生效
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    refresh_role_permissions(&state.rb, &mut conn, &[id.unwrap_or_default()]).await?;
+
+    ok_result(res)
 }
 
 /*
@@ -117,8 +130,9 @@ pub async fn update_sys_role(req: &mut Request, res: &mut Response) -> AppResult
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_role_status(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新角色信息状态", request_body = UpdateRoleStatusReq)]
+pub async fn update_sys_role_status(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UpdateRoleStatusReq>().await?;
     log::info!("update sys_role_status params: {:?}", &item);
 
@@ -134,7 +148,14 @@ pub async fn update_sys_role_status(req: &mut Request, res: &mut Response) -> Ap
     let mut param = vec![value!(item.status), value!(DateTime::now())];
     param.extend(item.ids.iter().map(|&id| value!(id)));
 
-    RB.clone().exec(&update_sql, param).await.map(|_| ok_result(res))?
+    state.rb.exec(&update_sql, param).await?;
+
+    //禁用或者启用的角色,拥有该角色的在线用户的权限立即生效
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    refresh_role_permissions(&state.rb, &mut conn, &item.ids).await?;
+
+    ok_result(res)
 }
 
 /*
@@ -142,12 +163,13 @@ pub async fn update_sys_role_status(req: &mut Request, res: &mut Response) -> Ap
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_role_detail(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询角色信息详情", request_body = QueryRoleDetailReq, responses((status_code = 200, body = BaseResponse<RoleResp>)))]
+pub async fn query_sys_role_detail(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryRoleDetailReq>().await?;
     log::info!("query sys_role_detail params: {:?}", &item);
 
-    Role::select_by_id(&mut RB.clone(), &item.id).await?.map_or_else(
+    Role::select_by_id(&state.rb, &item.id).await?.map_or_else(
         || Err(AppError::BusinessError("角色不存在")),
         |x| {
             let data: RoleResp = x.into();
@@ -161,13 +183,14 @@ pub async fn query_sys_role_detail(req: &mut Request, res: &mut Response) -> App
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_role_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询角色信息列表", request_body = QueryRoleListReq, responses((status_code = 200, body = ResponsePage<Vec<RoleResp>>)))]
+pub async fn query_sys_role_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryRoleListReq>().await?;
     log::info!("query sys_role_list params: {:?}", &item);
 
     let page = &PageRequest::new(item.page_no, item.page_size);
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     Role::select_sys_role_list(rb, page, &item)
         .await
@@ -179,12 +202,13 @@ pub async fn query_sys_role_list(req: &mut Request, res: &mut Response) -> AppRe
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_role_menu(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询角色关联的菜单", request_body = QueryRoleMenuReq, responses((status_code = 200, body = BaseResponse<QueryRoleMenuData>)))]
+pub async fn query_role_menu(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryRoleMenuReq>().await?;
     log::info!("query role_menu params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     let menu_list_all = Menu::select_all(rb).await?;
 
     let mut menu_list: Vec<MenuDataList> = Vec::new();
@@ -220,8 +244,9 @@ pub async fn query_role_menu(req: &mut Request, res: &mut Response) -> AppResult
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_role_menu(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新角色关联的菜单", request_body = UpdateRoleMenuReq)]
+pub async fn update_role_menu(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UpdateRoleMenuReq>().await?;
     log::info!("update_role_menu params: {:?}", &item);
     let role_id = item.role_id;
@@ -230,7 +255,7 @@ pub async fn update_role_menu(req: &mut Request, res: &mut Response) -> AppResul
         return Err(AppError::BusinessError("不允许操作超级管理员角色"));
     }
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     RoleMenu::delete_by_map(rb, value! {"role_id": &role_id}).await?;
     let mut role_menu: Vec<RoleMenu> = Vec::new();
@@ -246,6 +271,95 @@ pub async fn update_role_menu(req: &mut Request, res: &mut Response) -> AppResul
     }
 
     RoleMenu::insert_batch(rb, &role_menu, item.menu_ids.len() as u64).await?;
+
+    //拥有该角色的在线用户的权限立即生效
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    refresh_role_permissions(&state.rb, &mut conn, &[role_id]).await?;
+
+    ok_result(res)
+}
+
+/*
+ *查询角色的数据权限(数据范围和自定数据权限的部门)
+ */
+#[endpoint(summary = "查询角色的数据权限", request_body = QueryRoleDeptReq, responses((status_code = 200, body = BaseResponse<QueryRoleDeptData>)))]
+pub async fn query_role_dept(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let item = req.parse_json::<QueryRoleDeptReq>().await?;
+    log::info!("query role_dept params: {:?}", &item);
+
+    let rb = &state.rb;
+    let role = match Role::select_by_id(rb, &item.role_id).await? {
+        None => return Err(AppError::BusinessError("角色不存在")),
+        Some(x) => x,
+    };
+
+    //只返回当前用户有数据权限的部门
+    let scope = current_data_scope(depot).await?;
+    let mut dept_list: Vec<DeptDataList> = Vec::new();
+    for x in Dept::select_all(rb).await? {
+        if !scope.allows_dept(x.id.unwrap_or_default()) {
+            continue;
+        }
+        dept_list.push(DeptDataList {
+            id: x.id,
+            parent_id: x.parent_id,
+            title: x.dept_name.clone(),
+            key: x.id.unwrap_or_default().to_string(),
+            label: x.dept_name,
+        });
+    }
+
+    let dept_ids = RoleDept::select_by_map(rb, value! {"role_id": &item.role_id}).await?.iter().map(|x| x.dept_id).collect();
+
+    ok_result_data(
+        res,
+        QueryRoleDeptData {
+            data_scope: role.data_scope,
+            dept_ids,
+            dept_list,
+        },
+    )
+}
+
+/*
+ *更新角色的数据权限,自定数据权限时保存角色关联的部门
+ */
+#[endpoint(summary = "更新角色的数据权限", request_body = UpdateRoleDeptReq)]
+pub async fn update_role_dept(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let item = req.parse_json::<UpdateRoleDeptReq>().await?;
+    log::info!("update role_dept params: {:?}", &item);
+    let role_id = item.role_id;
+
+    if role_id == 1 {
+        return Err(AppError::BusinessError("不允许操作超级管理员角色"));
+    }
+
+    if item.data_scope < DATA_SCOPE_ALL || item.data_scope > DATA_SCOPE_DEPT_AND_CHILD {
+        return Err(AppError::BusinessError("数据范围不正确"));
+    }
+
+    let rb = &state.rb;
+    if Role::select_by_id(rb, &role_id).await?.is_none() {
+        return Err(AppError::BusinessError("角色不存在"));
+    }
+
+    //不能授予超出自己数据权限的范围
+    let scope = current_data_scope(depot).await?;
+    let dept_ids = if item.data_scope == DATA_SCOPE_CUSTOM { item.dept_ids.as_slice() } else { &[] };
+    check_role_scope(rb, &scope, role_id, item.data_scope, dept_ids).await?;
+
+    rb.exec("update sys_role set data_scope = ?, update_time = ? where id = ?", vec![value!(item.data_scope), value!(DateTime::now()), value!(role_id)])
+        .await?;
+
+    RoleDept::delete_by_map(rb, value! {"role_id": &role_id}).await?;
+    if item.data_scope == DATA_SCOPE_CUSTOM && !item.dept_ids.is_empty() {
+        let role_dept: Vec<RoleDept> = item.dept_ids.iter().map(|&dept_id| RoleDept { role_id, dept_id }).collect();
+        RoleDept::insert_batch(rb, &role_dept, role_dept.len() as u64).await?;
+    }
+
     ok_result(res)
 }
 
@@ -254,8 +368,9 @@ pub async fn update_role_menu(req: &mut Request, res: &mut Response) -> AppResul
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_allocated_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询已分配用户角色列表", request_body = AllocatedListReq, responses((status_code = 200, body = ResponsePage<Vec<UserResp>>)))]
+pub async fn query_allocated_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<AllocatedListReq>().await?;
     log::info!("update role_menu params: {:?}", &item);
 
@@ -266,15 +381,16 @@ pub async fn query_allocated_list(req: &mut Request, res: &mut Response) -> AppR
     let user_name = item.user_name.as_deref().unwrap_or_default();
 
     let page_no = (page_no - 1) * page_size;
-    let rb = &mut RB.clone();
-    let p = select_allocated_list(rb, role_id, user_name, mobile, page_no, page_size).await?;
+    let rb = &state.rb;
+    let scope = current_data_scope(depot).await?;
+    let p = select_allocated_list(rb, role_id, user_name, mobile, &scope, page_no, page_size).await?;
 
     let mut list: Vec<UserResp> = Vec::new();
     for x in p {
         list.push(x.into())
     }
 
-    let total = count_allocated_list(rb, role_id, user_name, mobile).await?;
+    let total = count_allocated_list(rb, role_id, user_name, mobile, &scope).await?;
     ok_result_page(res, list, total)
 }
 
@@ -283,8 +399,9 @@ pub async fn query_allocated_list(req: &mut Request, res: &mut Response) -> AppR
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_unallocated_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询未分配用户角色列表", request_body = UnallocatedListReq, responses((status_code = 200, body = ResponsePage<Vec<UserResp>>)))]
+pub async fn query_unallocated_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UnallocatedListReq>().await?;
     log::info!("update role_menu params: {:?}", &item);
 
@@ -296,15 +413,16 @@ pub async fn query_unallocated_list(req: &mut Request, res: &mut Response) -> Ap
 
     let page_no = (page_no - 1) * page_size;
 
-    let rb = &mut RB.clone();
-    let d = select_unallocated_list(rb, role_id, user_name, mobile, page_no, page_size).await?;
+    let rb = &state.rb;
+    let scope = current_data_scope(depot).await?;
+    let d = select_unallocated_list(rb, role_id, user_name, mobile, &scope, page_no, page_size).await?;
 
     let mut list: Vec<UserResp> = Vec::new();
     for x in d {
         list.push(x.into())
     }
 
-    let total = count_unallocated_list(rb, role_id, user_name, mobile).await?;
+    let total = count_unallocated_list(rb, role_id, user_name, mobile, &scope).await?;
     ok_result_page(res, list, total)
 }
 
@@ -313,14 +431,21 @@ pub async fn query_unallocated_list(req: &mut Request, res: &mut Response) -> Ap
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn cancel_auth_user(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "取消授权用户", request_body = CancelAuthUserReq)]
+pub async fn cancel_auth_user(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<CancelAuthUserReq>().await?;
     log::info!("update role_menu params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
+    check_user_scope(rb, &current_data_scope(depot).await?, &[item.user_id]).await?;
 
     delete_user_role_by_role_id_user_id(rb, item.role_id, item.user_id).await?;
+
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    refresh_user_permissions(&state.rb, &mut conn, &[item.user_id]).await?;
+
     ok_result(res)
 }
 
@@ -329,10 +454,12 @@ pub async fn cancel_auth_user(req: &mut Request, res: &mut Response) -> AppResul
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn batch_cancel_auth_user(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "批量取消授权用户", request_body = CancelAuthUserAllReq)]
+pub async fn batch_cancel_auth_user(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<CancelAuthUserAllReq>().await?;
     log::info!("cancel auth_user_all params: {:?}", &item);
+    check_user_scope(&state.rb, &current_data_scope(depot).await?, &item.user_ids).await?;
 
     let update_sql = format!(
         "delete from sys_user_role where role_id = ? and user_id in ({})",
@@ -342,7 +469,12 @@ pub async fn batch_cancel_auth_user(req: &mut Request, res: &mut Response) -> Ap
     let mut param = vec![value!(item.role_id)];
     param.extend(item.user_ids.iter().map(|&id| value!(id)));
 
-    let _ = &mut RB.clone().exec(&update_sql, param).await?;
+    let _ = &state.rb.exec(&update_sql, param).await?;
+
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    refresh_user_permissions(&state.rb, &mut conn, &item.user_ids).await?;
+
     ok_result(res)
 }
 
@@ -351,10 +483,12 @@ pub async fn batch_cancel_auth_user(req: &mut Request, res: &mut Response) -> Ap
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn batch_auth_user(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "批量选择用户授权", request_body = SelectAuthUserAllReq)]
+pub async fn batch_auth_user(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<SelectAuthUserAllReq>().await?;
     log::info!("select all_auth_user params: {:?}", &item);
+    check_user_scope(&state.rb, &current_data_scope(depot).await?, &item.user_ids).await?;
     let role_id = item.role_id;
 
     let mut user_role: Vec<UserRole> = Vec::new();
@@ -369,8 +503,13 @@ pub async fn batch_auth_user(req: &mut Request, res: &mut Response) -> AppResult
         })
     }
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     UserRole::insert_batch(rb, &user_role, item.user_ids.len() as u64).await?;
+
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    refresh_user_permissions(&state.rb, &mut conn, &item.user_ids).await?;
+
     ok_result(res)
 }
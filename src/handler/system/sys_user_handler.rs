@@ -2,40 +2,64 @@
 // author：刘飞华
 // date：2025/01/08 13:51:14
 
+use crate::common::app_state::{app_state, AppState};
 use crate::common::error::{AppError, AppResult};
-use crate::common::result::{ok_result, ok_result_data, ok_result_page};
+use crate::common::result::{BaseResponse, ResponsePage, ok_result, ok_result_data, ok_result_page};
+use crate::handler::system::sys_user_mfa_handler::{new_enrollment, verify_mfa_code};
 use crate::model::system::sys_dept_model::Dept;
 use crate::model::system::sys_login_log_model::LoginLog;
-use crate::model::system::sys_menu_model::Menu;
+use crate::model::system::sys_menu_model::{select_menu_by_user_id, Menu};
 use crate::model::system::sys_role_model::Role;
-use crate::model::system::sys_user_model::User;
+use crate::model::system::sys_user_mfa_model::{count_mfa_required_role, UserMfa};
+use crate::model::system::sys_user_model::{User, UserListQuery};
 use crate::model::system::sys_user_post_model::UserPost;
 use crate::model::system::sys_user_role_model::{is_admin, UserRole};
+use crate::utils::captcha_util::verify_captcha;
+use crate::utils::data_scope_util::{check_user_scope, current_data_scope};
 use crate::utils::jwt_util::JwtToken;
+use crate::utils::login_guard_util::{check_lock, clear_failures, failure_count, failure_delay, record_failure, unlock};
+use crate::utils::metrics_util::METRICS;
+use crate::utils::mfa_util::{create_challenge, generate_recovery_codes, get_challenge_user, hash_recovery_code, remove_challenge, take_challenge_attempt};
+use crate::utils::permission_util::{query_user_permissions, refresh_user_permissions};
+use crate::utils::session_store_util::StoreConnection;
+use crate::utils::session_util::{create_session, issue_refresh_token, new_session_id, now_timestamp, remove_session, remove_user_sessions, rotate_refresh_token, user_info_key, RefreshResult, Session};
+use crate::utils::ip_util::{client_ip, ClientIp};
 use crate::utils::user_agent_util::UserAgentUtil;
 use crate::vo::system::sys_dept_vo::DeptResp;
 use crate::vo::system::sys_role_vo::RoleResp;
+use crate::vo::system::sys_user_mfa_vo::{LoginChallengeResp, MfaChallengeReq, MfaEnrollResp, MfaLoginReq};
 use crate::vo::system::sys_user_vo::*;
-use crate::RB;
 use chrono::Local;
 use rbatis::plugin::page::PageRequest;
 use rbatis::rbatis_codegen::ops::AsProxy;
 use rbatis::rbdc::datetime::DateTime;
+use rbatis::RBatis;
 use rbs::value;
 use salvo::prelude::*;
 use salvo::{Request, Response};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 /*
  *添加用户信息
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn add_sys_user(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "添加用户信息", request_body = UserReq)]
+pub async fn add_sys_user(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UserReq>().await?;
     log::info!("add sys_user params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let password = match item.password.as_deref() {
+        Some(x) if !x.is_empty() => x,
+        _ => return Err(AppError::BusinessError("密码不能为空")),
+    };
+    let password_config = &state.config.password;
+    let password = password_config.hash(password)?;
+
+    //只能在有数据权限的部门下添加用户
+    current_data_scope(depot).await?.check_dept(item.dept_id)?;
+
+    let rb = &state.rb;
     if User::select_by_user_name(rb, &item.user_name).await?.is_some() {
         return Err(AppError::BusinessError("登录账号已存在"));
     }
@@ -49,7 +73,10 @@ pub async fn add_sys_user(req: &mut Request, res: &mut Response) -> AppResult<()
     }
 
     let post_ids = item.post_ids.clone();
-    let id = User::insert(rb, &User::from(item)).await?.last_insert_id;
+    let mut user = User::from(item);
+    user.password = password;
+    user.pwd_update_date = Some(DateTime::now());
+    let id = User::insert(rb, &user).await?.last_insert_id;
 
     let mut user_post_list: Vec<UserPost> = Vec::new();
     for post_id in post_ids {
@@ -65,8 +92,9 @@ pub async fn add_sys_user(req: &mut Request, res: &mut Response) -> AppResult<()
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
+#[endpoint(summary = "删除用户信息", request_body = DeleteUserReq)]
 pub async fn delete_sys_user(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<DeleteUserReq>().await?;
     log::info!("delete sys_user params: {:?}", &item);
 
@@ -79,12 +107,23 @@ pub async fn delete_sys_user(depot: &mut Depot, req: &mut Request, res: &mut Res
             return Err(AppError::BusinessError("不允许操作超级管理员用户"));
         }
 
-        let rb = &mut RB.clone();
+        let rb = &state.rb;
+        check_user_scope(rb, &current_data_scope(depot).await?, &ids).await?;
+
         UserRole::delete_by_map(rb, value! {"user_id": &ids}).await?;
 
         UserPost::delete_by_map(rb, value! {"user_id": &ids}).await?;
 
-        User::delete_by_map(rb, value! {"id": &item.ids}).await.map(|_| ok_result(res))?
+        User::delete_by_map(rb, value! {"id": &item.ids}).await?;
+
+        //删除的用户立即下线
+        let pool = &state.redis;
+        let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+        for id in &ids {
+            remove_user_sessions(&mut conn, *id).await?;
+        }
+
+        ok_result(res)
     } else {
         Err(AppError::BusinessError("参数错误"))
     }
@@ -95,8 +134,9 @@ pub async fn delete_sys_user(depot: &mut Depot, req: &mut Request, res: &mut Res
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_user(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新用户信息", request_body = UserReq)]
+pub async fn update_sys_user(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UserReq>().await?;
     log::info!("update sys_user params: {:?}", &item);
 
@@ -105,12 +145,17 @@ pub async fn update_sys_user(req: &mut Request, res: &mut Response) -> AppResult
         return Err(AppError::BusinessError("不允许操作超级管理员用户"));
     }
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     let user = match User::select_by_id(rb, item.id.unwrap_or_default()).await? {
         None => return Err(AppError::BusinessError("用户不存在")),
         Some(x) => x,
     };
 
+    //原来的部门和新的部门都需要有数据权限
+    let scope = current_data_scope(depot).await?;
+    scope.check_user(&user)?;
+    scope.check_dept(item.dept_id)?;
+
     if let Some(x) = User::select_by_user_name(rb, &item.user_name).await? {
         if x.id != id {
             return Err(AppError::BusinessError("登录账号已存在"));
@@ -141,9 +186,29 @@ pub async fn update_sys_user(req: &mut Request, res: &mut Response) -> AppResult
     UserPost::delete_by_map(rb, value! {"user_id": &item.id}).await?;
     UserPost::insert_batch(rb, &user_post_list, user_post_list.len() as u64).await?;
 
+    //没有传新密码时保留原来的密码
+    let (password, pwd_update_date) = match item.password.as_deref() {
+        Some(x) if !x.is_empty() => {
+            let password_config = &state.config.password;
+            (password_config.hash(x)?, Some(DateTime::now()))
+        }
+        _ => (user.password, user.pwd_update_date),
+    };
+
     let mut data = User::from(item);
+    data.password = password;
+    data.pwd_update_date = pwd_update_date;
     data.update_time = Some(DateTime::now());
-    User::update_by_map(rb, &data, value! {"id": &id}).await.map(|_| ok_result(res))?
+    User::update_by_map(rb, &data, value! {"id": &id}).await?;
+
+    //禁用的用户立即下线
+    if data.status != 1 {
+        let pool = &state.redis;
+        let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+        remove_user_sessions(&mut conn, id.unwrap_or_default()).await?;
+    }
+
+    ok_result(res)
 }
 
 /*
@@ -151,8 +216,9 @@ pub async fn update_sys_user(req: &mut Request, res: &mut Response) -> AppResult
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_sys_user_status(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新用户信息状态", request_body = UpdateUserStatusReq)]
+pub async fn update_sys_user_status(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UpdateUserStatusReq>().await?;
     log::info!("update sys_user_status params: {:?}", &item);
 
@@ -161,6 +227,8 @@ pub async fn update_sys_user_status(req: &mut Request, res: &mut Response) -> Ap
         return Err(AppError::BusinessError("不允许操作超级管理员用户"));
     }
 
+    check_user_scope(&state.rb, &current_data_scope(depot).await?, &ids).await?;
+
     let update_sql = format!(
         "update sys_user set status = ? ,update_time = ? where id in ({})",
         item.ids.iter().map(|_| "?").collect::<Vec<&str>>().join(", ")
@@ -169,7 +237,18 @@ pub async fn update_sys_user_status(req: &mut Request, res: &mut Response) -> Ap
     let mut param = vec![value!(item.status), value!(DateTime::now())];
     param.extend(item.ids.iter().map(|&id| value!(id)));
 
-    RB.clone().exec(&update_sql, param).await.map(|_| ok_result(res))?
+    state.rb.exec(&update_sql, param).await?;
+
+    //禁用的用户立即下线
+    if item.status != 1 {
+        let pool = &state.redis;
+        let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+        for id in &item.ids {
+            remove_user_sessions(&mut conn, *id).await?;
+        }
+    }
+
+    ok_result(res)
 }
 
 /*
@@ -178,7 +257,8 @@ pub async fn update_sys_user_status(req: &mut Request, res: &mut Response) -> Ap
  *date：2025/01/08 13:51:14
  */
 #[handler]
-pub async fn reset_sys_user_password(req: &mut Request, res: &mut Response) -> AppResult<()> {
+pub async fn reset_sys_user_password(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<ResetUserPwdReq>().await?;
     log::info!("update sys_user_password params: {:?}", &item);
 
@@ -187,13 +267,16 @@ pub async fn reset_sys_user_password(req: &mut Request, res: &mut Response) -> A
         return Err(AppError::BusinessError("不允许操作超级管理员用户"));
     }
 
-    let rb = &mut RB.clone();
+    let password_config = &state.config.password;
+    let rb = &state.rb;
 
     match User::select_by_id(rb, item.id).await? {
         None => Err(AppError::BusinessError("用户不存在")),
         Some(x) => {
+            current_data_scope(depot).await?.check_user(&x)?;
             let mut user = x;
-            user.password = item.password;
+            user.password = password_config.hash(&item.password)?;
+            user.pwd_update_date = Some(DateTime::now());
             User::update_by_map(rb, &user, value! {"id": &user.id}).await.map(|_| ok_result(res))?
         }
     }
@@ -204,22 +287,25 @@ pub async fn reset_sys_user_password(req: &mut Request, res: &mut Response) -> A
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
+#[endpoint(summary = "用户修改自己的密码", request_body = UpdateUserPwdReq)]
 pub async fn update_sys_user_password(req: &mut Request, depot: &mut Depot, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UpdateUserPwdReq>().await?;
     log::info!("update sys_user_password params: {:?}", &item);
 
     if let Ok(user_id) = depot.get::<i64>("userId").copied() {
-        let rb = &mut RB.clone();
+        let password_config = &state.config.password;
+        let rb = &state.rb;
 
         match User::select_by_id(rb, user_id).await? {
             None => Err(AppError::BusinessError("用户不存在")),
             Some(x) => {
                 let mut user = x;
-                if user.password != item.pwd {
+                if !password_config.verify(&item.pwd, &user.password) {
                     return Err(AppError::BusinessError("旧密码不正确"));
                 }
-                user.password = item.re_pwd;
+                user.password = password_config.hash(&item.re_pwd)?;
+                user.pwd_update_date = Some(DateTime::now());
 
                 User::update_by_map(rb, &user, value! {"id": &user.id}).await.map(|_| ok_result(res))?
             }
@@ -234,16 +320,18 @@ pub async fn update_sys_user_password(req: &mut Request, depot: &mut Depot, res:
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_user_detail(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询用户信息详情", request_body = QueryUserDetailReq, responses((status_code = 200, body = BaseResponse<UserResp>)))]
+pub async fn query_sys_user_detail(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryUserDetailReq>().await?;
     log::info!("query sys_user_detail params: {:?}", &item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     let mut x = match User::select_by_id(rb, item.id).await? {
         None => return Err(AppError::BusinessError("用户不存在")),
         Some(user) => {
+            current_data_scope(depot).await?.check_user(&user)?;
             let a: UserResp = user.into();
             a
         }
@@ -271,15 +359,17 @@ pub async fn query_sys_user_detail(req: &mut Request, res: &mut Response) -> App
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_sys_user_list(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询用户信息列表", request_body = QueryUserListReq, responses((status_code = 200, body = ResponsePage<Vec<UserResp>>)))]
+pub async fn query_sys_user_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryUserListReq>().await?;
     log::info!("query sys_user_list params: {:?}", &item);
 
     let page = &PageRequest::new(item.page_no, item.page_size);
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
+    let scope = current_data_scope(depot).await?;
 
-    User::select_sys_user_list(rb, page, &item)
+    User::select_sys_user_list(rb, page, &UserListQuery { req: &item, scope: &scope })
         .await
         .map(|x| ok_result_page(res, x.records.into_iter().map(|x| x.into()).collect::<Vec<UserResp>>(), x.total))?
 }
@@ -289,136 +379,411 @@ pub async fn query_sys_user_list(req: &mut Request, res: &mut Response) -> AppRe
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
+#[endpoint(tags("登录认证"), summary = "用户登录(角色要求双因素认证时返回LoginChallengeResp)", request_body = UserLoginReq, responses((status_code = 200, body = BaseResponse<UserLoginResp>)))]
 pub async fn login(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UserLoginReq>().await?;
     log::info!("user login params: {:?}", &item);
 
     let user_agent = req.header::<&str>("user-agent").unwrap_or_default();
     log::info!("user agent: {:?}", user_agent);
     let agent = UserAgentUtil::new(user_agent);
+    let client = client_ip(depot, req);
+
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    if let Some(lock) = check_lock(&mut conn, &item.mobile, &client.ipaddr).await? {
+        let reason = lock.reason();
+        add_login_log(&state.rb, item.mobile, 0, &reason, agent, &client).await;
+        return Err(AppError::LoginLockedError(reason));
+    }
+
+    //按配置每次都需要验证码,或者账号失败次数达到阈值后才需要
+    let captcha_config = &state.config.captcha;
+    if captcha_config.is_required(failure_count(&mut conn, &item.mobile).await?) {
+        let (captcha_id, captcha_code) = match (&item.captcha_id, &item.captcha_code) {
+            (Some(id), Some(code)) if !id.is_empty() && !code.is_empty() => (id, code),
+            _ => {
+                add_login_log(&state.rb, item.mobile, 0, "验证码不能为空", agent, &client).await;
+                return Err(AppError::BusinessError("验证码不能为空"));
+            }
+        };
+        if !verify_captcha(&mut conn, captcha_id, captcha_code).await? {
+            add_login_log(&state.rb, item.mobile, 0, "验证码错误或已过期", agent, &client).await;
+            return Err(AppError::BusinessError("验证码错误或已过期"));
+        }
+    }
 
-    let rb = &mut RB.clone();
-    let user_result = User::select_by_mobile(rb, &item.mobile).await?;
-    log::info!("query user by mobile: {:?}", user_result);
+    let rb = &state.rb;
+    //已删除的用户按不存在处理
+    let user_result = User::select_by_mobile(rb, &item.mobile).await?.filter(|x| x.del_flag == 1);
+    log::info!("query user by mobile: {:?}", user_result.as_ref().map(|x| x.id));
 
     match user_result {
+        //用户不存在时也校验一次密码并返回同样的提示,避免探测账号是否存在
         None => {
-            add_login_log(item.mobile, 0, "用户不存在", agent).await;
-            Err(AppError::BusinessError("用户不存在"))
+            drop(conn);
+            state.config.password.verify_dummy(&item.password);
+            login_failure(&state, item.mobile, &client, "密码不正确", agent).await
         }
         Some(user) => {
             let mut s_user = user.clone();
             let id = user.id.unwrap_or_default();
-            let username = user.user_name;
             let password = user.password;
 
-            if password.ne(&item.password) {
-                add_login_log(item.mobile, 0, "密码不正确", agent).await;
-                return Err(AppError::BusinessError("密码不正确"));
+            let password_config = &state.config.password;
+            if !password_config.verify(&item.password, &password) {
+                drop(conn);
+                return login_failure(&state, item.mobile, &client, "密码不正确", agent).await;
             }
 
-            let (btn_menu, is_super) = query_btn_menu(&id).await;
+            //密码正确之后再提示禁用,避免通过错误密码探测账号状态
+            if user.status != 1 {
+                add_login_log(&state.rb, item.mobile, 0, "用户已被禁用", agent, &client).await;
+                return Err(AppError::BusinessError("用户已被禁用"));
+            }
 
-            if btn_menu.len() == 0 {
-                add_login_log(item.mobile, 0, "用户没有分配角色或者菜单,不能登录", agent).await;
-                return Err(AppError::BusinessError("用户没有分配角色或者菜单,不能登录"));
+            //历史明文密码或者加密参数变更过的密码,登录成功后重新加密
+            //password字段太短(还没有执行V2迁移)时启动时已关闭重新加密
+            let rehash = state.password_rehash && password_config.needs_rehash(&password);
+            if rehash {
+                s_user.password = password_config.hash(&item.password)?;
+                s_user.pwd_update_date = Some(DateTime::now());
             }
 
-            let secret = depot.get::<String>("secret").map_err(|_| AppError::BusinessError("获取jwt密钥异常"))?;
-            let token = JwtToken::new(id, &username).create_token(secret)?;
-
-            let pool = depot.get::<deadpool_redis::Pool>("pool").map_err(|_| AppError::BusinessError("获取redis连接池异常"))?;
-            let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
-            let key = format!("salvo:admin:user:info:{:?}", s_user.id.unwrap_or_default());
-            deadpool_redis::redis::cmd("HSET")
-                .arg(&key)
-                .arg(&"permissions")
-                .arg(&btn_menu.join(","))
-                .arg(&"user_name")
-                .arg(&s_user.user_name)
-                .arg(&"is_admin")
-                .arg(&is_super)
-                .arg(&"token")
-                .arg(&token)
-                .arg(&"last_login")
-                .arg(&Local::now().format("%Y-%m-%d %H:%M:%S").to_string())
-                .query_async::<()>(&mut conn)
-                .await?;
-
-            add_login_log(item.mobile, 1, "登录成功", agent.clone()).await;
-            s_user.login_os = agent.os;
-            s_user.login_browser = agent.browser;
-            s_user.login_date = Some(DateTime::now());
-
-            User::update_by_map(rb, &s_user, value! {"id": &s_user.id}).await?;
-            ok_result_data(res, token)
+            //开启了双因素认证或者角色要求双因素认证时,先返回挑战token,校验动态码之后再签发token
+            let mfa_enabled = UserMfa::select_by_user_id(rb, id).await?.is_some_and(|x| x.status == 1);
+            if mfa_enabled || count_mfa_required_role(rb, id).await? > 0 {
+                if rehash {
+                    User::update_by_map(rb, &s_user, value! {"id": &s_user.id}).await?;
+                }
+                let mfa_config = &state.config.mfa;
+                let challenge_token = create_challenge(&mut conn, id, mfa_config.challenge_expire).await?;
+                return ok_result_data(
+                    res,
+                    LoginChallengeResp {
+                        mfa_required: true,
+                        mfa_enrolled: mfa_enabled,
+                        challenge_token,
+                        expires_in: mfa_config.challenge_expire,
+                    },
+                );
+            }
+
+            clear_failures(&mut conn, &item.mobile).await?;
+            let resp = finish_login(depot, &mut conn, s_user, agent, client).await?;
+            ok_result_data(res, resp)
         }
     }
 }
 
 /*
- *添加登录日志
- *author：刘飞华
- *date：2025/01/08 13:51:14
+ *登录第二步: 校验动态码或者恢复码
+ *角色要求双因素认证但还没有绑定的用户,首次校验通过后启用并返回恢复码
  */
-async fn add_login_log(name: String, status: i8, msg: &str, agent: UserAgentUtil) {
-    let sys_login_log = LoginLog {
-        id: None,                             //访问ID
-        login_name: name,                     //登录账号
-        ipaddr: "todo".to_string(),           //登录IP地址
-        login_location: "todo".to_string(),   //登录地点
-        platform: agent.platform,             //平台信息
-        browser: agent.browser,               //浏览器类型
-        version: agent.version,               //浏览器版本
-        os: agent.os,                         //操作系统
-        arch: agent.arch,                     //体系结构信息
-        engine: agent.engine,                 //渲染引擎信息
-        engine_details: agent.engine_details, //渲染引擎详细信息
-        extra: agent.extra,                   //其他信息（可选）
-        status,                               //登录状态(0:失败,1:成功)
-        msg: msg.to_string(),                 //提示消息
-        login_time: None,                     //访问时间
+#[endpoint(tags("登录认证"), summary = "双因素认证登录", request_body = MfaLoginReq, responses((status_code = 200, body = BaseResponse<UserLoginResp>)))]
+pub async fn login_mfa(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let item = req.parse_json::<MfaLoginReq>().await?;
+
+    let user_agent = req.header::<&str>("user-agent").unwrap_or_default();
+    let agent = UserAgentUtil::new(user_agent);
+    let client = client_ip(depot, req);
+
+    let mfa_config = &state.config.mfa;
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+
+    let user_id = match take_challenge_attempt(&mut conn, mfa_config, &item.challenge_token).await? {
+        None => return Err(AppError::BusinessError("验证已过期,请重新登录")),
+        Some(x) => x,
     };
 
-    match LoginLog::insert(&mut RB.clone(), &sys_login_log).await {
-        Ok(_u) => log::info!("add_login_log success: {:?}", sys_login_log),
-        Err(err) => log::error!("add_login_log error params: {:?}, error message: {:?}", sys_login_log, err),
+    let rb = &state.rb;
+    let user = match User::select_by_id(rb, user_id).await? {
+        Some(x) if x.del_flag == 1 => x,
+        _ => return Err(AppError::BusinessError("用户不存在")),
+    };
+    if user.status != 1 {
+        remove_challenge(&mut conn, &item.challenge_token).await?;
+        add_login_log(&state.rb, user.mobile, 0, "用户已被禁用", agent, &client).await;
+        return Err(AppError::BusinessError("用户已被禁用"));
+    }
+
+    if let Some(lock) = check_lock(&mut conn, &user.mobile, &client.ipaddr).await? {
+        remove_challenge(&mut conn, &item.challenge_token).await?;
+        let reason = lock.reason();
+        add_login_log(&state.rb, user.mobile, 0, &reason, agent, &client).await;
+        return Err(AppError::LoginLockedError(reason));
+    }
+
+    let mut mfa = match UserMfa::select_by_user_id(rb, user_id).await? {
+        None => return Err(AppError::BusinessError("请先绑定身份验证器")),
+        Some(x) => x,
+    };
+    if !verify_mfa_code(mfa_config, &mut mfa, &item.code) {
+        drop(conn);
+        return login_failure(&state, user.mobile, &client, "动态码不正确", agent).await;
+    }
+
+    let mut recovery_codes = None;
+    if mfa.status == 0 {
+        let codes = generate_recovery_codes(mfa_config.recovery_codes);
+        mfa.recovery_codes = codes.iter().map(|x| hash_recovery_code(x)).collect::<Vec<String>>().join(",");
+        mfa.status = 1;
+        recovery_codes = Some(codes);
     }
+    UserMfa::update_by_map(rb, &mfa, value! {"id": &mfa.id}).await?;
+
+    remove_challenge(&mut conn, &item.challenge_token).await?;
+    clear_failures(&mut conn, &user.mobile).await?;
+    let mut resp = finish_login(depot, &mut conn, user, agent, client).await?;
+    resp.recovery_codes = recovery_codes;
+    ok_result_data(res, resp)
 }
 
 /*
- *查询按钮权限
- *author：刘飞华
- *date：2025/01/08 13:51:14
+ *登录第二步之前绑定身份验证器(角色要求双因素认证但还没有绑定的用户)
  */
-async fn query_btn_menu(id: &i64) -> (Vec<String>, bool) {
-    let mut btn_menu: Vec<String> = Vec::new();
-    let rb = &mut RB.clone();
-    let count = is_admin(rb, id).await.unwrap_or_default();
-    if count == 1 {
-        for x in Menu::select_all(rb).await.unwrap_or_default() {
-            if let Some(a) = x.api_url {
-                if a != "" {
-                    btn_menu.push(a);
+#[endpoint(tags("登录认证"), summary = "登录第二步之前绑定身份验证器", request_body = MfaChallengeReq, responses((status_code = 200, body = BaseResponse<MfaEnrollResp>)))]
+pub async fn enroll_mfa_by_challenge(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let item = req.parse_json::<MfaChallengeReq>().await?;
+
+    let mfa_config = &state.config.mfa;
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+
+    let user_id = match get_challenge_user(&mut conn, &item.challenge_token).await? {
+        None => return Err(AppError::BusinessError("验证已过期,请重新登录")),
+        Some(x) => x,
+    };
+
+    let rb = &state.rb;
+    let user = match User::select_by_id(rb, user_id).await? {
+        None => return Err(AppError::BusinessError("用户不存在")),
+        Some(x) => x,
+    };
+
+    let resp = new_enrollment(rb, mfa_config, &user).await?;
+    ok_result_data(res, resp)
+}
+
+/// 签发token,保存会话和登录信息
+async fn finish_login(depot: &Depot, conn: &mut StoreConnection, mut s_user: User, agent: UserAgentUtil, client: ClientIp) -> AppResult<UserLoginResp> {
+    let state = app_state(depot)?;
+    let id = s_user.id.unwrap_or_default();
+    let permissions = query_user_permissions(&state.rb, id).await?;
+
+    if permissions.is_empty() {
+        add_login_log(&state.rb, s_user.mobile, 0, "用户没有分配角色或者菜单,不能登录", agent, &client).await;
+        return Err(AppError::BusinessError("用户没有分配角色或者菜单,不能登录"));
+    }
+
+    let session_config = &state.config.session;
+    let session_id = new_session_id();
+    let jwt_config = &state.config.jwt;
+    let jwt_keys = &state.jwt_keys;
+    let token = JwtToken::new(id, &s_user.user_name, &session_id, jwt_config.access_token_expire).create_token(jwt_keys)?;
+
+    let key = user_info_key(id);
+    let now = now_timestamp();
+    deadpool_redis::redis::cmd("HSET")
+        .arg(&key)
+        .arg("permissions")
+        .arg(permissions.api_urls.join(","))
+        .arg("perms")
+        .arg(permissions.perms.join(","))
+        .arg("user_name")
+        .arg(&s_user.user_name)
+        .arg("is_admin")
+        .arg(permissions.is_admin)
+        .arg("last_login")
+        .arg(Local::now().format("%Y-%m-%d %H:%M:%S").to_string())
+        .query_async::<()>(conn)
+        .await?;
+
+    //每个设备一个会话,超出配置的数量时踢掉最早登录的会话
+    let session = Session {
+        session_id: session_id.clone(),
+        user_id: id,
+        user_name: s_user.user_name.clone(),
+        ipaddr: client.ipaddr.clone(),
+        login_location: client.location.clone(),
+        platform: agent.platform.clone(),
+        browser: agent.browser.clone(),
+        os: agent.os.clone(),
+        login_time: now,
+        last_activity: now,
+    };
+    let kicked = create_session(conn, session_config, &session, jwt_config.refresh_token_expire).await?;
+    if !kicked.is_empty() {
+        log::info!("user {} login, kicked sessions: {:?}", id, kicked);
+    }
+    let refresh_token = issue_refresh_token(conn, &session_id, id, jwt_config.refresh_token_expire).await?;
+
+    METRICS.login(true);
+    add_login_log(&state.rb, s_user.mobile.clone(), 1, "登录成功", agent.clone(), &client).await;
+    s_user.login_os = agent.os;
+    s_user.login_browser = agent.browser;
+    s_user.login_ip = client.ipaddr;
+    s_user.login_date = Some(DateTime::now());
+
+    User::update_by_map(&state.rb, &s_user, value! {"id": &s_user.id}).await?;
+    Ok(UserLoginResp {
+        token,
+        refresh_token,
+        expires_in: jwt_config.access_token_expire,
+        recovery_codes: None,
+    })
+}
+
+/*
+ *刷新token
+ */
+#[endpoint(tags("登录认证"), summary = "刷新token", request_body = RefreshTokenReq, responses((status_code = 200, body = BaseResponse<UserLoginResp>)))]
+pub async fn refresh_user_token(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let item = req.parse_json::<RefreshTokenReq>().await?;
+
+    let jwt_config = &state.config.jwt;
+    let jwt_keys = &state.jwt_keys;
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+
+    match rotate_refresh_token(&mut conn, &item.refresh_token, jwt_config.refresh_token_expire).await? {
+        RefreshResult::Invalid => Err(AppError::BusinessError("刷新token无效或已过期")),
+        RefreshResult::Reused(session_id) => {
+            log::error!("refresh token reused, session {} has been revoked", session_id);
+            Err(AppError::BusinessError("刷新token已被使用,请重新登录"))
+        }
+        RefreshResult::Rotated { session_id, user_id, refresh_token } => {
+            let user_name = match User::select_by_id(&state.rb, user_id).await? {
+                Some(user) if user.del_flag == 1 && user.status == 1 => user.user_name,
+                _ => {
+                    remove_session(&mut conn, &session_id).await?;
+                    return Err(AppError::BusinessError("用户不存在或已被禁用"));
                 }
-            }
+            };
+            let token = JwtToken::new(user_id, &user_name, &session_id, jwt_config.access_token_expire).create_token(jwt_keys)?;
+
+            ok_result_data(
+                res,
+                UserLoginResp {
+                    token,
+                    refresh_token,
+                    expires_in: jwt_config.access_token_expire,
+                    recovery_codes: None,
+                },
+            )
         }
+    }
+}
 
-        log::info!("The current user is a super administrator");
-        (btn_menu, true)
-    } else {
-        let sql_str = "select distinct u.api_url from sys_user_role t left join sys_role usr on t.role_id = usr.id left join sys_role_menu srm on usr.id = srm.role_id left join sys_menu u on srm.menu_id = u.id where t.user_id = ?";
-        let btn_menu_map = rb.query_decode::<Vec<HashMap<String, String>>>(sql_str, vec![value!(id)]).await.unwrap_or_default();
-        for x in btn_menu_map {
-            if let Some(a) = x.get("api_url") {
-                if a.to_string() != "" {
-                    btn_menu.push(a.to_string());
-                }
-            }
+/*
+ *退出登录
+ */
+#[endpoint(summary = "退出登录")]
+pub async fn logout(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let user_id = depot.get::<i64>("userId").copied().map_err(|_| AppError::BusinessError("用户ID不能为空"))?;
+    let session_id = depot.get::<String>("sessionId").cloned().map_err(|_| AppError::BusinessError("会话ID不能为空"))?;
+    log::info!("user logout params: {:?}, {:?}", user_id, session_id);
+
+    let user_agent = req.header::<&str>("user-agent").unwrap_or_default();
+    let agent = UserAgentUtil::new(user_agent);
+    let client = client_ip(depot, req);
+
+    //只删除当前设备的会话,当前token立即失效
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    remove_session(&mut conn, &session_id).await?;
+    state.permission_cache.evict(user_id);
+
+    if let Some(user) = User::select_by_id(&state.rb, user_id).await? {
+        add_login_log(&state.rb, user.mobile, 1, "退出登录", agent, &client).await;
+    }
+
+    ok_result(res)
+}
+
+/*
+ *解锁用户(登录失败次数过多被锁定)
+ */
+#[endpoint(summary = "解锁用户", request_body = UnlockUserReq)]
+pub async fn unlock_sys_user(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let item = req.parse_json::<UnlockUserReq>().await?;
+    log::info!("unlock sys_user params: {:?}", &item);
+
+    let user = match User::select_by_id(&state.rb, item.id).await? {
+        None => return Err(AppError::BusinessError("用户不存在")),
+        Some(user) => user,
+    };
+    current_data_scope(depot).await?.check_user(&user)?;
+
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    unlock(&mut conn, &user.mobile, item.ipaddr.as_deref()).await?;
+
+    ok_result(res)
+}
+
+/// 记录登录失败,达到阈值时锁定,否则按失败次数延迟响应
+async fn login_failure(state: &AppState, mobile: String, client: &ClientIp, msg: &'static str, agent: UserAgentUtil) -> AppResult<()> {
+    let config = &state.config.login_guard;
+    let failure = {
+        let mut conn = state.redis.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+        record_failure(&mut conn, config, &mobile, &client.ipaddr).await?
+    };
+
+    match failure.lock {
+        Some(lock) => {
+            let reason = lock.reason();
+            log::warn!("login locked, mobile: {}, ip: {}, reason: {}", mobile, client.ipaddr, reason);
+            add_login_log(&state.rb, mobile, 0, &format!("{},{}", msg, reason), agent, client).await;
+            Err(AppError::LoginLockedError(reason))
+        }
+        None => {
+            add_login_log(&state.rb, mobile, 0, msg, agent, client).await;
+            tokio::time::sleep(failure_delay(config, failure.failures)).await;
+            Err(AppError::BusinessError(msg))
         }
-        log::info!("The current user is not a super administrator");
-        (btn_menu, false)
+    }
+}
+
+/*
+ *添加登录日志
+ *author：刘飞华
+ *date：2025/01/08 13:51:14
+ */
+async fn add_login_log(rb: &RBatis, name: String, status: i8, msg: &str, agent: UserAgentUtil, client: &ClientIp) {
+    //登录失败都会记录登录日志,在这里统计失败次数;成功次数在finish_login中统计(退出登录也会记录成功状态的日志)
+    if status == 0 {
+        METRICS.login(false);
+    }
+
+    let sys_login_log = LoginLog {
+        id: None,                                //访问ID
+        login_name: name,                        //登录账号
+        ipaddr: client.ipaddr.clone(),           //登录IP地址
+        login_location: client.location.clone(), //登录地点
+        platform: agent.platform,                //平台信息
+        browser: agent.browser,                  //浏览器类型
+        version: agent.version,                  //浏览器版本
+        os: agent.os,                            //操作系统
+        arch: agent.arch,                        //体系结构信息
+        engine: agent.engine,                    //渲染引擎信息
+        engine_details: agent.engine_details,    //渲染引擎详细信息
+        extra: agent.extra,                      //其他信息（可选）
+        status,                                  //登录状态(0:失败,1:成功)
+        msg: msg.to_string(),                    //提示消息
+        login_time: None,                        //访问时间
+    };
+
+    match LoginLog::insert(rb, &sys_login_log).await {
+        Ok(_u) => log::info!("add_login_log success: {:?}", sys_login_log),
+        Err(err) => log::error!("add_login_log error params: {:?}, error message: {:?}", sys_login_log, err),
     }
 }
 
@@ -427,15 +792,17 @@ async fn query_btn_menu(id: &i64) -> (Vec<String>, bool) {
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn query_user_role(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "查询用户角色", request_body = QueryUserRoleReq, responses((status_code = 200, body = BaseResponse<QueryUserRoleResp>)))]
+pub async fn query_user_role(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<QueryUserRoleReq>().await?;
     log::info!("query_user_role params: {:?}", item);
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
     let mut user_role_ids: Vec<i64> = Vec::new();
 
     let user_id = item.user_id;
+    check_user_scope(rb, &current_data_scope(depot).await?, &[user_id]).await?;
     for x in UserRole::select_by_map(rb, value! {"user_id": user_id}).await? {
         user_role_ids.push(x.role_id);
     }
@@ -450,8 +817,9 @@ pub async fn query_user_role(req: &mut Request, res: &mut Response) -> AppResult
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
-pub async fn update_user_role(req: &mut Request, res: &mut Response) -> AppResult<()> {
+#[endpoint(summary = "更新用户与角色的关联", request_body = UpdateUserRoleReq)]
+pub async fn update_user_role(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     let item = req.parse_json::<UpdateUserRoleReq>().await?;
     log::info!("update_user_role params: {:?}", item);
 
@@ -463,7 +831,8 @@ pub async fn update_user_role(req: &mut Request, res: &mut Response) -> AppResul
         return Err(AppError::BusinessError("不能修改超级管理员的角色"));
     }
 
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
+    check_user_scope(rb, &current_data_scope(depot).await?, &[user_id]).await?;
 
     UserRole::delete_by_map(rb, value! {"user_id": user_id.clone()}).await?;
 
@@ -478,7 +847,14 @@ pub async fn update_user_role(req: &mut Request, res: &mut Response) -> AppResul
         })
     }
 
-    UserRole::insert_batch(rb, &list, len as u64).await.map(|_| ok_result(res))?
+    UserRole::insert_batch(rb, &list, len as u64).await?;
+
+    //用户的角色变更后权限立即生效,不需要重新登录
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+    refresh_user_permissions(&state.rb, &mut conn, &[user_id]).await?;
+
+    ok_result(res)
 }
 
 /*
@@ -486,8 +862,9 @@ pub async fn update_user_role(req: &mut Request, res: &mut Response) -> AppResul
  *author：刘飞华
  *date：2025/01/08 13:51:14
  */
-#[handler]
+#[endpoint(summary = "查询用户菜单", responses((status_code = 200, body = BaseResponse<QueryUserMenuResp>)))]
 pub async fn query_user_menu(depot: &mut Depot, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
     log::info!("query user menu params {:?}", depot);
     let user_id = depot.get::<i64>("userId").copied().unwrap();
     let user_name = depot.get::<String>("username").unwrap();
@@ -495,7 +872,7 @@ pub async fn query_user_menu(depot: &mut Depot, res: &mut Response) -> AppResult
     log::info!("query user menu params user_name {:?}", user_name);
 
     //根据id查询用户
-    let rb = &mut RB.clone();
+    let rb = &state.rb;
 
     match User::select_by_id(rb, user_id).await? {
         None => Err(AppError::BusinessError("用户不存在")),
@@ -505,17 +882,18 @@ pub async fn query_user_menu(depot: &mut Depot, res: &mut Response) -> AppResult
 
             let sys_menu_list: Vec<Menu>;
 
+            //禁用的角色和菜单不返回
             if count == 1 {
                 log::info!("The current user is a super administrator");
-                sys_menu_list = Menu::select_all(rb).await?;
+                sys_menu_list = Menu::select_enabled_menu(rb).await?;
             } else {
                 log::info!("The current user is not a super administrator");
-                let sql_str = "select u.* from sys_user_role t left join sys_role usr on t.role_id = usr.id left join sys_role_menu srm on usr.id = srm.role_id left join sys_menu u on srm.menu_id = u.id where t.user_id = ?";
-                sys_menu_list = RB.query_decode(sql_str, vec![value!(user.id)]).await?;
+                sys_menu_list = select_menu_by_user_id(rb, &user_id).await?;
             }
 
             let mut sys_menu: Vec<MenuList> = Vec::new();
             let mut btn_menu: Vec<String> = Vec::new();
+            let mut perms: Vec<String> = Vec::new();
             let mut sys_menu_ids: HashSet<i64> = HashSet::new();
 
             for x in sys_menu_list {
@@ -526,6 +904,9 @@ pub async fn query_user_menu(depot: &mut Depot, res: &mut Response) -> AppResult
                 if x.api_url.clone().unwrap_or_default().len() > 0 {
                     btn_menu.push(x.api_url.unwrap_or_default());
                 }
+                if x.perms.clone().unwrap_or_default().len() > 0 {
+                    perms.push(x.perms.unwrap_or_default());
+                }
             }
 
             let mut menu_ids = Vec::new();
@@ -554,6 +935,7 @@ pub async fn query_user_menu(depot: &mut Depot, res: &mut Response) -> AppResult
                 QueryUserMenuResp {
                     sys_menu,
                     btn_menu,
+                    perms,
                     avatar: "https://gw.alipayobjects.com/zos/antfincdn/XAosXuNZyF/BiazfanxmamNRoxxVxka.png".to_string(),
                     name: user.user_name,
                 },
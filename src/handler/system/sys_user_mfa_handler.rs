@@ -0,0 +1,175 @@
+use crate::common::app_state::app_state;
+use crate::common::error::{AppError, AppResult};
+use crate::common::result::{BaseResponse, ok_result, ok_result_data};
+use crate::model::system::sys_user_mfa_model::{count_mfa_required_role, UserMfa};
+use crate::model::system::sys_user_model::User;
+use crate::utils::mfa_util::{generate_recovery_codes, generate_secret, hash_recovery_code, otpauth_uri, render_qr_png, verify_totp, MfaConfig};
+use crate::utils::session_util::now_timestamp;
+use crate::vo::system::sys_user_mfa_vo::*;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rbatis::rbdc::datetime::DateTime;
+use rbatis::RBatis;
+use rbs::value;
+use salvo::prelude::*;
+use salvo::{Request, Response};
+
+/*
+ *查询当前用户的双因素认证状态
+ */
+#[endpoint(summary = "查询当前用户的双因素认证状态", responses((status_code = 200, body = BaseResponse<MfaStatusResp>)))]
+pub async fn query_user_mfa_status(depot: &mut Depot, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let user_id = depot.get::<i64>("userId").copied().map_err(|_| AppError::BusinessError("用户ID不能为空"))?;
+
+    let rb = &state.rb;
+    let mfa = UserMfa::select_by_user_id(rb, user_id).await?.filter(|x| x.status == 1);
+    let required = count_mfa_required_role(rb, user_id).await? > 0;
+
+    ok_result_data(
+        res,
+        MfaStatusResp {
+            enabled: mfa.is_some(),
+            required,
+            recovery_codes: mfa.map(|x| x.recovery_codes.split(',').filter(|c| !c.is_empty()).count()).unwrap_or_default(),
+        },
+    )
+}
+
+/*
+ *绑定身份验证器: 生成密钥和二维码,校验动态码之后才会启用
+ */
+#[endpoint(summary = "绑定身份验证器", responses((status_code = 200, body = BaseResponse<MfaEnrollResp>)))]
+pub async fn enroll_user_mfa(depot: &mut Depot, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let user_id = depot.get::<i64>("userId").copied().map_err(|_| AppError::BusinessError("用户ID不能为空"))?;
+    let mfa_config = &state.config.mfa;
+
+    let rb = &state.rb;
+    let user = match User::select_by_id(rb, user_id).await? {
+        None => return Err(AppError::BusinessError("用户不存在")),
+        Some(x) => x,
+    };
+
+    let resp = new_enrollment(rb, mfa_config, &user).await?;
+    ok_result_data(res, resp)
+}
+
+/*
+ *启用双因素认证: 校验身份验证器中的动态码,返回恢复码
+ */
+#[endpoint(summary = "启用双因素认证", request_body = MfaCodeReq, responses((status_code = 200, body = BaseResponse<MfaActivateResp>)))]
+pub async fn activate_user_mfa(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let item = req.parse_json::<MfaCodeReq>().await?;
+    let user_id = depot.get::<i64>("userId").copied().map_err(|_| AppError::BusinessError("用户ID不能为空"))?;
+    let mfa_config = &state.config.mfa;
+
+    let rb = &state.rb;
+    let mut mfa = match UserMfa::select_by_user_id(rb, user_id).await? {
+        None => return Err(AppError::BusinessError("请先绑定身份验证器")),
+        Some(x) if x.status == 1 => return Err(AppError::BusinessError("已经启用双因素认证")),
+        Some(x) => x,
+    };
+
+    if !verify_mfa_code(mfa_config, &mut mfa, &item.code) {
+        return Err(AppError::BusinessError("动态码不正确"));
+    }
+
+    let recovery_codes = generate_recovery_codes(mfa_config.recovery_codes);
+    mfa.recovery_codes = recovery_codes.iter().map(|x| hash_recovery_code(x)).collect::<Vec<String>>().join(",");
+    mfa.status = 1;
+    mfa.update_time = Some(DateTime::now());
+    UserMfa::update_by_map(rb, &mfa, value! {"id": &mfa.id}).await?;
+
+    ok_result_data(res, MfaActivateResp { recovery_codes })
+}
+
+/*
+ *关闭双因素认证(角色要求双因素认证时不能关闭)
+ */
+#[endpoint(summary = "关闭双因素认证", request_body = MfaCodeReq)]
+pub async fn disable_user_mfa(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let item = req.parse_json::<MfaCodeReq>().await?;
+    let user_id = depot.get::<i64>("userId").copied().map_err(|_| AppError::BusinessError("用户ID不能为空"))?;
+    let mfa_config = &state.config.mfa;
+
+    let rb = &state.rb;
+    if count_mfa_required_role(rb, user_id).await? > 0 {
+        return Err(AppError::BusinessError("角色要求双因素认证,不能关闭"));
+    }
+
+    let mut mfa = match UserMfa::select_by_user_id(rb, user_id).await? {
+        Some(x) if x.status == 1 => x,
+        _ => return Err(AppError::BusinessError("没有启用双因素认证")),
+    };
+
+    if !verify_mfa_code(mfa_config, &mut mfa, &item.code) {
+        return Err(AppError::BusinessError("动态码不正确"));
+    }
+
+    UserMfa::delete_by_map(rb, value! {"id": &mfa.id}).await.map(|_| ok_result(res))?
+}
+
+/*
+ *生成新的密钥(待验证状态),已启用的不能重复绑定
+ */
+pub async fn new_enrollment(rb: &RBatis, config: &MfaConfig, user: &User) -> AppResult<MfaEnrollResp> {
+    let user_id = user.id.unwrap_or_default();
+    let secret = generate_secret();
+
+    match UserMfa::select_by_user_id(rb, user_id).await? {
+        Some(x) if x.status == 1 => return Err(AppError::BusinessError("已经启用双因素认证")),
+        Some(mut x) => {
+            x.secret = secret.clone();
+            x.recovery_codes = "".to_string();
+            x.last_used_step = 0;
+            x.update_time = Some(DateTime::now());
+            UserMfa::update_by_map(rb, &x, value! {"id": &x.id}).await?;
+        }
+        None => {
+            let mfa = UserMfa {
+                id: None,                       //主键
+                user_id,                        //用户id
+                secret: secret.clone(),         //TOTP密钥(Base32)
+                status: 0,                      //状态(0:待验证,1:已启用)
+                recovery_codes: "".to_string(), //恢复码的SHA-256摘要
+                last_used_step: 0,              //最后一次使用的时间步
+                create_time: None,              //创建时间
+                update_time: None,              //修改时间
+            };
+            UserMfa::insert(rb, &mfa).await?;
+        }
+    }
+
+    let uri = otpauth_uri(config, &user.user_name, &secret);
+    let qr_code = format!("data:image/png;base64,{}", STANDARD.encode(render_qr_png(&uri)?));
+    Ok(MfaEnrollResp { secret, otpauth_uri: uri, qr_code })
+}
+
+/*
+ *校验动态码,已启用时也可以使用恢复码(每个恢复码只能使用一次)
+ *校验通过后会更新last_used_step或者recovery_codes,调用方负责保存
+ */
+pub fn verify_mfa_code(config: &MfaConfig, mfa: &mut UserMfa, code: &str) -> bool {
+    if let Some(step) = verify_totp(config, &mfa.secret, code, now_timestamp() as u64, mfa.last_used_step as u64) {
+        mfa.last_used_step = step as i64;
+        return true;
+    }
+
+    if mfa.status != 1 {
+        return false;
+    }
+    let hash = hash_recovery_code(code);
+    let mut codes: Vec<&str> = mfa.recovery_codes.split(',').filter(|x| !x.is_empty()).collect();
+    match codes.iter().position(|x| *x == hash) {
+        None => false,
+        Some(index) => {
+            codes.remove(index);
+            let remaining = codes.join(",");
+            mfa.recovery_codes = remaining;
+            true
+        }
+    }
+}
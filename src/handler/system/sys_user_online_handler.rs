@@ -0,0 +1,65 @@
+use crate::common::app_state::app_state;
+use crate::common::error::{AppError, AppResult};
+use crate::common::result::{ResponsePage, ok_result, ok_result_page};
+use crate::utils::session_util::{list_sessions, remove_session};
+use crate::vo::system::sys_user_online_vo::*;
+use salvo::prelude::*;
+use salvo::{Request, Response};
+
+/*
+ *查询在线用户列表
+ */
+#[endpoint(summary = "查询在线用户列表", request_body = QueryUserOnlineListReq, responses((status_code = 200, body = ResponsePage<Vec<UserOnlineResp>>)))]
+pub async fn query_sys_user_online_list(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let item = req.parse_json::<QueryUserOnlineListReq>().await?;
+    log::info!("query sys_user_online_list params: {:?}", &item);
+
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+
+    let user_name = item.user_name.unwrap_or_default();
+    let ipaddr = item.ipaddr.unwrap_or_default();
+    let list = list_sessions(&mut conn)
+        .await?
+        .into_iter()
+        .filter(|x| user_name.is_empty() || x.user_name == user_name)
+        .filter(|x| ipaddr.is_empty() || x.ipaddr == ipaddr)
+        .collect::<Vec<_>>();
+
+    let total = list.len() as u64;
+    let skip = (item.page_no.max(1) - 1) * item.page_size;
+    let records = list
+        .into_iter()
+        .skip(skip as usize)
+        .take(item.page_size as usize)
+        .map(|x| x.into())
+        .collect::<Vec<UserOnlineResp>>();
+
+    ok_result_page(res, records, total)
+}
+
+/*
+ *强制退出
+ */
+#[endpoint(summary = "强制退出", request_body = ForceLogoutReq)]
+pub async fn force_logout(depot: &mut Depot, req: &mut Request, res: &mut Response) -> AppResult<()> {
+    let state = app_state(depot)?;
+    let item = req.parse_json::<ForceLogoutReq>().await?;
+    log::info!("force_logout params: {:?}", &item);
+
+    if let Ok(session_id) = depot.get::<String>("sessionId") {
+        if item.session_ids.contains(session_id) {
+            return Err(AppError::BusinessError("不能强制退出当前会话"));
+        }
+    }
+
+    let pool = &state.redis;
+    let mut conn = pool.get().await.map_err(|_| AppError::BusinessError("获取redis连接异常"))?;
+
+    for session_id in &item.session_ids {
+        remove_session(&mut conn, session_id).await?;
+    }
+
+    ok_result(res)
+}
@@ -1,19 +1,39 @@
 #[macro_use]
 extern crate rbatis;
 
+use crate::common::app_config::{AppConfig, CliArgs, USAGE};
+use crate::common::app_state::AppState;
+use crate::handler::other::captcha_handler::query_captcha;
+use crate::handler::other::health_handler::{health_live, health_ready};
+use crate::handler::other::jwks_handler::query_jwks;
+use crate::handler::other::metrics_handler::query_metrics;
 use crate::middleware::auth::auth_token;
+use crate::middleware::cors::cors;
+use crate::middleware::metrics::metrics;
+use crate::middleware::operate_log::operate_log;
+use crate::middleware::rate_limit::rate_limit;
+use crate::model::system::sys_user_model::select_password_max_length;
 use crate::routes::{build_other_route, build_system_route};
-use config::{Config, File};
+use crate::utils::db_util::connection_manager;
+use crate::utils::migration_util::{migrate, migrations, rollback, seed, SEEDS};
+use crate::utils::rate_limit_util::RateLimitKey;
+use crate::utils::session_store_util::SessionStore;
+use crate::utils::user_agent_util::USER_AGENT_PARSER;
+use deadpool_redis::PoolConfig;
 use handler::system::sys_user_handler::*;
 use once_cell::sync::Lazy;
 use rbatis::RBatis;
-use rbatis::rbdc::pool::{ConnectionManager, Pool};
-use rbdc_mysql::MysqlDriver;
+use rbatis::rbdc::pool::Pool;
 use rbdc_pool_fast::FastPool;
+use salvo::oapi::security::{Http, HttpAuthScheme, SecurityScheme};
+use salvo::oapi::SecurityRequirement;
 use salvo::prelude::*;
-use serde::Deserialize;
+use salvo::server::ServerHandle;
+use std::sync::Arc;
+use std::time::Duration;
 
 pub mod common;
+pub mod driver;
 pub mod handler;
 pub mod middleware;
 pub mod model;
@@ -21,79 +41,180 @@ pub mod routes;
 pub mod utils;
 pub mod vo;
 
-pub static RB: Lazy<RBatis> = Lazy::new(RBatis::new);
+#[cfg(test)]
+mod tests;
 
 #[handler]
 async fn hello() -> &'static str {
     "Hello World123123"
 }
-// 定义应用状态结构体，包含数据库连接池
-pub struct AppState {
-    pub batis: RBatis,
-}
-
-// 定义配置结构体，包含服务器和数据库配置
-#[derive(Debug, Deserialize)]
-struct Config1 {
-    server: ServerConfig,
-    db: DbConfig,
-    redis: RedisConfig,
-    jwt: JwtConfig,
-}
-
-// 定义服务器配置结构体
-#[derive(Debug, Deserialize)]
-struct ServerConfig {
-    addr: String,
+// 主函数，异步运行
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run().await {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
 }
 
-// 定义数据库配置结构体
-#[derive(Debug, Deserialize)]
-struct DbConfig {
-    url: String,
-}
+async fn run() -> Result<(), String> {
+    // 解析命令行参数
+    let args = CliArgs::parse(std::env::args().skip(1)).map_err(|e| format!("{}\n\n{}", e, USAGE))?;
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
 
-#[derive(Debug, Deserialize)]
-struct RedisConfig {
-    url: String,
-}
+    // 按 默认值 -> 配置文件 -> profile配置文件 -> 环境变量 -> 命令行参数 加载配置,并校验所有配置项
+    let config = AppConfig::load(&args)?;
+    if args.print_config {
+        println!("{}", config.redacted());
+        return Ok(());
+    }
+    if let Err(errors) = config.validate() {
+        return Err(format!("配置校验失败:\n  - {}", errors.join("\n  - ")));
+    }
+    if args.check {
+        println!("配置校验通过");
+        return Ok(());
+    }
 
-#[derive(Debug, Deserialize)]
-struct JwtConfig {
-    secret: String,
-}
-// 主函数，异步运行
-#[tokio::main]
-async fn main() {
     // 初始化日志系统
-    log4rs::init_file("src/config/log4rs.yaml", Default::default()).unwrap();
+    log4rs::init_file(&config.log.config, Default::default()).map_err(|e| format!("初始化日志失败({}): {}", config.log.config, e))?;
     // tracing_subscriber::fmt().init();
+    log::info!("config: {}", config.redacted());
+
+    // 初始化数据库连接,根据连接地址的scheme选择MySQL、PostgreSQL或者SQLite驱动
+    let (db_kind, manager) = connection_manager(config.db.url.as_str()).map_err(|e| format!("创建数据库连接失败: {}", e))?;
+    let pool = FastPool::new(manager).map_err(|e| format!("创建数据库连接池失败: {}", e))?;
+    pool.set_max_open_conns(config.db.max_connections).await;
+    pool.set_timeout(Some(Duration::from_secs(config.db.timeout))).await;
+
+    let rb = RBatis::new();
+    rb.init_pool(pool).map_err(|e| format!("初始化数据库连接池失败: {}", e))?;
 
-    // 加载和解析配置文件
-    let config = Config::builder().add_source(File::with_name("config.toml")).build().unwrap().try_deserialize::<Config1>().unwrap();
-    println!("Config: {:?}", config);
+    // 执行数据库迁移和导入初始数据
+    if args.rollback {
+        match rollback(&rb, migrations(db_kind)).await? {
+            Some(version) => println!("已回滚迁移V{}", version),
+            None => println!("没有可以回滚的迁移"),
+        }
+        return Ok(());
+    }
+    if args.migrate || config.db.auto_migrate {
+        let versions = migrate(&rb, migrations(db_kind)).await?;
+        let tables = seed(&rb, SEEDS).await?;
+        log::info!("migrate versions: {:?}, seed tables: {:?}", versions, tables);
+        if args.migrate {
+            println!("已执行迁移: {:?}, 已导入初始数据: {:?}", versions, tables);
+            return Ok(());
+        }
+    }
 
-    // 初始化数据库连接
-    let manager = ConnectionManager::new(MysqlDriver {}, config.db.url.as_str()).expect("create connection manager error");
-    let pool = FastPool::new(manager).expect("create db pool error");
+    // 初始化redis连接池
+    let mut redis_config = deadpool_redis::Config::from_url(config.redis.url.as_str());
+    let mut redis_pool_config = PoolConfig::new(config.redis.pool_size);
+    redis_pool_config.timeouts.wait = Some(Duration::from_secs(config.redis.timeout));
+    redis_pool_config.timeouts.create = Some(Duration::from_secs(config.redis.timeout));
+    redis_config.pool = Some(redis_pool_config);
+    let redis_pool = redis_config.create_pool(Some(deadpool_redis::Runtime::Tokio1)).map_err(|e| format!("创建redis连接池失败: {}", e))?;
 
-    RB.init_pool(pool).expect("init db pool error");
+    // 加载User-Agent解析规则,规则有误时启动失败
+    Lazy::force(&USER_AGENT_PARSER);
+
+    // 加载jwt签名密钥、受信任的代理和IP归属地离线库,启动操作日志的后台写入任务
+    let mut state = AppState::new(rb, SessionStore::Redis(redis_pool), config.clone())?;
+
+    // 还没有执行V2迁移的数据库password是varchar(64),保存Argon2id密文会失败或者被截断,启动时检查一次,字段太短时登录后不重新加密
+    let password_length = select_password_max_length(&state.rb).await.map_err(|e| format!("查询sys_user.password字段长度失败: {}", e))?;
+    let hash_length = config.password.hash("password").map_err(|e| e.to_string())?.len() as u64;
+    if password_length.is_some_and(|x| hash_length > x) {
+        log::warn!("sys_user.password is too short to store argon2id hash, run migrations first");
+        state.password_rehash = false;
+    }
+    let state = Arc::new(state);
 
     // 创建TCP监听器并启动服务器
-    let acceptor = TcpListener::new(config.server.addr).bind().await;
-    Server::new(acceptor).serve(route(config.redis.url.as_str(), config.jwt.secret)).await;
+    let acceptor = TcpListener::new(config.server.addr.clone()).try_bind().await.map_err(|e| format!("监听{}失败: {}", config.server.addr, e))?;
+    let service = Service::new(route(state.clone())).hoop(metrics).hoop(cors(config.cors));
+    let server = Server::new(acceptor);
+    let shutdown_timeout = Duration::from_secs(config.server.shutdown_timeout);
+    tokio::spawn(shutdown_signal(server.handle(), shutdown_timeout));
+    server.serve(service).await;
+
+    // 服务器已经停止,写完队列中剩余的操作日志
+    state.operate_log_writer.shutdown(shutdown_timeout).await;
+    log::info!("server stopped");
+    Ok(())
 }
 
-// 定义路由配置函数
-fn route(url: &str, secret: String) -> Router {
-    let cfg = deadpool_redis::Config::from_url(url);
-    let pool = cfg.create_pool(Some(deadpool_redis::Runtime::Tokio1)).unwrap();
+// 收到Ctrl+C或者SIGTERM(docker stop)后停止接收新的连接,等待正在处理的请求完成
+async fn shutdown_signal(handle: ServerHandle, timeout: Duration) {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            log::error!("listen ctrl_c signal error: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                log::error!("listen terminate signal error: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    log::info!("shutdown signal received, waiting at most {:?} for in-flight requests", timeout);
+    handle.stop_graceful(timeout);
+}
 
+// 定义路由配置函数
+fn route(state: Arc<AppState>) -> Router {
     // 创建路由实例，配置API路径和处理函数
-    Router::new()
-        .hoop(affix_state::insert("pool", pool).insert("secret", secret))
-        .path("/api")
-        .get(hello)
-        .push(Router::new().path("/system/user/login").post(login))
-        .push(Router::new().hoop(auth_token).push(build_system_route()).push(build_other_route()))
+    let router = Router::new()
+        .hoop(affix_state::inject(state))
+        .hoop(rate_limit(&[RateLimitKey::Ip, RateLimitKey::Route]))
+        .push(Router::new().path("/health/live").get(health_live))
+        .push(Router::new().path("/health/ready").get(health_ready))
+        .push(Router::new().path("/metrics").get(query_metrics))
+        .push(Router::new().path("/.well-known/jwks.json").get(query_jwks))
+        .push(
+            Router::new()
+                .path("/api")
+                .get(hello)
+                .push(Router::new().path("/captcha").get(query_captcha))
+                .push(Router::new().path("/system/user/login").post(login))
+                .push(Router::new().path("/system/user/loginMfa").post(login_mfa))
+                .push(Router::new().path("/system/user/mfa/enrollByChallenge").post(enroll_mfa_by_challenge))
+                .push(Router::new().path("/system/user/refreshToken").post(refresh_user_token))
+                .push(
+                    Router::new()
+                        .oapi_security(SecurityRequirement::new("bearerAuth", Vec::<String>::new()))
+                        .hoop(auth_token)
+                        .hoop(rate_limit(&[RateLimitKey::User]))
+                        .hoop(operate_log)
+                        .push(build_system_route())
+                        .push(build_other_route()),
+                ),
+        );
+
+    // 接口文档由handler上的#[endpoint]生成,需要token的接口使用bearerAuth
+    let doc = OpenApi::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+        .add_security_scheme("bearerAuth", SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer).bearer_format("JWT")))
+        .merge_router(&router);
+    router
+        .push(doc.into_router("/api/openapi.json"))
+        .push(SwaggerUi::new("/api/openapi.json").into_router("/api/swagger-ui"))
 }
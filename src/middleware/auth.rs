@@ -1,15 +1,24 @@
+use crate::common::app_state::app_state;
 use crate::common::result::BaseResponse;
 use crate::utils::jwt_util::JwtToken;
+use crate::utils::metrics_util::METRICS;
+use crate::utils::permission_util::PermissionSet;
+use crate::utils::session_util::{get_session, touch_session, user_info_key};
 use salvo::prelude::*;
 use salvo::{Depot, FlowCtrl, Request, Response};
 use std::collections::HashMap;
+use std::sync::Arc;
 
+/*
+ *认证: 校验token和会话,把当前用户和用户的权限放入Depot
+ *接口的权限由路由上的require_perm校验,没有require_perm的接口登录后即可访问
+ */
 #[handler]
 pub async fn auth_token(req: &mut Request, res: &mut Response, ctrl: &mut FlowCtrl, depot: &mut Depot) {
     let item = match req.parse_headers::<HashMap<String, String>>() {
         Ok(item) => item,
         Err(e) => {
-            return er_res(res, ctrl, e.to_string().as_str());
+            return er_res(res, ctrl, "invalid_header", e.to_string().as_str());
         }
     };
 
@@ -18,65 +27,57 @@ pub async fn auth_token(req: &mut Request, res: &mut Response, ctrl: &mut FlowCt
     log::info!("req url:{}", path);
 
     match authorization {
-        None => er_res(res, ctrl, "token不能为空"),
+        None => er_res(res, ctrl, "missing_token", "token不能为空"),
         Some(token) => {
             let split_vec = token.split_whitespace().collect::<Vec<_>>();
             if split_vec.len() != 2 || split_vec[0] != "Bearer" {
-                return er_res(res, ctrl, "token格式错误");
+                return er_res(res, ctrl, "malformed_token", "token格式错误");
             }
             let token = split_vec[1];
 
-            match depot.get::<String>("secret") {
-                Ok(secret) => {
-                    let jwt_token_e = JwtToken::verify(secret, &token);
+            match app_state(depot) {
+                Ok(state) => {
+                    let jwt_token_e = JwtToken::verify(&state.jwt_keys, token);
                     let jwt_token = match jwt_token_e {
                         Ok(data) => data,
                         Err(err) => {
-                            return er_res(res, ctrl, err.to_string().as_str());
+                            return er_res(res, ctrl, "invalid_token", err.to_string().as_str());
                         }
                     };
 
-                    if let Ok(pool) = depot.get::<deadpool_redis::Pool>("pool") {
-                        if let Ok(mut conn) = pool.get().await {
-                            let key = format!("salvo:admin:user:info:{:?}", jwt_token.id);
-                            let values: HashMap<String, String> = deadpool_redis::redis::cmd("HGETALL").arg(key).query_async(&mut conn).await.unwrap_or_default();
-                            let token_1 = values.get("token").cloned().unwrap_or_default();
-                            if token != token_1 {
-                                return er_res(res, ctrl, "无效的token");
-                            }
-                            let permissions_str = values.get("permissions").cloned().unwrap_or_default();
-                            let permissions: Vec<String> = if permissions_str.is_empty() {
-                                Vec::new()
-                            } else {
-                                permissions_str.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
-                            };
+                    if let Ok(mut conn) = state.redis.get().await {
+                        //会话不存在(已退出、被踢下线或者过期)时token无效
+                        let session_id = jwt_token.session_id().to_string();
+                        match get_session(&mut conn, &session_id).await {
+                            Ok(Some(session)) if session.user_id == jwt_token.id => {}
+                            _ => return er_res(res, ctrl, "session_not_found", "无效的token"),
+                        }
+                        if let Err(err) = touch_session(&mut conn, &session_id).await {
+                            log::error!("touch session error: {:?}", err);
+                        }
 
-                            if permissions.len() == 0 {
-                                return er_res(res, ctrl, format!("你没有权限访问: {}", path).as_str());
-                            }
-                            let is_admin = values.get("is_admin").map(|v| v == "1").unwrap_or(false);
+                        let key = user_info_key(jwt_token.id);
+                        let values: HashMap<String, String> = deadpool_redis::redis::cmd("HGETALL").arg(key).query_async(&mut conn).await.unwrap_or_default();
+                        let value = |field: &str| values.get(field).map(String::as_str).unwrap_or_default();
+                        let permissions = state.permission_cache.permission_set(jwt_token.id, value("is_admin") == "1", value("permissions"), value("perms"));
 
-                            if is_admin || has_permission(&permissions, path.as_str()) {
-                                depot.insert("userId", jwt_token.id.clone());
-                                depot.insert("username", jwt_token.username.clone());
-                            } else {
-                                log::error!("你没有权限访问: {:?}", path);
-                                er_res(res, ctrl, format!("你没有权限访问: {}", path).as_str())
-                            }
-                        } else {
-                            er_res(res, ctrl, "获取redis conn失败")
-                        }
+                        depot.insert("userId", jwt_token.id);
+                        depot.insert("username", jwt_token.username.clone());
+                        depot.insert("sessionId", session_id);
+                        depot.inject(permissions);
                     } else {
-                        er_res(res, ctrl, "获取redis pool异常")
+                        er_res(res, ctrl, "redis_unavailable", "获取redis conn失败")
                     }
                 }
-                Err(_) => er_res(res, ctrl, "获取jwt密钥异常"),
+                Err(_) => er_res(res, ctrl, "state_unavailable", "获取应用状态异常"),
             }
         }
     }
 }
 
-fn er_res(res: &mut Response, ctrl: &mut FlowCtrl, msg: &str) {
+/// reason: 认证失败的原因,用于监控指标auth_failures_total
+fn er_res(res: &mut Response, ctrl: &mut FlowCtrl, reason: &str, msg: &str) {
+    METRICS.auth_failure(reason);
     let resp = BaseResponse {
         msg: msg.to_string(),
         code: 1,
@@ -86,6 +87,31 @@ fn er_res(res: &mut Response, ctrl: &mut FlowCtrl, msg: &str) {
     res.render(Json(resp));
 }
 
-fn has_permission(permissions: &[String], path: &str) -> bool {
-    permissions.iter().any(|permission| permission == path)
+/*
+ *授权: 校验当前用户是否拥有权限标识,在routes中按接口挂载,例如:
+ *Router::new().path("/system/user/addUser").hoop(require_perm("system:user:add")).post(add_sys_user)
+ */
+pub fn require_perm(perm: &'static str) -> RequirePerm {
+    RequirePerm { perm }
+}
+
+pub struct RequirePerm {
+    perm: &'static str, //权限标识
+}
+
+#[handler]
+impl RequirePerm {
+    async fn handle(&self, req: &mut Request, res: &mut Response, ctrl: &mut FlowCtrl, depot: &mut Depot) {
+        let path = req.uri().path().to_string();
+        //操作日志根据权限标识推断业务类型和模块标题
+        depot.insert("perm", self.perm);
+        match depot.obtain::<Arc<PermissionSet>>() {
+            Ok(permissions) if permissions.allows(self.perm, req.method().as_str(), &path) => {}
+            Ok(_) => {
+                log::error!("你没有权限访问: {:?}, 需要权限: {}", path, self.perm);
+                er_res(res, ctrl, "permission_denied", format!("你没有权限访问: {}", path).as_str())
+            }
+            Err(_) => er_res(res, ctrl, "missing_token", "token不能为空"),
+        }
+    }
 }
@@ -0,0 +1,96 @@
+use salvo::http::header::{self, HeaderValue};
+use salvo::http::{Method, StatusCode};
+use salvo::prelude::*;
+use salvo::{Depot, FlowCtrl, Request, Response};
+use serde::{Deserialize, Serialize};
+
+/*
+ *跨域配置
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorsConfig {
+    pub enabled: bool,               //是否允许跨域访问
+    pub allow_origins: Vec<String>,  //允许的来源,*表示任意来源
+    pub allow_methods: Vec<String>,  //允许的请求方法
+    pub allow_headers: Vec<String>,  //允许的请求头
+    pub expose_headers: Vec<String>, //前端可以读取的响应头
+    pub allow_credentials: bool,     //是否允许携带cookie,为true时allow_origins不能为*
+    pub max_age: u64,                //预检请求的缓存时间(秒)
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            enabled: false,
+            allow_origins: vec!["*".to_string()],
+            allow_methods: ["GET", "POST", "PUT", "DELETE", "OPTIONS"].iter().map(|x| x.to_string()).collect(),
+            allow_headers: ["Authorization", "Content-Type"].iter().map(|x| x.to_string()).collect(),
+            expose_headers: ["retry-after", "x-ratelimit-limit", "x-ratelimit-remaining"].iter().map(|x| x.to_string()).collect(),
+            allow_credentials: false,
+            max_age: 3600,
+        }
+    }
+}
+
+impl CorsConfig {
+    fn allow_any_origin(&self) -> bool {
+        self.allow_origins.iter().any(|x| x == "*")
+    }
+
+    fn is_allowed(&self, origin: &str) -> bool {
+        self.allow_any_origin() || self.allow_origins.iter().any(|x| x.eq_ignore_ascii_case(origin))
+    }
+}
+
+/*
+ *跨域: 在Service上执行,没有匹配到路由的OPTIONS预检请求也能处理
+ */
+pub fn cors(config: CorsConfig) -> Cors {
+    Cors { config }
+}
+
+pub struct Cors {
+    config: CorsConfig, //跨域配置
+}
+
+#[handler]
+impl Cors {
+    async fn handle(&self, req: &mut Request, res: &mut Response, ctrl: &mut FlowCtrl, depot: &mut Depot) {
+        let origin = match req.header::<String>(header::ORIGIN) {
+            Some(origin) if self.config.enabled && self.config.is_allowed(&origin) => origin,
+            _ => return,
+        };
+
+        let allow_origin = if self.config.allow_any_origin() && !self.config.allow_credentials { "*".to_string() } else { origin };
+        let headers = res.headers_mut();
+        if let Ok(value) = HeaderValue::from_str(&allow_origin) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, value);
+        }
+        headers.append(header::VARY, HeaderValue::from_static("Origin"));
+        if self.config.allow_credentials {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+        }
+        if !self.config.expose_headers.is_empty() {
+            if let Ok(value) = HeaderValue::from_str(&self.config.expose_headers.join(", ")) {
+                headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, value);
+            }
+        }
+
+        let preflight = *req.method() == Method::OPTIONS && req.headers().contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+        if !preflight {
+            ctrl.call_next(req, depot, res).await;
+            return;
+        }
+
+        if let Ok(value) = HeaderValue::from_str(&self.config.allow_methods.join(", ")) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, value);
+        }
+        if let Ok(value) = HeaderValue::from_str(&self.config.allow_headers.join(", ")) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, value);
+        }
+        headers.insert(header::ACCESS_CONTROL_MAX_AGE, HeaderValue::from(self.config.max_age));
+        res.status_code(StatusCode::NO_CONTENT);
+        ctrl.skip_rest();
+    }
+}
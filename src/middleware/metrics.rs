@@ -0,0 +1,17 @@
+use crate::utils::metrics_util::METRICS;
+use salvo::http::StatusCode;
+use salvo::prelude::*;
+use salvo::{Depot, FlowCtrl, Request, Response};
+use std::time::Instant;
+
+/*
+ *请求监控: 按接口和响应状态统计请求数和耗时,在Service上执行,没有匹配到路由的请求也会统计
+ */
+#[handler]
+pub async fn metrics(req: &mut Request, res: &mut Response, ctrl: &mut FlowCtrl, depot: &mut Depot) {
+    let start = Instant::now();
+    ctrl.call_next(req, depot, res).await;
+
+    let status = res.status_code.unwrap_or(StatusCode::OK).as_u16();
+    METRICS.observe_request(req.method().as_str(), req.uri().path(), status, start.elapsed().as_secs_f64());
+}
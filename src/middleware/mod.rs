@@ -1 +1,5 @@
 pub mod auth;
+pub mod cors;
+pub mod metrics;
+pub mod operate_log;
+pub mod rate_limit;
@@ -0,0 +1,85 @@
+use crate::common::app_state::app_state;
+use crate::utils::ip_util::client_ip;
+use crate::utils::operate_log_util::{build_operate_log, business_type, is_mutating, redact, OperateRecord};
+use salvo::http::header::CONTENT_LENGTH;
+use salvo::http::{mime, ResBody};
+use salvo::prelude::*;
+use salvo::{Depot, FlowCtrl, Request, Response};
+use serde_json::Value;
+use std::time::Instant;
+
+//只记录不超过64KB的请求体,更大的请求体(比如导入文件)不读取
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/*
+ *操作日志: 记录新增、修改、删除类的请求
+ *业务类型根据路由上require_perm的权限标识推断,模块标题和部门名称由后台写入任务补全
+ */
+#[handler]
+pub async fn operate_log(req: &mut Request, res: &mut Response, ctrl: &mut FlowCtrl, depot: &mut Depot) {
+    let writer = match app_state(depot) {
+        Ok(state) if state.operate_log_writer.config.enabled => state.operate_log_writer.clone(),
+        _ => return,
+    };
+
+    let start = Instant::now();
+    let body = read_body(req).await;
+
+    ctrl.call_next(req, depot, res).await;
+
+    let method = req.method().as_str().to_string();
+    let perm = depot.get::<&'static str>("perm").copied().unwrap_or_default();
+    let business_type = business_type(perm, &method);
+    if !is_mutating(business_type) {
+        return;
+    }
+
+    let user_id = depot.get::<i64>("userId").copied().unwrap_or_default();
+    let operate_name = depot.get::<String>("username").cloned().unwrap_or_default();
+    let client = client_ip(depot, req);
+    let params = if body.is_empty() { req.uri().query().unwrap_or_default().to_string() } else { body };
+
+    let result = match &res.body {
+        ResBody::Once(bytes) => String::from_utf8_lossy(bytes).to_string(),
+        _ => "".to_string(),
+    };
+    let error_msg = response_error(depot, &result);
+
+    let log = build_operate_log(
+        business_type,
+        perm,
+        &method,
+        &operate_name,
+        req.uri().path(),
+        &client.ipaddr,
+        &client.location,
+        &redact(&params, &writer.config.sensitive_fields),
+        &redact(&result, &writer.config.sensitive_fields),
+        error_msg.as_deref(),
+        start.elapsed().as_millis() as i64,
+    );
+    writer.send(OperateRecord { log, user_id, perm: perm.to_string() });
+}
+
+/// 读取JSON或者表单格式的请求体,请求体会被缓存,后面的处理函数仍然可以解析
+/// 其它格式或者没有Content-Length、超过MAX_BODY_SIZE的请求体不读取,避免每个请求都把请求体读进内存
+async fn read_body(req: &mut Request) -> String {
+    let readable = req.content_type().is_some_and(|x| x.subtype() == mime::JSON || x.subtype() == mime::WWW_FORM_URLENCODED);
+    let length = req.headers().get(CONTENT_LENGTH).and_then(|x| x.to_str().ok()).and_then(|x| x.parse::<usize>().ok());
+    if !readable || length.is_none_or(|x| x > MAX_BODY_SIZE) {
+        return "".to_string();
+    }
+    req.payload().await.map(|x| String::from_utf8_lossy(x).to_string()).unwrap_or_default()
+}
+
+/// 处理函数返回的错误(AppError)或者响应中code不为0时视为操作失败
+fn response_error(depot: &Depot, result: &str) -> Option<String> {
+    if let Ok(msg) = depot.get::<String>("errorMsg") {
+        return Some(msg.clone());
+    }
+    let value = serde_json::from_str::<Value>(result).ok()?;
+    match value.get("code").and_then(Value::as_i64) {
+        Some(0) | None => None,
+        Some(_) => Some(value.get("msg").and_then(Value::as_str).unwrap_or_default().to_string()),
+    }
+}
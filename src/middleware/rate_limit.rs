@@ -0,0 +1,58 @@
+use crate::common::app_state::app_state;
+use crate::common::result::BaseResponse;
+use crate::utils::ip_util::client_ip;
+use crate::utils::rate_limit_util::RateLimitKey;
+use salvo::http::StatusCode;
+use salvo::prelude::*;
+use salvo::{Depot, FlowCtrl, Request, Response};
+
+/*
+ *限流: 按配置的规则计数,超过限制时返回429和Retry-After
+ *按IP和接口计数的规则在认证之前执行(登录接口也需要限流),按用户计数的规则在认证之后执行
+ */
+pub fn rate_limit(keys: &'static [RateLimitKey]) -> RateLimit {
+    RateLimit { keys }
+}
+
+pub struct RateLimit {
+    keys: &'static [RateLimitKey], //本次执行的计数维度
+}
+
+#[handler]
+impl RateLimit {
+    async fn handle(&self, req: &mut Request, res: &mut Response, ctrl: &mut FlowCtrl, depot: &mut Depot) {
+        let limiter = match app_state(depot) {
+            Ok(state) => state.rate_limiter.clone(),
+            Err(_) => return,
+        };
+        let method = req.method().as_str().to_string();
+        let path = req.uri().path().to_string();
+
+        for rule in limiter.matched_rules(self.keys, &method, &path) {
+            let identity = match rule.key {
+                RateLimitKey::Ip => client_ip(depot, req).ipaddr,
+                RateLimitKey::User => match depot.get::<i64>("userId") {
+                    Ok(user_id) => user_id.to_string(),
+                    Err(_) => continue,
+                },
+                RateLimitKey::Route => format!("{} {}", method, path),
+            };
+
+            let decision = limiter.check(rule, &identity).await;
+            let _ = res.add_header("x-ratelimit-limit", decision.limit, true);
+            let _ = res.add_header("x-ratelimit-remaining", decision.remaining, true);
+            if !decision.allowed {
+                log::warn!("rate limit {} exceeded, identity: {}, path: {}", rule.name, identity, path);
+                let _ = res.add_header("retry-after", decision.retry_after, true);
+                res.status_code(StatusCode::TOO_MANY_REQUESTS);
+                res.render(Json(BaseResponse {
+                    msg: format!("请求过于频繁,请{}秒后再试", decision.retry_after),
+                    code: 1,
+                    data: Some("None"),
+                }));
+                ctrl.skip_rest();
+                return;
+            }
+        }
+    }
+}
@@ -9,6 +9,7 @@ pub mod sys_post_model;
 pub mod sys_role_dept_model;
 pub mod sys_role_menu_model;
 pub mod sys_role_model;
+pub mod sys_user_mfa_model;
 pub mod sys_user_model;
 pub mod sys_user_post_model;
 pub mod sys_user_role_model;
@@ -2,6 +2,7 @@
 // author：刘飞华
 // createTime：2024/12/25 10:01:11
 
+use crate::utils::data_scope_util::DataScope;
 use crate::vo::system::sys_dept_vo::DeptReq;
 use crate::vo::system::sys_dept_vo::DeptResp;
 use crate::vo::system::sys_dept_vo::QueryDeptListReq;
@@ -101,12 +102,17 @@ impl_select_page!(Dept{select_page() =>"
  *author：刘飞华
  *date：2024/12/25 10:01:11
  */
-impl_select!(Dept{select_page_dept_list(req:&QueryDeptListReq) =>"
+impl_select!(Dept{select_page_dept_list(req:&QueryDeptListReq, scope:&DataScope) =>"
     where 1=1
-     if req.dept_name != null && req.dept_name != '':
-      ` and dept_name = #{req.dept_name} `
+     if req.deptName != null && req.deptName != '':
+      ` and dept_name = #{req.deptName} `
      if req.status != 2:
-      ` and status = #req.{status} `
+      ` and status = #{req.status} `
+     if scope.all == false:
+      ` and (1 = 0 `
+      for _,dept_id in scope.dept_ids:
+        ` or id = #{dept_id} `
+      ` ) `
      if !sql.contains('count'):
       ` order by sort"
 },"sys_dept");
@@ -116,7 +122,7 @@ impl_select!(Dept{select_page_dept_list(req:&QueryDeptListReq) =>"
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-#[sql("select count(*) from sys_dept where status = 1 and del_flag = '1' and find_in_set(?, ancestors)")]
+#[sql("select count(*) from sys_dept where status = 1 and del_flag = '1' and find_in_set(?, ancestors) > 0")]
 pub async fn select_normal_children_dept_by_id(rb: &RBatis, id: &i64) -> rbatis::Result<i64> {
     impled!()
 }
@@ -146,7 +152,7 @@ pub async fn check_dept_exist_user(rb: &RBatis, id: &i64) -> rbatis::Result<i64>
  * author：刘飞华
  * date：2025/1/6 11:29
  */
-#[sql("select * from sys_dept where find_in_set(?, ancestors)")]
+#[sql("select * from sys_dept where find_in_set(?, ancestors) > 0")]
 pub async fn select_children_dept_by_id(rb: &RBatis, id: &i64) -> rbatis::Result<Vec<Dept>> {
     impled!()
 }
@@ -2,9 +2,11 @@
 // author：刘飞华
 // createTime：2024/12/25 10:01:11
 
+use crate::utils::db_util::DbKind;
 use crate::vo::system::sys_login_log_vo::LoginLogResp;
 use crate::vo::system::sys_login_log_vo::QueryLoginLogListReq;
 use rbatis::rbdc::datetime::DateTime;
+use rbatis::rbdc::db::ExecResult;
 use rbatis::RBatis;
 use serde::{Deserialize, Serialize};
 /*
@@ -83,8 +85,8 @@ impl_select_page!(LoginLog{select_page() =>"
  */
 impl_select_page!(LoginLog{select_login_log_list(req:&QueryLoginLogListReq) =>"
     where 1=1
-     if req.login_name != '' && req.login_name != null:
-       ` and login_name = #{req.login_name} `
+     if req.loginName != '' && req.loginName != null:
+       ` and login_name = #{req.loginName} `
      if req.ipaddr != '' && req.ipaddr != null:
        ` and ipaddr = #{req.ipaddr} `
      if req.browser != '' && req.browser != null:
@@ -102,7 +104,7 @@ impl_select_page!(LoginLog{select_login_log_list(req:&QueryLoginLogListReq) =>"
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-#[sql("truncate table sys_login_log")]
-pub async fn clean_login_log(rb: &RBatis) -> Option<i64> {
-    impled!()
+pub async fn clean_login_log(rb: &RBatis) -> rbatis::Result<ExecResult> {
+    let kind = DbKind::from_driver(rb.driver_type()?).unwrap_or(DbKind::Mysql);
+    rb.exec(&kind.truncate_sql("sys_login_log"), vec![]).await
 }
@@ -23,6 +23,7 @@ pub struct Menu {
     pub parent_id: Option<i64>,        //父ID
     pub menu_url: Option<String>,      //路由路径
     pub api_url: Option<String>,       //接口URL
+    pub perms: Option<String>,         //权限标识
     pub menu_icon: Option<String>,     //菜单图标
     pub remark: Option<String>,        //备注
     pub create_time: Option<DateTime>, //创建时间
@@ -40,6 +41,7 @@ impl From<MenuReq> for Menu {
             parent_id: item.parent_id, //父ID
             menu_url: item.menu_url,   //路由路径
             api_url: item.api_url,     //接口URL
+            perms: item.perms,         //权限标识
             menu_icon: item.menu_icon, //菜单图标
             remark: item.remark,       //备注
             create_time: None,         //创建时间
@@ -60,6 +62,7 @@ impl Into<MenuResp> for Menu {
             parent_id: self.parent_id,     //父ID
             menu_url: self.menu_url,       //路由路径
             api_url: self.api_url,         //接口URL
+            perms: self.perms,             //权限标识
             menu_icon: self.menu_icon,     //菜单图标
             remark: self.remark,           //备注
             create_time: self.create_time, //创建时间
@@ -118,3 +121,16 @@ pub async fn select_count_menu_by_parent_id(rb: &RBatis, parent_id: &i64) -> rba
  *date：2025/01/04 22:24:01
  */
 impl_select!(Menu{select_menu_list() -> Vec => "`where menu_type != 3 and status = 1`"}, "sys_menu");
+
+/*
+ *查询所有启用的菜单
+ */
+impl_select!(Menu{select_enabled_menu() -> Vec => "`where status = 1`"}, "sys_menu");
+
+/*
+ *查询用户通过启用的角色获得的启用菜单(禁用的角色和菜单不授予权限)
+ */
+pub async fn select_menu_by_user_id(rb: &RBatis, user_id: &i64) -> rbatis::Result<Vec<Menu>> {
+    let sql = "select distinct m.* from sys_user_role ur join sys_role r on ur.role_id = r.id join sys_role_menu rm on r.id = rm.role_id join sys_menu m on rm.menu_id = m.id where ur.user_id = ? and r.status = 1 and m.status = 1";
+    rb.query_decode(sql, vec![(*user_id).into()]).await
+}
@@ -83,10 +83,10 @@ impl_select!(Notice{select_by_title(title:&str) -> Option => "`where notice_titl
  */
 impl_select_page!(Notice{select_sys_notice_list(req:&QueryNoticeListReq) =>"
     where 1=1
-     if req.notice_title != '' && req.notice_title != null:
-       ` and notice_title = #{req.notice_title} `
-     if req.notice_type != 0:
-      ` and notice_type = #{req.notice_type} `
+     if req.noticeTitle != '' && req.noticeTitle != null:
+       ` and notice_title = #{req.noticeTitle} `
+     if req.noticeType != 0:
+      ` and notice_type = #{req.noticeType} `
      if req.status != 2:
        ` and status = #{req.status} `
      if !sql.contains('count'):
@@ -94,13 +94,13 @@ impl_select_page!(Notice{select_sys_notice_list(req:&QueryNoticeListReq) =>"
 },"sys_notice");
 
 impl Notice {
-    pub async fn exists_by_title(rb: &mut RBatis, title: &str) -> Result<bool, Error> {
+    pub async fn exists_by_title(rb: &RBatis, title: &str) -> Result<bool, Error> {
         let sql = "SELECT COUNT(*) FROM sys_notice WHERE notice_title = ?";
         let count: i64 = rb.query_decode(sql, vec![title.into()]).await?;
         Ok(count > 0)
     }
 
-    pub async fn exists_by_title_except_id(rb: &mut RBatis, title: &str, id: i64) -> Result<bool, Error> {
+    pub async fn exists_by_title_except_id(rb: &RBatis, title: &str, id: i64) -> Result<bool, Error> {
         let sql = "SELECT COUNT(*) FROM sys_notice WHERE notice_title = ? and id != ?";
         let count: i64 = rb.query_decode(sql, vec![title.into(), id.into()]).await?;
         Ok(count > 0)
@@ -5,6 +5,7 @@ use crate::vo::system::sys_role_vo::QueryRoleListReq;
 use crate::vo::system::sys_role_vo::RoleReq;
 use crate::vo::system::sys_role_vo::RoleResp;
 use rbatis::rbdc::datetime::DateTime;
+use rbatis::RBatis;
 use serde::{Deserialize, Serialize};
 /*
  *角色信息
@@ -18,6 +19,7 @@ pub struct Role {
     pub role_key: String,              //角色权限字符串
     pub data_scope: i8,                //数据范围（1：全部数据权限 2：自定数据权限 3：本部门数据权限 4：本部门及以下数据权限）
     pub status: i8,                    //状态(1:正常，0:禁用)
+    pub mfa_required: i8,              //是否要求双因素认证(0:否,1:是)
     pub remark: Option<String>,        //备注
     pub del_flag: Option<i8>,          //删除标志（0代表删除 1代表存在）
     pub create_time: Option<DateTime>, //创建时间
@@ -33,15 +35,16 @@ rbatis::crud!(Role {}, "sys_role");
 impl From<RoleReq> for Role {
     fn from(item: RoleReq) -> Self {
         Role {
-            id: item.id,                 //主键
-            role_name: item.role_name,   //名称
-            role_key: item.role_key,     //角色权限字符串
-            data_scope: item.data_scope, //数据范围（1：全部数据权限 2：自定数据权限 3：本部门数据权限 4：本部门及以下数据权限）
-            status: item.status,         //状态(1:正常，0:禁用)
-            remark: item.remark,         //备注
-            del_flag: None,              //删除标志（0代表删除 1代表存在）
-            create_time: None,           //创建时间
-            update_time: None,           //修改时间
+            id: item.id,                     //主键
+            role_name: item.role_name,       //名称
+            role_key: item.role_key,         //角色权限字符串
+            data_scope: item.data_scope,     //数据范围（1：全部数据权限 2：自定数据权限 3：本部门数据权限 4：本部门及以下数据权限）
+            status: item.status,             //状态(1:正常，0:禁用)
+            mfa_required: item.mfa_required, //是否要求双因素认证(0:否,1:是)
+            remark: item.remark,             //备注
+            del_flag: None,                  //删除标志（0代表删除 1代表存在）
+            create_time: None,               //创建时间
+            update_time: None,               //修改时间
         }
     }
 }
@@ -49,14 +52,15 @@ impl From<RoleReq> for Role {
 impl Into<RoleResp> for Role {
     fn into(self) -> RoleResp {
         RoleResp {
-            id: self.id,                   //主键
-            role_name: self.role_name,     //名称
-            role_key: self.role_key,       //角色权限字符串
-            data_scope: self.data_scope,   //数据范围（1：全部数据权限 2：自定数据权限 3：本部门数据权限 4：本部门及以下数据权限）
-            status: self.status,           //状态(1:正常，0:禁用)
-            remark: self.remark,           //备注
-            create_time: self.create_time, //创建时间
-            update_time: self.update_time, //修改时间
+            id: self.id,                     //主键
+            role_name: self.role_name,       //名称
+            role_key: self.role_key,         //角色权限字符串
+            data_scope: self.data_scope,     //数据范围（1：全部数据权限 2：自定数据权限 3：本部门数据权限 4：本部门及以下数据权限）
+            status: self.status,             //状态(1:正常，0:禁用)
+            mfa_required: self.mfa_required, //是否要求双因素认证(0:否,1:是)
+            remark: self.remark,             //备注
+            create_time: self.create_time,   //创建时间
+            update_time: self.update_time,   //修改时间
         }
     }
 }
@@ -98,11 +102,19 @@ impl_select_page!(Role{select_page() =>"
  */
 impl_select_page!(Role{select_sys_role_list(req:&QueryRoleListReq) =>"
       where 1=1
-     if req.role_name != null && req.role_name != '':
-       ` and role_name = #{req.role_name} `
-     if req.role_key != null && req.role_key != '':
-       ` and role_key = #{req.role_key} `
+     if req.roleName != null && req.roleName != '':
+       ` and role_name = #{req.roleName} `
+     if req.roleKey != null && req.roleKey != '':
+       ` and role_key = #{req.roleKey} `
      if req.status != 2:
        ` and status = #{req.status} `
      if !sql.contains('count'):
         ` order by create_time desc `"},"sys_role");
+
+/*
+ *查询用户拥有的启用状态的角色
+ */
+pub async fn select_role_by_user_id(rb: &RBatis, user_id: &i64) -> rbatis::Result<Vec<Role>> {
+    let sql = "select r.* from sys_role r join sys_user_role ur on r.id = ur.role_id where ur.user_id = ? and r.status = 1";
+    rb.query_decode(sql, vec![(*user_id).into()]).await
+}
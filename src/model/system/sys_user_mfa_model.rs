@@ -0,0 +1,36 @@
+use rbatis::rbdc::datetime::DateTime;
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+
+/*
+ *用户双因素认证
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserMfa {
+    pub id: Option<i64>,               //主键
+    pub user_id: i64,                  //用户id
+    pub secret: String,                //TOTP密钥(Base32)
+    pub status: i8,                    //状态(0:待验证,1:已启用)
+    pub recovery_codes: String,        //恢复码的SHA-256摘要,逗号分隔,使用后移除
+    pub last_used_step: i64,           //最后一次使用的时间步,防止动态码被重放
+    pub create_time: Option<DateTime>, //创建时间
+    pub update_time: Option<DateTime>, //修改时间
+}
+
+/*
+ *用户双因素认证基本操作
+ */
+rbatis::crud!(UserMfa {}, "sys_user_mfa");
+
+/*
+ *根据用户id查询双因素认证
+ */
+impl_select!(UserMfa{select_by_user_id(user_id:i64) -> Option => "`where user_id = #{user_id} limit 1`"}, "sys_user_mfa");
+
+/*
+ *查询用户的角色中是否有要求双因素认证的角色
+ */
+pub async fn count_mfa_required_role(rb: &RBatis, user_id: i64) -> rbatis::Result<i64> {
+    let sql = "select count(1) from sys_role r join sys_user_role ur on r.id = ur.role_id where ur.user_id = ? and r.status = 1 and r.mfa_required = 1";
+    rb.query_decode(sql, vec![user_id.into()]).await
+}
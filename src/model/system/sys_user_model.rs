@@ -1,12 +1,15 @@
 // author：刘飞华
 // createTime：2024/12/12 14:41:44
 
+use crate::utils::data_scope_util::DataScope;
+use crate::utils::db_util::DbKind;
 use crate::vo::system::sys_user_vo::QueryUserListReq;
 use crate::vo::system::sys_user_vo::UserReq;
 use crate::vo::system::sys_user_vo::UserResp;
 use rbatis::executor::Executor;
 use rbatis::rbdc::datetime::DateTime;
 use rbatis::rbdc::Error;
+use rbatis::RBatis;
 use serde::{Deserialize, Serialize};
 /*
  *用户信息
@@ -131,21 +134,35 @@ impl_select_page!(User{select_page() =>"
        order by create_time desc"
 },"sys_user");
 
+/*
+ *分页查询用户信息的条件,impl_select_page生成的函数已经有6个参数,查询条件和数据权限合并成一个参数
+ */
+#[derive(Serialize)]
+pub struct UserListQuery<'a> {
+    pub req: &'a QueryUserListReq, //查询条件
+    pub scope: &'a DataScope,      //数据权限
+}
+
 /*
  *根据条件分页查询用户信息
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-impl_select_page!(User{select_sys_user_list(req:&QueryUserListReq) =>"
+impl_select_page!(User{select_sys_user_list(query:&UserListQuery<'_>) =>"
       where 1=1
-      if req.mobile != null && req.mobile != '':
-       ` and mobile = #{req.mobile} `
-     if req.user_name != null && req.user_name != '':
-       ` and user_name = #{req.user_name} `
-     if req.status != 2:
-       ` and status = #{req.status} `
-     if req.dept_id != 0:
-       ` and (dept_id = #{req.dept_id} OR dept_id IN (SELECT id FROM sys_dept WHERE find_in_set(#{req.dept_id}, ancestors))) `
+      if query.req.mobile != null && query.req.mobile != '':
+       ` and mobile = #{query.req.mobile} `
+     if query.req.userName != null && query.req.userName != '':
+       ` and user_name = #{query.req.userName} `
+     if query.req.status != 2:
+       ` and status = #{query.req.status} `
+     if query.req.deptId != 0:
+       ` and (dept_id = #{query.req.deptId} OR dept_id IN (SELECT id FROM sys_dept WHERE find_in_set(#{query.req.deptId}, ancestors) > 0)) `
+     if query.scope.all == false:
+       ` and (id = #{query.scope.user_id} `
+       for _,dept_id in query.scope.dept_ids:
+         ` or dept_id = #{dept_id} `
+       ` ) `
      if !sql.contains('count'):
         ` order by create_time desc `"},"sys_user");
 
@@ -160,9 +177,14 @@ impl_select_page!(User{select_sys_user_list(req:&QueryUserListReq) =>"
                 ` and u.mobile = #{mobile} `
             if user_name != '':
                 ` and u.user_name = #{user_name} `
-            limit #{page_no},#{page_size}` "
+            if scope.all == false:
+                ` and (u.id = #{scope.user_id} `
+                for _,dept_id in scope.dept_ids:
+                    ` or u.dept_id = #{dept_id} `
+                ` ) `
+            ` limit #{page_size} offset #{page_no}` "
 )]
-async fn select_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, page_no: u64, page_size: u64) -> Result<Vec<User>, Error> {
+async fn select_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, scope: &DataScope, page_no: u64, page_size: u64) -> Result<Vec<User>, Error> {
     impled!()
 }
 
@@ -176,9 +198,14 @@ async fn select_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str,
             if mobile != '':
                 ` and u.mobile = #{mobile} `
             if user_name != '':
-                ` and u.user_name = #{user_name} `"
+                ` and u.user_name = #{user_name} `
+            if scope.all == false:
+                ` and (u.id = #{scope.user_id} `
+                for _,dept_id in scope.dept_ids:
+                    ` or u.dept_id = #{dept_id} `
+                ` ) `"
 )]
-async fn count_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str) -> Result<u64, Error> {
+async fn count_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, scope: &DataScope) -> Result<u64, Error> {
     impled!()
 }
 
@@ -193,9 +220,14 @@ async fn count_allocated_list(rb: &dyn Executor, role_id: i64, user_name: &str,
                 ` and u.mobile = #{mobile} `
             if user_name != '':
                 ` and u.user_name = #{user_name} `
-            limit #{page_no},#{page_size}` "
+            if scope.all == false:
+                ` and (u.id = #{scope.user_id} `
+                for _,dept_id in scope.dept_ids:
+                    ` or u.dept_id = #{dept_id} `
+                ` ) `
+            ` limit #{page_size} offset #{page_no}` "
 )]
-pub async fn select_unallocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, page_no: u64, page_size: u64) -> rbatis::Result<Vec<User>> {
+pub async fn select_unallocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, scope: &DataScope, page_no: u64, page_size: u64) -> rbatis::Result<Vec<User>> {
     impled!()
 }
 
@@ -209,8 +241,28 @@ pub async fn select_unallocated_list(rb: &dyn Executor, role_id: i64, user_name:
             if mobile != '':
                 ` and u.mobile = #{mobile} `
             if user_name != '':
-                ` and u.user_name = #{user_name} `"
+                ` and u.user_name = #{user_name} `
+            if scope.all == false:
+                ` and (u.id = #{scope.user_id} `
+                for _,dept_id in scope.dept_ids:
+                    ` or u.dept_id = #{dept_id} `
+                ` ) `"
 )]
-pub async fn count_unallocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str) -> rbatis::Result<u64> {
+pub async fn count_unallocated_list(rb: &dyn Executor, role_id: i64, user_name: &str, mobile: &str, scope: &DataScope) -> rbatis::Result<u64> {
     impled!()
 }
+
+/*
+ *查询password字段的最大长度,旧版本的表结构是varchar(64),保存不了Argon2id密文
+ *SQLite不限制varchar的长度,返回None
+ */
+pub async fn select_password_max_length(rb: &RBatis) -> rbatis::Result<Option<u64>> {
+    let sql = match DbKind::from_driver(rb.driver_type()?) {
+        Some(DbKind::Mysql) => "select character_maximum_length from information_schema.columns where table_schema = database() and table_name = 'sys_user' and column_name = 'password'",
+        Some(DbKind::Postgres) => "select cast(character_maximum_length as bigint) from information_schema.columns where table_schema = current_schema() and table_name = 'sys_user' and column_name = 'password'",
+        _ => return Ok(None),
+    };
+    let rows = rb.query(sql, vec![]).await?;
+    let length = rows.into_iter().next().and_then(|(_, row)| row.into_iter().next()).and_then(|(_, x)| x.as_u64());
+    Ok(length.filter(|x| *x > 0))
+}
@@ -26,11 +26,11 @@ pub struct UserRole {
 rbatis::crud!(UserRole {}, "sys_user_role");
 
 /*
- *查询是否为超级管理员(role_id=1是预设超级管理的id)
+ *查询是否为超级管理员(role_id=1是预设超级管理的id,角色被禁用时不算)
  *author：刘飞华
  *date：2024/12/12 14:41:44
  */
-#[sql("select count(1) from sys_user_role where role_id = 1 and user_id = ?")]
+#[sql("select count(1) from sys_user_role ur join sys_role r on ur.role_id = r.id where ur.role_id = 1 and ur.user_id = ? and r.status = 1")]
 pub async fn is_admin(rb: &RBatis, user_id: &i64) -> rbatis::Result<i64> {
     impled!()
 }
@@ -7,6 +7,8 @@ use crate::routes::system::sys_notice_route::build_sys_notice_route;
 use crate::routes::system::sys_operate_log_route::build_sys_operate_log_route;
 use crate::routes::system::sys_post_route::build_sys_post_route;
 use crate::routes::system::sys_role_route::build_sys_role_route;
+use crate::routes::system::sys_user_mfa_route::build_sys_user_mfa_route;
+use crate::routes::system::sys_user_online_route::build_sys_user_online_route;
 use crate::routes::system::sys_user_route::build_sys_user_route;
 use salvo::Router;
 
@@ -25,6 +27,8 @@ pub fn build_system_route() -> Router {
         .push(build_sys_operate_log_route())
         .push(build_sys_post_route())
         .push(build_sys_notice_route())
+        .push(build_sys_user_online_route())
+        .push(build_sys_user_mfa_route())
 }
 
 pub fn build_other_route() -> Router {
@@ -7,4 +7,6 @@ pub mod sys_notice_route;
 pub mod sys_operate_log_route;
 pub mod sys_post_route;
 pub mod sys_role_route;
-pub mod sys_user_route;
\ No newline at end of file
+pub mod sys_user_mfa_route;
+pub mod sys_user_online_route;
+pub mod sys_user_route;
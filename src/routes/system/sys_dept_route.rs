@@ -1,4 +1,5 @@
-use salvo::Router;
+use salvo::prelude::*;
+use crate::middleware::auth::require_perm;
 use crate::handler::system::sys_dept_handler::*;
 /*
  *构建部门表路由
@@ -7,11 +8,12 @@ use crate::handler::system::sys_dept_handler::*;
  */
 pub fn build_sys_dept_route() -> Router {
     Router::new()
-        .push(Router::new().path("/system/dept/addDept").post(add_sys_dept))
-        .push(Router::new().path("/system/dept/deleteDept").post(delete_sys_dept))
-        .push(Router::new().path("/system/dept/updateDept").post(update_sys_dept))
-        .push(Router::new().path("/system/dept/updateDeptStatus").post(update_sys_dept_status))
-        .push(Router::new().path("/system/dept/queryDeptDetail").post(query_sys_dept_detail))
-        .push(Router::new().path("/system/dept/queryDeptList").post(query_sys_dept_list))
+        .oapi_tag("部门表")
+        .push(Router::new().path("/system/dept/addDept").hoop(require_perm("system:dept:add")).post(add_sys_dept))
+        .push(Router::new().path("/system/dept/deleteDept").hoop(require_perm("system:dept:remove")).post(delete_sys_dept))
+        .push(Router::new().path("/system/dept/updateDept").hoop(require_perm("system:dept:edit")).post(update_sys_dept))
+        .push(Router::new().path("/system/dept/updateDeptStatus").hoop(require_perm("system:dept:status")).post(update_sys_dept_status))
+        .push(Router::new().path("/system/dept/queryDeptDetail").hoop(require_perm("system:dept:query")).post(query_sys_dept_detail))
+        .push(Router::new().path("/system/dept/queryDeptList").hoop(require_perm("system:dept:list")).post(query_sys_dept_list))
         //记得在main.rs中的route()函数中添加构建部门表路由build_sys_dept_route()
 }
@@ -1,4 +1,5 @@
-use salvo::Router;
+use salvo::prelude::*;
+use crate::middleware::auth::require_perm;
 use crate::handler::system::sys_dict_data_handler::*;
 /*
  *构建字典数据表路由
@@ -7,11 +8,12 @@ use crate::handler::system::sys_dict_data_handler::*;
  */
 pub fn build_sys_dict_data_route() -> Router {
     Router::new()
-        .push(Router::new().path("/system/dictData/addDictData").post(add_sys_dict_data))
-        .push(Router::new().path("/system/dictData/deleteDictData").post(delete_sys_dict_data))
-        .push(Router::new().path("/system/dictData/updateDictData").post(update_sys_dict_data))
-        .push(Router::new().path("/system/dictData/updateDictDataStatus").post(update_sys_dict_data_status))
-        .push(Router::new().path("/system/dictData/queryDictDataDetail").post(query_sys_dict_data_detail))
-        .push(Router::new().path("/system/dictData/queryDictDataList").post(query_sys_dict_data_list))
+        .oapi_tag("字典数据表")
+        .push(Router::new().path("/system/dictData/addDictData").hoop(require_perm("system:dictData:add")).post(add_sys_dict_data))
+        .push(Router::new().path("/system/dictData/deleteDictData").hoop(require_perm("system:dictData:remove")).post(delete_sys_dict_data))
+        .push(Router::new().path("/system/dictData/updateDictData").hoop(require_perm("system:dictData:edit")).post(update_sys_dict_data))
+        .push(Router::new().path("/system/dictData/updateDictDataStatus").hoop(require_perm("system:dictData:status")).post(update_sys_dict_data_status))
+        .push(Router::new().path("/system/dictData/queryDictDataDetail").hoop(require_perm("system:dictData:query")).post(query_sys_dict_data_detail))
+        .push(Router::new().path("/system/dictData/queryDictDataList").hoop(require_perm("system:dictData:list")).post(query_sys_dict_data_list))
         //记得在main.rs中的route()函数中添加构建字典数据表路由build_sys_dict_data_route()
 }
@@ -1,4 +1,5 @@
-use salvo::Router;
+use salvo::prelude::*;
+use crate::middleware::auth::require_perm;
 use crate::handler::system::sys_dict_type_handler::*;
 /*
  *构建字典类型表路由
@@ -7,11 +8,12 @@ use crate::handler::system::sys_dict_type_handler::*;
  */
 pub fn build_sys_dict_type_route() -> Router {
     Router::new()
-        .push(Router::new().path("/system/dictType/addDictType").post(add_sys_dict_type))
-        .push(Router::new().path("/system/dictType/deleteDictType").post(delete_sys_dict_type))
-        .push(Router::new().path("/system/dictType/updateDictType").post(update_sys_dict_type))
-        .push(Router::new().path("/system/dictType/updateDictTypeStatus").post(update_sys_dict_type_status))
-        .push(Router::new().path("/system/dictType/queryDictTypeDetail").post(query_sys_dict_type_detail))
-        .push(Router::new().path("/system/dictType/queryDictTypeList").post(query_sys_dict_type_list))
+        .oapi_tag("字典类型表")
+        .push(Router::new().path("/system/dictType/addDictType").hoop(require_perm("system:dictType:add")).post(add_sys_dict_type))
+        .push(Router::new().path("/system/dictType/deleteDictType").hoop(require_perm("system:dictType:remove")).post(delete_sys_dict_type))
+        .push(Router::new().path("/system/dictType/updateDictType").hoop(require_perm("system:dictType:edit")).post(update_sys_dict_type))
+        .push(Router::new().path("/system/dictType/updateDictTypeStatus").hoop(require_perm("system:dictType:status")).post(update_sys_dict_type_status))
+        .push(Router::new().path("/system/dictType/queryDictTypeDetail").hoop(require_perm("system:dictType:query")).post(query_sys_dict_type_detail))
+        .push(Router::new().path("/system/dictType/queryDictTypeList").hoop(require_perm("system:dictType:list")).post(query_sys_dict_type_list))
         //记得在main.rs中的route()函数中添加构建字典类型表路由build_sys_dict_type_route()
 }
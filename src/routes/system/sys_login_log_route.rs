@@ -1,4 +1,5 @@
-use salvo::Router;
+use salvo::prelude::*;
+use crate::middleware::auth::require_perm;
 use crate::handler::system::sys_login_log_handler::*;
 /*
  *构建系统访问记录路由
@@ -7,8 +8,9 @@ use crate::handler::system::sys_login_log_handler::*;
  */
 pub fn build_sys_login_log_route() -> Router {
     Router::new()
-        .push(Router::new().path("/system/loginLog/deleteLoginLog").post(delete_sys_login_log))
-        .push(Router::new().path("/system/loginLog/queryLoginLogDetail").post(query_sys_login_log_detail))
-        .push(Router::new().path("/system/loginLog/queryLoginLogList").post(query_sys_login_log_list))
+        .oapi_tag("系统访问记录")
+        .push(Router::new().path("/system/loginLog/deleteLoginLog").hoop(require_perm("system:loginLog:remove")).post(delete_sys_login_log))
+        .push(Router::new().path("/system/loginLog/queryLoginLogDetail").hoop(require_perm("system:loginLog:query")).post(query_sys_login_log_detail))
+        .push(Router::new().path("/system/loginLog/queryLoginLogList").hoop(require_perm("system:loginLog:list")).post(query_sys_login_log_list))
         //记得在main.rs中的route()函数中添加构建系统访问记录路由build_sys_login_log_route()
 }
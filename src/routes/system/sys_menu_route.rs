@@ -1,4 +1,5 @@
-use salvo::Router;
+use salvo::prelude::*;
+use crate::middleware::auth::require_perm;
 use crate::handler::system::sys_menu_handler::*;
 /*
  *构建菜单信息路由
@@ -7,12 +8,13 @@ use crate::handler::system::sys_menu_handler::*;
  */
 pub fn build_sys_menu_route() -> Router {
     Router::new()
-        .push(Router::new().path("/system/menu/addMenu").post(add_sys_menu))
-        .push(Router::new().path("/system/menu/deleteMenu").post(delete_sys_menu))
-        .push(Router::new().path("/system/menu/updateMenu").post(update_sys_menu))
-        .push(Router::new().path("/system/menu/updateMenuStatus").post(update_sys_menu_status))
-        .push(Router::new().path("/system/menu/queryMenuDetail").post(query_sys_menu_detail))
-        .push(Router::new().path("/system/menu/queryMenuList").post(query_sys_menu_list))
-        .push(Router::new().path("/system/menu/queryMenuListSimple").post(query_sys_menu_list_simple))
+        .oapi_tag("菜单信息")
+        .push(Router::new().path("/system/menu/addMenu").hoop(require_perm("system:menu:add")).post(add_sys_menu))
+        .push(Router::new().path("/system/menu/deleteMenu").hoop(require_perm("system:menu:remove")).post(delete_sys_menu))
+        .push(Router::new().path("/system/menu/updateMenu").hoop(require_perm("system:menu:edit")).post(update_sys_menu))
+        .push(Router::new().path("/system/menu/updateMenuStatus").hoop(require_perm("system:menu:status")).post(update_sys_menu_status))
+        .push(Router::new().path("/system/menu/queryMenuDetail").hoop(require_perm("system:menu:query")).post(query_sys_menu_detail))
+        .push(Router::new().path("/system/menu/queryMenuList").hoop(require_perm("system:menu:list")).post(query_sys_menu_list))
+        .push(Router::new().path("/system/menu/queryMenuListSimple").hoop(require_perm("system:menu:tree")).post(query_sys_menu_list_simple))
         //记得在main.rs中的route()函数中添加构建菜单信息路由build_sys_menu_route()
 }
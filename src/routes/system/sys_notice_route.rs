@@ -1,4 +1,5 @@
-use salvo::Router;
+use salvo::prelude::*;
+use crate::middleware::auth::require_perm;
 use crate::handler::system::sys_notice_handler::*;
 /*
  *构建通知公告表路由
@@ -7,11 +8,12 @@ use crate::handler::system::sys_notice_handler::*;
  */
 pub fn build_sys_notice_route() -> Router {
     Router::new()
-        .push(Router::new().path("/system/notice/addNotice").post(add_sys_notice))
-        .push(Router::new().path("/system/notice/deleteNotice").post(delete_sys_notice))
-        .push(Router::new().path("/system/notice/updateNotice").post(update_sys_notice))
-        .push(Router::new().path("/system/notice/updateNoticeStatus").post(update_sys_notice_status))
-        .push(Router::new().path("/system/notice/queryNoticeDetail").post(query_sys_notice_detail))
-        .push(Router::new().path("/system/notice/queryNoticeList").post(query_sys_notice_list))
+        .oapi_tag("通知公告表")
+        .push(Router::new().path("/system/notice/addNotice").hoop(require_perm("system:notice:add")).post(add_sys_notice))
+        .push(Router::new().path("/system/notice/deleteNotice").hoop(require_perm("system:notice:remove")).post(delete_sys_notice))
+        .push(Router::new().path("/system/notice/updateNotice").hoop(require_perm("system:notice:edit")).post(update_sys_notice))
+        .push(Router::new().path("/system/notice/updateNoticeStatus").hoop(require_perm("system:notice:status")).post(update_sys_notice_status))
+        .push(Router::new().path("/system/notice/queryNoticeDetail").hoop(require_perm("system:notice:query")).post(query_sys_notice_detail))
+        .push(Router::new().path("/system/notice/queryNoticeList").hoop(require_perm("system:notice:list")).post(query_sys_notice_list))
         //记得在main.rs中的route()函数中添加构建通知公告表路由build_sys_notice_route()
 }
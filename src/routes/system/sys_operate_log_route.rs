@@ -1,4 +1,5 @@
-use salvo::Router;
+use salvo::prelude::*;
+use crate::middleware::auth::require_perm;
 use crate::handler::system::sys_operate_log_handler::*;
 /*
  *构建操作日志记录路由
@@ -7,8 +8,9 @@ use crate::handler::system::sys_operate_log_handler::*;
  */
 pub fn build_sys_operate_log_route() -> Router {
     Router::new()
-        .push(Router::new().path("/system/operateLog/deleteOperateLog").post(delete_sys_operate_log))
-        .push(Router::new().path("/system/operateLog/queryOperateLogDetail").post(query_sys_operate_log_detail))
-        .push(Router::new().path("/system/operateLog/queryOperateLogList").post(query_sys_operate_log_list))
+        .oapi_tag("操作日志记录")
+        .push(Router::new().path("/system/operateLog/deleteOperateLog").hoop(require_perm("system:operateLog:remove")).post(delete_sys_operate_log))
+        .push(Router::new().path("/system/operateLog/queryOperateLogDetail").hoop(require_perm("system:operateLog:query")).post(query_sys_operate_log_detail))
+        .push(Router::new().path("/system/operateLog/queryOperateLogList").hoop(require_perm("system:operateLog:list")).post(query_sys_operate_log_list))
         //记得在main.rs中的route()函数中添加构建操作日志记录路由build_sys_operate_log_route()
 }
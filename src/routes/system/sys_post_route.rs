@@ -1,4 +1,5 @@
-use salvo::Router;
+use salvo::prelude::*;
+use crate::middleware::auth::require_perm;
 use crate::handler::system::sys_post_handler::*;
 /*
  *构建岗位信息表路由
@@ -7,11 +8,12 @@ use crate::handler::system::sys_post_handler::*;
  */
 pub fn build_sys_post_route() -> Router {
     Router::new()
-        .push(Router::new().path("/system/post/addPost").post(add_sys_post))
-        .push(Router::new().path("/system/post/deletePost").post(delete_sys_post))
-        .push(Router::new().path("/system/post/updatePost").post(update_sys_post))
-        .push(Router::new().path("/system/post/updatePostStatus").post(update_sys_post_status))
-        .push(Router::new().path("/system/post/queryPostDetail").post(query_sys_post_detail))
-        .push(Router::new().path("/system/post/queryPostList").post(query_sys_post_list))
+        .oapi_tag("岗位信息表")
+        .push(Router::new().path("/system/post/addPost").hoop(require_perm("system:post:add")).post(add_sys_post))
+        .push(Router::new().path("/system/post/deletePost").hoop(require_perm("system:post:remove")).post(delete_sys_post))
+        .push(Router::new().path("/system/post/updatePost").hoop(require_perm("system:post:edit")).post(update_sys_post))
+        .push(Router::new().path("/system/post/updatePostStatus").hoop(require_perm("system:post:status")).post(update_sys_post_status))
+        .push(Router::new().path("/system/post/queryPostDetail").hoop(require_perm("system:post:query")).post(query_sys_post_detail))
+        .push(Router::new().path("/system/post/queryPostList").hoop(require_perm("system:post:list")).post(query_sys_post_list))
         //记得在main.rs中的route()函数中添加构建岗位信息表路由build_sys_post_route()
 }
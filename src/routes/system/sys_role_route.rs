@@ -1,4 +1,5 @@
-use salvo::Router;
+use salvo::prelude::*;
+use crate::middleware::auth::require_perm;
 use crate::handler::system::sys_role_handler::*;
 /*
  *构建角色信息路由
@@ -7,18 +8,21 @@ use crate::handler::system::sys_role_handler::*;
  */
 pub fn build_sys_role_route() -> Router {
     Router::new()
-        .push(Router::new().path("/system/role/addRole").post(add_sys_role))
-        .push(Router::new().path("/system/role/deleteRole").post(delete_sys_role))
-        .push(Router::new().path("/system/role/updateRole").post(update_sys_role))
-        .push(Router::new().path("/system/role/updateRoleStatus").post(update_sys_role_status))
-        .push(Router::new().path("/system/role/queryRoleDetail").post(query_sys_role_detail))
-        .push(Router::new().path("/system/role/queryRoleList").post(query_sys_role_list))
-        .push(Router::new().path("/system/role/queryRoleMenu").post(query_role_menu))
-        .push(Router::new().path("/system/role/updateRoleMenu").post(update_role_menu))
-        .push(Router::new().path("/system/role/queryAllocatedList").post(query_allocated_list))
-        .push(Router::new().path("/system/role/queryUnallocatedList").post(query_unallocated_list))
-        .push(Router::new().path("/system/role/cancelAuthUser").post(cancel_auth_user))
-        .push(Router::new().path("/system/role/batchCancelAuthUser").post(batch_cancel_auth_user))
-        .push(Router::new().path("/system/role/batchAuthUser").post(batch_auth_user))
+        .oapi_tag("角色信息")
+        .push(Router::new().path("/system/role/addRole").hoop(require_perm("system:role:add")).post(add_sys_role))
+        .push(Router::new().path("/system/role/deleteRole").hoop(require_perm("system:role:remove")).post(delete_sys_role))
+        .push(Router::new().path("/system/role/updateRole").hoop(require_perm("system:role:edit")).post(update_sys_role))
+        .push(Router::new().path("/system/role/updateRoleStatus").hoop(require_perm("system:role:status")).post(update_sys_role_status))
+        .push(Router::new().path("/system/role/queryRoleDetail").hoop(require_perm("system:role:query")).post(query_sys_role_detail))
+        .push(Router::new().path("/system/role/queryRoleList").hoop(require_perm("system:role:list")).post(query_sys_role_list))
+        .push(Router::new().path("/system/role/queryRoleMenu").hoop(require_perm("system:role:queryMenu")).post(query_role_menu))
+        .push(Router::new().path("/system/role/updateRoleMenu").hoop(require_perm("system:role:authMenu")).post(update_role_menu))
+        .push(Router::new().path("/system/role/queryRoleDept").hoop(require_perm("system:role:queryDept")).post(query_role_dept))
+        .push(Router::new().path("/system/role/updateRoleDept").hoop(require_perm("system:role:authDept")).post(update_role_dept))
+        .push(Router::new().path("/system/role/queryAllocatedList").hoop(require_perm("system:role:allocatedList")).post(query_allocated_list))
+        .push(Router::new().path("/system/role/queryUnallocatedList").hoop(require_perm("system:role:unallocatedList")).post(query_unallocated_list))
+        .push(Router::new().path("/system/role/cancelAuthUser").hoop(require_perm("system:role:cancelAuth")).post(cancel_auth_user))
+        .push(Router::new().path("/system/role/batchCancelAuthUser").hoop(require_perm("system:role:cancelAuth")).post(batch_cancel_auth_user))
+        .push(Router::new().path("/system/role/batchAuthUser").hoop(require_perm("system:role:auth")).post(batch_auth_user))
         //记得在main.rs中的route()函数中添加构建角色信息路由build_sys_role_route()
 }
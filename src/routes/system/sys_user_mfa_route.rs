@@ -0,0 +1,14 @@
+use salvo::prelude::*;
+use crate::handler::system::sys_user_mfa_handler::*;
+/*
+ *构建双因素认证路由(当前用户自助管理,只需要登录)
+ */
+pub fn build_sys_user_mfa_route() -> Router {
+    Router::new()
+        .oapi_tag("双因素认证")
+        .push(Router::new().path("/system/user/mfa/queryStatus").get(query_user_mfa_status))
+        .push(Router::new().path("/system/user/mfa/enroll").post(enroll_user_mfa))
+        .push(Router::new().path("/system/user/mfa/activate").post(activate_user_mfa))
+        .push(Router::new().path("/system/user/mfa/disable").post(disable_user_mfa))
+        //记得在main.rs中的route()函数中添加构建双因素认证路由build_sys_user_mfa_route()
+}
@@ -0,0 +1,13 @@
+use salvo::prelude::*;
+use crate::middleware::auth::require_perm;
+use crate::handler::system::sys_user_online_handler::*;
+/*
+ *构建在线用户路由
+ */
+pub fn build_sys_user_online_route() -> Router {
+    Router::new()
+        .oapi_tag("在线用户")
+        .push(Router::new().path("/system/online/queryOnlineList").hoop(require_perm("system:online:list")).post(query_sys_user_online_list))
+        .push(Router::new().path("/system/online/forceLogout").hoop(require_perm("system:online:forceLogout")).post(force_logout))
+        //记得在main.rs中的route()函数中添加构建在线用户路由build_sys_user_online_route()
+}
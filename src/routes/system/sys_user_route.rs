@@ -1,4 +1,5 @@
-use salvo::Router;
+use salvo::prelude::*;
+use crate::middleware::auth::require_perm;
 use crate::handler::system::sys_user_handler::*;
 /*
  *构建用户信息路由
@@ -7,15 +8,18 @@ use crate::handler::system::sys_user_handler::*;
  */
 pub fn build_sys_user_route() -> Router {
     Router::new()
-        .push(Router::new().path("/system/user/addUser").post(add_sys_user))
-        .push(Router::new().path("/system/user/deleteUser").post(delete_sys_user))
-        .push(Router::new().path("/system/user/updateUser").post(update_sys_user))
-        .push(Router::new().path("/system/user/updateUserStatus").post(update_sys_user_status))
-        .push(Router::new().path("/system/user/queryUserDetail").post(query_sys_user_detail))
-        .push(Router::new().path("/system/user/queryUserList").post(query_sys_user_list))
+        .oapi_tag("用户信息")
+        .push(Router::new().path("/system/user/addUser").hoop(require_perm("system:user:add")).post(add_sys_user))
+        .push(Router::new().path("/system/user/deleteUser").hoop(require_perm("system:user:remove")).post(delete_sys_user))
+        .push(Router::new().path("/system/user/updateUser").hoop(require_perm("system:user:edit")).post(update_sys_user))
+        .push(Router::new().path("/system/user/updateUserStatus").hoop(require_perm("system:user:status")).post(update_sys_user_status))
+        .push(Router::new().path("/system/user/queryUserDetail").hoop(require_perm("system:user:query")).post(query_sys_user_detail))
+        .push(Router::new().path("/system/user/queryUserList").hoop(require_perm("system:user:list")).post(query_sys_user_list))
         .push(Router::new().path("/system/user/queryUserMenu").get(query_user_menu))
-        .push(Router::new().path("/system/user/queryUserRole").post(query_user_role))
-        .push(Router::new().path("/system/user/updateUserRole").post(update_user_role))
+        .push(Router::new().path("/system/user/queryUserRole").hoop(require_perm("system:user:queryRole")).post(query_user_role))
+        .push(Router::new().path("/system/user/updateUserRole").hoop(require_perm("system:user:authRole")).post(update_user_role))
         .push(Router::new().path("/system/user/updateUserPassword").post(update_sys_user_password))
+        .push(Router::new().path("/system/user/unlockUser").hoop(require_perm("system:user:unlock")).post(unlock_sys_user))
+        .push(Router::new().path("/system/user/logout").post(logout))
         //记得在main.rs中的route()函数中添加构建用户信息路由build_sys_user_route()
 }
@@ -0,0 +1,56 @@
+use crate::tests::test_app::{TestApp, ADMIN_MOBILE, PASSWORD, TEST_MOBILE};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_login() {
+    let app = TestApp::new().await;
+
+    assert_eq!(app.login(ADMIN_MOBILE, "wrong-password").await["msg"], "业务异常: 密码不正确");
+    assert_eq!(app.login("13800000000", PASSWORD).await["msg"], "业务异常: 密码不正确");
+    //演示账号没有分配角色
+    assert_eq!(app.login(TEST_MOBILE, PASSWORD).await["msg"], "业务异常: 用户没有分配角色或者菜单,不能登录");
+
+    let token = app.token(ADMIN_MOBILE, PASSWORD).await;
+    let menu = app.get(&token, "/api/system/user/queryUserMenu").await;
+    assert_eq!(menu["code"], 0, "{}", menu);
+    assert_eq!(menu["data"]["name"], "admin");
+    assert!(!menu["data"]["sysMenu"].as_array().unwrap().is_empty());
+
+    //登录日志记录了成功和失败的登录
+    let logs = app.post(&token, "/api/system/loginLog/queryLoginLogList", json!({"pageNo": 1, "pageSize": 10})).await;
+    assert_eq!(logs["total"], 4, "{}", logs);
+
+    //退出后token失效
+    app.post_ok(&token, "/api/system/user/logout", json!({})).await;
+    let resp = app.post(&token, "/api/system/user/queryUserList", json!({"pageNo": 1, "pageSize": 10})).await;
+    assert_eq!(resp["code"], 1);
+    assert_eq!(resp["msg"], "无效的token");
+}
+
+#[tokio::test]
+async fn test_permission_denied() {
+    let app = TestApp::new().await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+
+    let resp = app.post("invalid", "/api/system/user/queryUserList", json!({"pageNo": 1, "pageSize": 10})).await;
+    assert_eq!(resp["code"], 1);
+    let resp = app.post("", "/api/system/user/queryUserList", json!({"pageNo": 1, "pageSize": 10})).await;
+    assert_eq!(resp["code"], 1);
+
+    //只授予查询用户列表的权限
+    let token = app.grant_test_user(&admin, &["system:user:list"]).await;
+    let resp = app.post(&token, "/api/system/user/queryUserList", json!({"pageNo": 1, "pageSize": 10})).await;
+    assert_eq!(resp["code"], 0, "{}", resp);
+    assert_eq!(resp["total"], 2);
+    assert_eq!(app.get(&token, "/api/system/user/queryUserMenu").await["code"], 0);
+
+    let resp = app.post(&token, "/api/system/user/deleteUser", json!({"ids": [1]})).await;
+    assert_eq!(resp["code"], 1);
+    assert_eq!(resp["msg"], "你没有权限访问: /api/system/user/deleteUser");
+    let resp = app.post(&token, "/api/system/role/queryRoleList", json!({"pageNo": 1, "pageSize": 10})).await;
+    assert_eq!(resp["msg"], "你没有权限访问: /api/system/role/queryRoleList");
+
+    //修改自己的密码只需要登录
+    app.post_ok(&token, "/api/system/user/updateUserPassword", json!({"pwd": PASSWORD, "rePwd": "new-password"})).await;
+    assert_eq!(app.login(TEST_MOBILE, "new-password").await["code"], 0);
+}
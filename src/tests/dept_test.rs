@@ -0,0 +1,62 @@
+use crate::tests::test_app::{TestApp, ADMIN_MOBILE, PASSWORD};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_dept() {
+    let app = TestApp::new().await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+
+    let dept = json!({"parentId": 4, "deptName": "研发一组", "sort": 1, "leader": "张三", "phone": "13800000001", "email": "dev@qq.com", "status": 1});
+    app.post_ok(&admin, "/api/system/dept/addDept", dept.clone()).await;
+    assert_eq!(app.post(&admin, "/api/system/dept/addDept", dept).await["msg"], "业务异常: 部门名称已存在");
+    let missing = json!({"parentId": 999, "deptName": "无效部门", "sort": 1, "leader": "", "phone": "", "email": "", "status": 1});
+    assert_eq!(app.post(&admin, "/api/system/dept/addDept", missing).await["msg"], "业务异常: 添加失败,上级部门不存在");
+
+    let list = app.post_ok(&admin, "/api/system/dept/queryDeptList", json!({"deptName": "研发一组"})).await;
+    assert_eq!(list.as_array().unwrap().len(), 1, "{}", list);
+    let id = list[0]["id"].as_i64().unwrap();
+    assert_eq!(list[0]["ancestors"], "0,1,2,4");
+
+    //祖级列表跟随上级部门变化
+    let update = json!({"id": id, "parentId": 5, "deptName": "市场一组", "sort": 1, "leader": "张三", "phone": "13800000001", "email": "dev@qq.com", "status": 1});
+    app.post_ok(&admin, "/api/system/dept/updateDept", update).await;
+    let detail = app.post_ok(&admin, "/api/system/dept/queryDeptDetail", json!({"id": id})).await;
+    assert_eq!(detail["deptName"], "市场一组");
+    assert_eq!(detail["ancestors"], "0,1,2,5");
+
+    assert_eq!(app.post(&admin, "/api/system/dept/deleteDept", json!({"id": 2})).await["msg"], "业务异常: 存在下级部门,不允许删除");
+    assert_eq!(app.post(&admin, "/api/system/dept/deleteDept", json!({"id": 1})).await["msg"], "业务异常: 存在下级部门,不允许删除");
+    app.post_ok(&admin, "/api/system/dept/deleteDept", json!({"id": id})).await;
+    assert_eq!(app.post(&admin, "/api/system/dept/queryDeptDetail", json!({"id": id})).await["msg"], "业务异常: 部门不存在");
+}
+
+#[tokio::test]
+async fn test_post() {
+    let app = TestApp::new().await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+
+    let post = json!({"postCode": "qa", "postName": "测试工程师", "sort": 5, "status": 1, "remark": ""});
+    app.post_ok(&admin, "/api/system/post/addPost", post).await;
+    let duplicate = json!({"postCode": "qa2", "postName": "测试工程师", "sort": 5, "status": 1, "remark": ""});
+    assert_eq!(app.post(&admin, "/api/system/post/addPost", duplicate).await["msg"], "业务异常: 岗位名称已存在");
+    let duplicate = json!({"postCode": "qa", "postName": "测试工程师2", "sort": 5, "status": 1, "remark": ""});
+    assert_eq!(app.post(&admin, "/api/system/post/addPost", duplicate).await["msg"], "业务异常: 岗位编码已存在");
+
+    let list = app.post(&admin, "/api/system/post/queryPostList", json!({"pageNo": 1, "pageSize": 10, "postCode": "qa"})).await;
+    assert_eq!(list["total"], 1, "{}", list);
+    let id = list["data"][0]["id"].as_i64().unwrap();
+
+    app.post_ok(&admin, "/api/system/post/updatePostStatus", json!({"ids": [id], "status": 0})).await;
+    let list = app.post(&admin, "/api/system/post/queryPostList", json!({"pageNo": 1, "pageSize": 10, "status": 0})).await;
+    assert_eq!(list["total"], 1, "{}", list);
+
+    let update = json!({"id": id, "postCode": "qa", "postName": "质量保障", "sort": 6, "status": 1, "remark": "QA"});
+    app.post_ok(&admin, "/api/system/post/updatePost", update).await;
+    let detail = app.post_ok(&admin, "/api/system/post/queryPostDetail", json!({"id": id})).await;
+    assert_eq!(detail["postName"], "质量保障");
+
+    //已分配给用户的岗位不能删除
+    let user = json!({"mobile": "13800000002", "userName": "lisi", "nickName": "李四", "email": "ls@qq.com", "password": "Ls@123456", "status": 1, "deptId": 4, "remark": "", "postIds": [id]});
+    app.post_ok(&admin, "/api/system/user/addUser", user).await;
+    assert_eq!(app.post(&admin, "/api/system/post/deletePost", json!({"ids": [id]})).await["msg"], "业务异常: 已分配,不能删除");
+}
@@ -0,0 +1,40 @@
+use crate::tests::test_app::{TestApp, ADMIN_MOBILE, PASSWORD};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_dict() {
+    let app = TestApp::new().await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+
+    let dict_type = json!({"dictName": "订单状态", "dictType": "order_status", "status": 1, "remark": ""});
+    app.post_ok(&admin, "/api/system/dictType/addDictType", dict_type.clone()).await;
+    assert_eq!(app.post(&admin, "/api/system/dictType/addDictType", dict_type).await["msg"], "业务异常: 字典类型已存在");
+
+    let list = app.post(&admin, "/api/system/dictType/queryDictTypeList", json!({"pageNo": 1, "pageSize": 10, "dictType": "order_status"})).await;
+    assert_eq!(list["total"], 1, "{}", list);
+    let type_id = list["data"][0]["id"].as_i64().unwrap();
+
+    for (sort, label, value) in [(1, "待支付", "0"), (2, "已支付", "1")] {
+        let data = json!({"dictSort": sort, "dictLabel": label, "dictValue": value, "dictType": "order_status", "cssClass": "", "listClass": "", "isDefault": "N", "status": 1, "remark": ""});
+        app.post_ok(&admin, "/api/system/dictData/addDictData", data).await;
+    }
+    let duplicate = json!({"dictSort": 3, "dictLabel": "待支付", "dictValue": "2", "dictType": "order_status", "cssClass": "", "listClass": "", "isDefault": "N", "status": 1, "remark": ""});
+    assert_eq!(app.post(&admin, "/api/system/dictData/addDictData", duplicate).await["msg"], "业务异常: 字典标签已存在");
+
+    let list = app.post(&admin, "/api/system/dictData/queryDictDataList", json!({"pageNo": 1, "pageSize": 10, "dictType": "order_status"})).await;
+    assert_eq!(list["total"], 2, "{}", list);
+    let data_id = list["data"][0]["id"].as_i64().unwrap();
+
+    let update = json!({"id": data_id, "dictSort": 1, "dictLabel": "已取消", "dictValue": "9", "dictType": "order_status", "cssClass": "", "listClass": "danger", "isDefault": "N", "status": 1, "remark": ""});
+    app.post_ok(&admin, "/api/system/dictData/updateDictData", update).await;
+    let detail = app.post_ok(&admin, "/api/system/dictData/queryDictDataDetail", json!({"id": data_id})).await;
+    assert_eq!(detail["dictLabel"], "已取消");
+    assert_eq!(detail["listClass"], "danger");
+
+    //字典类型下存在数据时不能删除
+    assert_eq!(app.post(&admin, "/api/system/dictType/deleteDictType", json!({"ids": [type_id]})).await["msg"], "业务异常: 已分配,不能删除");
+    let ids = list["data"].as_array().unwrap().iter().map(|x| x["id"].clone()).collect::<Vec<_>>();
+    app.post_ok(&admin, "/api/system/dictData/deleteDictData", json!({"ids": ids})).await;
+    app.post_ok(&admin, "/api/system/dictType/deleteDictType", json!({"ids": [type_id]})).await;
+    assert_eq!(app.post(&admin, "/api/system/dictType/queryDictTypeDetail", json!({"id": type_id})).await["msg"], "业务异常: 字典类型不存在");
+}
@@ -0,0 +1,40 @@
+use crate::tests::test_app::{TestApp, ADMIN_MOBILE, PASSWORD};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_login_log() {
+    let app = TestApp::new().await;
+    app.login(ADMIN_MOBILE, "wrong-password").await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+
+    let list = app.post(&admin, "/api/system/loginLog/queryLoginLogList", json!({"pageNo": 1, "pageSize": 10, "loginName": ADMIN_MOBILE, "status": 0})).await;
+    assert_eq!(list["total"], 1, "{}", list);
+    let id = list["data"][0]["id"].as_i64().unwrap();
+
+    let detail = app.post_ok(&admin, "/api/system/loginLog/queryLoginLogDetail", json!({"id": id})).await;
+    assert_eq!(detail["msg"], "密码不正确");
+
+    app.post_ok(&admin, "/api/system/loginLog/deleteLoginLog", json!({"ids": [id]})).await;
+    let list = app.post(&admin, "/api/system/loginLog/queryLoginLogList", json!({"pageNo": 1, "pageSize": 10, "loginName": ADMIN_MOBILE})).await;
+    assert_eq!(list["total"], 1, "{}", list);
+}
+
+#[tokio::test]
+async fn test_operate_log() {
+    let app = TestApp::new().await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+
+    let post = json!({"postCode": "ops", "postName": "运维工程师", "sort": 5, "status": 1, "remark": ""});
+    app.post_ok(&admin, "/api/system/post/addPost", post).await;
+    app.post_ok(&admin, "/api/system/post/queryPostList", json!({"pageNo": 1, "pageSize": 10})).await;
+    app.flush_operate_log().await;
+
+    //只记录修改数据的操作
+    let list = app.post(&admin, "/api/system/operateLog/queryOperateLogList", json!({"pageNo": 1, "pageSize": 10})).await;
+    assert_eq!(list["total"], 1, "{}", list);
+    assert_eq!(list["data"][0]["businessType"], 1);
+    assert_eq!(list["data"][0]["operateUrl"], "/api/system/post/addPost");
+
+    let list = app.post(&admin, "/api/system/operateLog/queryOperateLogList", json!({"pageNo": 1, "pageSize": 10, "businessType": 3})).await;
+    assert_eq!(list["total"], 0, "{}", list);
+}
@@ -0,0 +1,105 @@
+use crate::tests::test_app::{empty_db, TestApp};
+use crate::utils::migration_util::{migrate, migrations, rollback, seed, split_statements, Migration, SEEDS};
+
+const V1: Migration = Migration {
+    version: 1,
+    name: "create_demo",
+    up: "create table demo\n(\n    id   integer primary key,\n    name varchar(50) default '' not null -- 名称;\n);\ninsert into demo (id, name) values (1, 'a;b');",
+    down: Some("drop table demo;"),
+};
+const V2: Migration = Migration {
+    version: 2,
+    name: "add_demo_remark",
+    up: "alter table demo add column remark varchar(255) default '' not null;",
+    down: None,
+};
+
+#[tokio::test]
+async fn test_migrate() {
+    let (rb, _db) = empty_db().await;
+    let rb = &rb;
+
+    assert_eq!(migrate(rb, &[V1]).await.unwrap(), vec![1]);
+    assert_eq!(migrate(rb, &[V1, V2]).await.unwrap(), vec![2]);
+    assert!(migrate(rb, &[V1, V2]).await.unwrap().is_empty());
+    let name: String = rb.query_decode("select name from demo where id = 1", vec![]).await.unwrap();
+    assert_eq!(name, "a;b");
+
+    //已执行的迁移被修改
+    let changed = Migration { up: "create table demo (id integer primary key);", ..V1 };
+    assert!(migrate(rb, &[changed, V2]).await.unwrap_err().contains("checksum不一致"));
+    //程序中缺少数据库中已执行的迁移
+    assert!(migrate(rb, &[V1]).await.unwrap_err().contains("不存在"));
+
+    assert!(rollback(rb, &[V1, V2]).await.unwrap_err().contains("没有回滚脚本"));
+    rb.exec("delete from schema_migrations where version = 2", vec![]).await.unwrap();
+    rb.exec("alter table demo drop column remark", vec![]).await.unwrap();
+    assert_eq!(rollback(rb, &[V1]).await.unwrap(), Some(1));
+    assert_eq!(rollback(rb, &[V1]).await.unwrap(), None);
+    assert!(rb.query("select 1 from demo", vec![]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_migrate_schema() {
+    let (rb, db) = empty_db().await;
+    let (rb, schema) = (&rb, migrations(db.kind()));
+
+    assert_eq!(migrate(rb, schema).await.unwrap(), vec![1, 2]);
+    assert_eq!(seed(rb, SEEDS).await.unwrap().len(), SEEDS.len());
+    //先回滚权限标识和双因素认证的表结构变更
+    assert_eq!(rollback(rb, schema).await.unwrap(), Some(2));
+    assert!(rb.query("select 1 from sys_user_mfa", vec![]).await.is_err());
+    assert!(rb.query("select perms from sys_menu", vec![]).await.is_err());
+    //再回滚初始化的表结构
+    assert_eq!(rollback(rb, schema).await.unwrap(), Some(1));
+    assert!(rb.query("select 1 from sys_user", vec![]).await.is_err());
+    assert_eq!(migrate(rb, schema).await.unwrap(), vec![1, 2]);
+}
+
+#[tokio::test]
+async fn test_migrate_legacy_schema() {
+    let (rb, db) = empty_db().await;
+    let (rb, schema) = (&rb, migrations(db.kind()));
+
+    //模拟按docs/sql手动建表的旧数据库: 有业务表和数据,但没有schema_migrations
+    for sql in split_statements(schema[0].up) {
+        rb.exec(&sql, vec![]).await.unwrap();
+    }
+    rb.exec("insert into sys_menu (id, menu_name, menu_type, parent_id, api_url) values (1, '用户管理', 2, 0, '/api/system/user/queryUserList')", vec![])
+        .await
+        .unwrap();
+    rb.exec("insert into sys_user (id, mobile, user_name, nick_name, password) values (1, '18613030111', 'legacy', 'legacy', '123456')", vec![])
+        .await
+        .unwrap();
+
+    assert_eq!(migrate(rb, schema).await.unwrap(), vec![1, 2]);
+    let perms: String = rb.query_decode("select perms from sys_menu where id = 1", vec![]).await.unwrap();
+    assert_eq!(perms, "");
+    let count: u64 = rb.query_decode("select count(1) from sys_role where mfa_required = 1", vec![]).await.unwrap();
+    assert_eq!(count, 0);
+    let count: u64 = rb.query_decode("select count(1) from sys_user_mfa", vec![]).await.unwrap();
+    assert_eq!(count, 0);
+
+    //已有数据的表不会被初始数据覆盖
+    let seeded = seed(rb, SEEDS).await.unwrap();
+    assert!(!seeded.contains(&"sys_menu"));
+    assert!(!seeded.contains(&"sys_user"));
+    let password: String = rb.query_decode("select password from sys_user where id = 1", vec![]).await.unwrap();
+    assert_eq!(password, "123456");
+}
+
+#[tokio::test]
+async fn test_seed() {
+    let app = TestApp::new().await;
+    let rb = &app.state.rb;
+
+    //TestApp已经导入过初始数据,再次执行不会重复导入
+    assert!(seed(rb, SEEDS).await.unwrap().is_empty());
+    let count: u64 = rb.query_decode("select count(1) from sys_user", vec![]).await.unwrap();
+    assert_eq!(count, 2);
+
+    rb.exec("delete from sys_notice", vec![]).await.unwrap();
+    assert_eq!(seed(rb, SEEDS).await.unwrap(), vec!["sys_notice"]);
+    let count: u64 = rb.query_decode("select count(1) from sys_notice", vec![]).await.unwrap();
+    assert_eq!(count, 2);
+}
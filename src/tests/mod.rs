@@ -0,0 +1,13 @@
+mod test_app;
+
+mod auth_test;
+mod dept_test;
+mod dict_test;
+mod log_test;
+mod migration_test;
+mod notice_test;
+mod openapi_test;
+#[cfg(feature = "postgres")]
+mod postgres_test;
+mod role_test;
+mod user_test;
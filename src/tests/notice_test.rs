@@ -0,0 +1,27 @@
+use crate::tests::test_app::{TestApp, ADMIN_MOBILE, PASSWORD};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_notice() {
+    let app = TestApp::new().await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+
+    let notice = json!({"noticeTitle": "系统升级", "noticeType": 1, "noticeContent": "今晚22点升级", "status": 1, "remark": ""});
+    app.post_ok(&admin, "/api/system/notice/addNotice", notice.clone()).await;
+    assert_eq!(app.post(&admin, "/api/system/notice/addNotice", notice).await["msg"], "业务异常: 公告标题已存在");
+
+    let list = app.post(&admin, "/api/system/notice/queryNoticeList", json!({"pageNo": 1, "pageSize": 10, "noticeTitle": "系统升级"})).await;
+    assert_eq!(list["total"], 1, "{}", list);
+    let id = list["data"][0]["id"].as_i64().unwrap();
+
+    let update = json!({"id": id, "noticeTitle": "系统升级通知", "noticeType": 2, "noticeContent": "改为23点升级", "status": 1, "remark": ""});
+    app.post_ok(&admin, "/api/system/notice/updateNotice", update).await;
+    app.post_ok(&admin, "/api/system/notice/updateNoticeStatus", json!({"ids": [id], "status": 0})).await;
+    let detail = app.post_ok(&admin, "/api/system/notice/queryNoticeDetail", json!({"id": id})).await;
+    assert_eq!(detail["noticeTitle"], "系统升级通知");
+    assert_eq!(detail["noticeType"], 2);
+    assert_eq!(detail["status"], 0);
+
+    app.post_ok(&admin, "/api/system/notice/deleteNotice", json!({"ids": [id]})).await;
+    assert_eq!(app.post(&admin, "/api/system/notice/queryNoticeDetail", json!({"id": id})).await["msg"], "业务异常: 通知公告表不存在");
+}
@@ -0,0 +1,52 @@
+use crate::routes::build_system_route;
+use crate::tests::test_app::TestApp;
+use salvo::Router;
+use std::collections::BTreeSet;
+
+//收集路由树中所有的(请求方式, 路径)
+fn collect(router: &Router, prefix: &str, routes: &mut BTreeSet<(String, String)>) {
+    let mut path = prefix.to_string();
+    let mut method = None;
+    for filter in router.filters() {
+        let info = format!("{:?}", filter);
+        if let Some(x) = info.strip_prefix("path:") {
+            path.push_str(x);
+        } else if let Some(x) = info.strip_prefix("method:") {
+            method = Some(x.to_lowercase());
+        }
+    }
+    if let Some(method) = method {
+        routes.insert((method, path.clone()));
+    }
+    for child in router.routers() {
+        collect(child, &path, routes);
+    }
+}
+
+#[tokio::test]
+async fn test_openapi() {
+    let app = TestApp::new().await;
+    let doc = app.get("", "/api/openapi.json").await;
+    assert_eq!(doc["components"]["securitySchemes"]["bearerAuth"]["scheme"], "bearer", "{}", doc["components"]);
+
+    //需要token的接口都有文档,并且要求bearerAuth
+    let mut routes = BTreeSet::new();
+    collect(&build_system_route(), "/api", &mut routes);
+    for (method, path) in &routes {
+        let operation = &doc["paths"][path][method];
+        assert!(operation.is_object(), "{} {} without api doc", method, path);
+        assert_eq!(operation["security"][0]["bearerAuth"], serde_json::json!([]), "{} {} should require token", method, path);
+    }
+
+    //登录接口不需要token
+    let login = &doc["paths"]["/api/system/user/login"]["post"];
+    assert!(login["security"].is_null(), "{}", login);
+    assert_eq!(login["tags"][0], "登录认证");
+    let body = login["requestBody"]["content"]["application/json"]["schema"]["$ref"].as_str().unwrap_or_default();
+    assert!(body.ends_with("UserLoginReq"), "{}", login);
+
+    //Swagger UI使用内置的静态资源,不依赖外部CDN
+    let html = app.get_text("/api/swagger-ui/").await;
+    assert!(html.contains("swagger-ui"), "{}", html);
+    assert!(!html.contains("unpkg.com"), "{}", html);
+}
@@ -0,0 +1,80 @@
+use crate::tests::test_app::empty_db;
+use crate::utils::db_util::DbKind;
+use crate::utils::migration_util::{migrate, migrations, seed, SEEDS};
+use rbs::{value, Value};
+use serde_json::json;
+
+//需要设置TEST_POSTGRES_URL,否则使用SQLite时直接跳过
+#[tokio::test]
+async fn test_pg_types() {
+    let (rb, db) = empty_db().await;
+    if db.kind() != DbKind::Postgres {
+        return;
+    }
+    let rb = &rb;
+
+    let sql = "create table demo
+(
+    id       bigserial primary key,
+    amount   numeric(10, 2),
+    extra    jsonb,
+    tags     text[],
+    role_ids bigint[],
+    birthday date,
+    login_at time
+)";
+    rb.exec(sql, vec![]).await.unwrap();
+    let params = vec![
+        "12.50".into(),
+        value!({"theme": "dark", "size": 2}),
+        Value::Array(vec!["a".into(), "b".into()]),
+        Value::Array(vec![1.into(), "2".into()]),
+        "2026-10-19".into(),
+        "08:05:17".into(),
+    ];
+    let result = rb.exec("insert into demo (amount, extra, tags, role_ids, birthday, login_at) values (?, ?, ?, ?, ?, ?)", params).await.unwrap();
+    assert_eq!(result.last_insert_id, Value::I64(1));
+    rb.exec("insert into demo (amount, extra) values (?, ?)", vec![Value::F64(0.5), "[1, 2]".into()]).await.unwrap();
+
+    let rows: Vec<serde_json::Value> = rb.query_decode("select amount, extra, tags, role_ids, birthday, login_at from demo order by id", vec![]).await.unwrap();
+    assert_eq!(rows[0]["amount"], "12.50");
+    assert_eq!(rows[0]["extra"], json!({"theme": "dark", "size": 2}));
+    assert_eq!(rows[0]["tags"], json!(["a", "b"]));
+    assert_eq!(rows[0]["role_ids"], json!([1, 2]));
+    assert_eq!(rows[0]["birthday"], "2026-10-19");
+    assert_eq!(rows[0]["login_at"], "08:05:17");
+    assert_eq!(rows[1]["amount"], "0.50");
+    assert_eq!(rows[1]["extra"], json!([1, 2]));
+    assert_eq!(rows[1]["tags"], serde_json::Value::Null);
+
+    //information_schema中的字段是域类型
+    let sql = "select numeric_precision from information_schema.columns where table_schema = current_schema() and table_name = 'demo' and column_name = 'amount'";
+    let precision: i64 = rb.query_decode(sql, vec![]).await.unwrap();
+    assert_eq!(precision, 10);
+
+    //不支持的类型返回错误,不会按字符串读取
+    let err = rb.query("select '127.0.0.1'::inet as ip", vec![]).await.unwrap_err();
+    assert!(err.to_string().contains("inet"), "{}", err);
+    let err = rb.exec("update demo set extra = ? where id = 1", vec!["not json".into()]).await.unwrap_err();
+    assert!(err.to_string().contains("jsonb"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_pg_migrate_lock() {
+    let (rb, db) = empty_db().await;
+    if db.kind() != DbKind::Postgres {
+        return;
+    }
+    let (rb, schema) = (&rb, migrations(db.kind()));
+
+    //两个实例同时迁移时只有一个实例执行,另一个等待后跳过
+    let (a, b) = tokio::join!(migrate(rb, schema), migrate(rb, schema));
+    let mut versions = [a.unwrap(), b.unwrap()];
+    versions.sort();
+    assert_eq!(versions, [vec![], vec![1, 2]]);
+
+    let (a, b) = tokio::join!(seed(rb, SEEDS), seed(rb, SEEDS));
+    assert_eq!(a.unwrap().len() + b.unwrap().len(), SEEDS.len());
+    let count: u64 = rb.query_decode("select count(1) from sys_user", vec![]).await.unwrap();
+    assert_eq!(count, 2);
+}
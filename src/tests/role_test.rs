@@ -0,0 +1,130 @@
+use crate::tests::test_app::{TestApp, ADMIN_MOBILE, PASSWORD, TEST_MOBILE};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_role() {
+    let app = TestApp::new().await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+
+    let role = json!({"roleName": "运营", "roleKey": "operator", "dataScope": 1, "status": 1, "mfaRequired": 0, "remark": "运营人员"});
+    app.post_ok(&admin, "/api/system/role/addRole", role.clone()).await;
+    assert_eq!(app.post(&admin, "/api/system/role/addRole", role).await["msg"], "业务异常: 角色名称已存在");
+
+    let list = app.post(&admin, "/api/system/role/queryRoleList", json!({"pageNo": 1, "pageSize": 10, "roleName": "运营"})).await;
+    assert_eq!(list["total"], 1, "{}", list);
+    let id = list["data"][0]["id"].as_i64().unwrap();
+
+    let update = json!({"id": id, "roleName": "运营主管", "roleKey": "operator", "dataScope": 3, "status": 1, "mfaRequired": 0, "remark": "运营人员"});
+    app.post_ok(&admin, "/api/system/role/updateRole", update).await;
+    let detail = app.post_ok(&admin, "/api/system/role/queryRoleDetail", json!({"id": id})).await;
+    assert_eq!(detail["roleName"], "运营主管");
+    assert_eq!(detail["dataScope"], 3);
+
+    app.post_ok(&admin, "/api/system/role/updateRoleMenu", json!({"roleId": id, "menuIds": [1, 2]})).await;
+    let menus = app.post_ok(&admin, "/api/system/role/queryRoleMenu", json!({"roleId": id})).await;
+    assert_eq!(menus["menuIds"], json!([1, 2]));
+
+    app.post_ok(&admin, "/api/system/role/updateRoleDept", json!({"roleId": id, "dataScope": 2, "deptIds": [4, 5]})).await;
+    let depts = app.post_ok(&admin, "/api/system/role/queryRoleDept", json!({"roleId": id})).await;
+    assert_eq!(depts["dataScope"], 2);
+    assert_eq!(depts["deptIds"], json!([4, 5]));
+
+    //超级管理员角色不允许修改和删除
+    assert_eq!(app.post(&admin, "/api/system/role/deleteRole", json!({"ids": [1]})).await["msg"], "业务异常: 不允许操作超级管理员角色");
+
+    app.post_ok(&admin, "/api/system/user/updateUserRole", json!({"userId": 2, "roleIds": [id]})).await;
+    let allocated = app.post(&admin, "/api/system/role/queryAllocatedList", json!({"pageNo": 1, "pageSize": 10, "roleId": id})).await;
+    assert_eq!(allocated["total"], 1, "{}", allocated);
+    assert_eq!(allocated["data"][0]["mobile"], TEST_MOBILE);
+    assert_eq!(app.post(&admin, "/api/system/role/deleteRole", json!({"ids": [id]})).await["msg"], "业务异常: 已分配,不能删除");
+
+    app.post_ok(&admin, "/api/system/role/cancelAuthUser", json!({"roleId": id, "userId": 2})).await;
+    app.post_ok(&admin, "/api/system/role/deleteRole", json!({"ids": [id]})).await;
+    let list = app.post(&admin, "/api/system/role/queryRoleList", json!({"pageNo": 1, "pageSize": 10, "roleName": "运营主管"})).await;
+    assert_eq!(list["total"], 0);
+}
+
+#[tokio::test]
+async fn test_menu() {
+    let app = TestApp::new().await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+
+    let menu = json!({"menuName": "测试目录", "menuType": 1, "visible": 1, "status": 1, "sort": 1, "parentId": 0, "menuUrl": "/test", "apiUrl": "", "perms": "", "menuIcon": "", "remark": ""});
+    app.post_ok(&admin, "/api/system/menu/addMenu", menu.clone()).await;
+    assert_eq!(app.post(&admin, "/api/system/menu/addMenu", menu).await["msg"], "业务异常: 菜单名称已存在");
+
+    let menus = app.post_ok(&admin, "/api/system/menu/queryMenuList", json!({})).await;
+    let parent_id = menus.as_array().unwrap().iter().find(|x| x["menuName"] == "测试目录").expect("menu not found")["id"].as_i64().unwrap();
+
+    let child = json!({"menuName": "测试按钮", "menuType": 3, "visible": 1, "status": 1, "sort": 1, "parentId": parent_id, "menuUrl": "/test/button", "apiUrl": "/api/test/button", "perms": "test:button", "menuIcon": "", "remark": ""});
+    app.post_ok(&admin, "/api/system/menu/addMenu", child).await;
+    assert_eq!(app.post(&admin, "/api/system/menu/deleteMenu", json!({"id": parent_id})).await["msg"], "业务异常: 存在子菜单,不允许删除");
+
+    let update = json!({"id": parent_id, "menuName": "测试目录2", "menuType": 1, "visible": 0, "status": 1, "sort": 2, "parentId": 0, "menuUrl": "/test", "apiUrl": "", "perms": "", "menuIcon": "", "remark": ""});
+    app.post_ok(&admin, "/api/system/menu/updateMenu", update).await;
+    let detail = app.post_ok(&admin, "/api/system/menu/queryMenuDetail", json!({"id": parent_id})).await;
+    assert_eq!(detail["menuName"], "测试目录2");
+    assert_eq!(detail["visible"], 0);
+
+    let menus = app.post_ok(&admin, "/api/system/menu/queryMenuList", json!({})).await;
+    let child_id = menus.as_array().unwrap().iter().find(|x| x["parentId"] == parent_id).expect("child not found")["id"].as_i64().unwrap();
+    app.post_ok(&admin, "/api/system/menu/deleteMenu", json!({"id": child_id})).await;
+    app.post_ok(&admin, "/api/system/menu/deleteMenu", json!({"id": parent_id})).await;
+    assert_eq!(app.post(&admin, "/api/system/menu/queryMenuDetail", json!({"id": parent_id})).await["msg"], "业务异常: 菜单信息不存在");
+}
+
+#[tokio::test]
+async fn test_role_user_data_scope() {
+    let app = TestApp::new().await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+    let token = app.grant_test_user(&admin, &["system:role:allocatedList", "system:role:unallocatedList", "system:role:cancelAuth", "system:role:auth"]).await;
+
+    //演示账号的角色只能访问自己的数据
+    let role_id: i64 = app.state.rb.query_decode("select role_id from sys_user_role where user_id = 2", vec![]).await.unwrap();
+    app.post_ok(&admin, "/api/system/role/updateRoleDept", json!({"roleId": role_id, "dataScope": 2, "deptIds": []})).await;
+
+    let allocated = app.post(&token, "/api/system/role/queryAllocatedList", json!({"pageNo": 1, "pageSize": 10, "roleId": 1})).await;
+    assert_eq!(allocated["total"], 0, "{}", allocated);
+    let allocated = app.post(&admin, "/api/system/role/queryAllocatedList", json!({"pageNo": 1, "pageSize": 10, "roleId": 1})).await;
+    assert_eq!(allocated["total"], 1, "{}", allocated);
+    let unallocated = app.post(&token, "/api/system/role/queryUnallocatedList", json!({"pageNo": 1, "pageSize": 10, "roleId": 1})).await;
+    assert_eq!(unallocated["total"], 1, "{}", unallocated);
+    assert_eq!(unallocated["data"][0]["mobile"], TEST_MOBILE);
+
+    let denied = "业务异常: 没有权限访问该用户的数据";
+    assert_eq!(app.post(&token, "/api/system/role/cancelAuthUser", json!({"roleId": 1, "userId": 1})).await["msg"], denied);
+    assert_eq!(app.post(&token, "/api/system/role/batchCancelAuthUser", json!({"roleId": 1, "userIds": [1]})).await["msg"], denied);
+    assert_eq!(app.post(&token, "/api/system/role/batchAuthUser", json!({"roleId": role_id, "userIds": [1]})).await["msg"], denied);
+    let allocated = app.post(&admin, "/api/system/role/queryAllocatedList", json!({"pageNo": 1, "pageSize": 10, "roleId": role_id})).await;
+    assert_eq!(allocated["total"], 1, "{}", allocated);
+}
+
+#[tokio::test]
+async fn test_role_dept_data_scope() {
+    let app = TestApp::new().await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+    let token = app.grant_test_user(&admin, &["system:role:authDept"]).await;
+    let own_role_id: i64 = app.state.rb.query_decode("select role_id from sys_user_role where user_id = 2", vec![]).await.unwrap();
+    app.post_ok(&admin, "/api/system/role/updateRoleDept", json!({"roleId": own_role_id, "dataScope": 2, "deptIds": []})).await;
+
+    app.post_ok(&admin, "/api/system/role/addRole", json!({"roleName": "数据权限测试", "roleKey": "scope", "dataScope": 2, "status": 1, "remark": ""})).await;
+    let roles = app.post_ok(&admin, "/api/system/role/queryRoleList", json!({"pageNo": 1, "pageSize": 10, "roleName": "数据权限测试"})).await;
+    let role_id = roles[0]["id"].as_i64().unwrap();
+
+    //只能访问自己数据的用户不能授予全部数据权限,也不能修改自己拥有的角色
+    let resp = app.post(&token, "/api/system/role/updateRoleDept", json!({"roleId": role_id, "dataScope": 1, "deptIds": []})).await;
+    assert_eq!(resp["msg"], "业务异常: 没有权限授予全部数据权限");
+    let resp = app.post(&token, "/api/system/role/updateRoleDept", json!({"roleId": own_role_id, "dataScope": 2, "deptIds": []})).await;
+    assert_eq!(resp["msg"], "业务异常: 不能修改自己拥有的角色的数据权限");
+    let resp = app.post(&token, "/api/system/role/updateRoleDept", json!({"roleId": role_id, "dataScope": 2, "deptIds": [1]})).await;
+    assert_eq!(resp["msg"], "业务异常: 没有权限访问该部门的数据");
+
+    //本部门数据权限按角色下用户所在的部门校验
+    app.post_ok(&token, "/api/system/role/updateRoleDept", json!({"roleId": role_id, "dataScope": 3, "deptIds": []})).await;
+    app.post_ok(&admin, "/api/system/role/batchAuthUser", json!({"roleId": role_id, "userIds": [1]})).await;
+    let resp = app.post(&token, "/api/system/role/updateRoleDept", json!({"roleId": role_id, "dataScope": 4, "deptIds": []})).await;
+    assert_eq!(resp["msg"], "业务异常: 没有权限访问该部门的数据");
+
+    let data_scope: i64 = app.state.rb.query_decode("select data_scope from sys_role where id = ?", vec![role_id.into()]).await.unwrap();
+    assert_eq!(data_scope, 3);
+}
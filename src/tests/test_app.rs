@@ -0,0 +1,179 @@
+use crate::common::app_config::AppConfig;
+use crate::common::app_state::AppState;
+use crate::route;
+use crate::driver::sqlite_driver::SqliteDriver;
+use crate::utils::db_util::DbKind;
+use crate::utils::migration_util::{migrate, migrations, seed, SEEDS};
+use crate::utils::session_store_util::SessionStore;
+use rbatis::RBatis;
+use salvo::test::{ResponseExt, TestClient};
+use salvo::Service;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+pub const ADMIN_MOBILE: &str = "18613030111"; //超级管理员
+pub const TEST_MOBILE: &str = "18613030222"; //演示账号,初始没有角色
+pub const PASSWORD: &str = "123456"; //初始数据中两个账号的密码
+
+const BASE_URL: &str = "http://127.0.0.1:8100";
+
+static DB_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+/*
+ *集成测试应用: 每个测试使用独立的数据库和内存会话存储,路由和main中完全一致
+ */
+pub struct TestApp {
+    pub state: Arc<AppState>, //应用状态
+    service: Service,         //完整的路由
+    _db: TestDb,              //测试数据库,测试结束后删除
+}
+
+/*
+ *测试数据库: 默认是临时的SQLite文件
+ *启用postgres特性并设置TEST_POSTGRES_URL时,在这个库中为每个测试创建独立的schema
+ */
+pub enum TestDb {
+    Sqlite(PathBuf),
+    #[cfg(feature = "postgres")]
+    Postgres { url: String, schema: String },
+}
+
+impl TestDb {
+    pub fn kind(&self) -> DbKind {
+        match self {
+            TestDb::Sqlite(_) => DbKind::Sqlite,
+            #[cfg(feature = "postgres")]
+            TestDb::Postgres { .. } => DbKind::Postgres,
+        }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        match self {
+            TestDb::Sqlite(path) => {
+                let _ = std::fs::remove_file(path);
+            }
+            //drop时不能await,在单独的线程和运行时中删除schema
+            #[cfg(feature = "postgres")]
+            TestDb::Postgres { url, schema } => {
+                let (url, sql) = (url.clone(), format!("drop schema if exists {} cascade", schema));
+                let _ = std::thread::spawn(move || {
+                    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().expect("create runtime error");
+                    runtime.block_on(async move {
+                        let rb = RBatis::new();
+                        rb.init(crate::driver::postgres_driver::PgDriver {}, &url).expect("init rbatis error");
+                        rb.exec(&sql, vec![]).await
+                    })
+                })
+                .join();
+            }
+        }
+    }
+}
+
+/// 创建一个新的空数据库
+pub async fn empty_db() -> (RBatis, TestDb) {
+    let seq = DB_SEQ.fetch_add(1, Ordering::SeqCst);
+    #[cfg(feature = "postgres")]
+    if let Ok(url) = std::env::var("TEST_POSTGRES_URL") {
+        let schema = format!("salvo_admin_test_{}_{}", std::process::id(), seq);
+        let rb = RBatis::new();
+        rb.init(crate::driver::postgres_driver::PgDriver {}, &url).expect("init rbatis error");
+        rb.exec(&format!("create schema {}", schema), vec![]).await.expect("create schema error");
+        let separator = if url.contains('?') { '&' } else { '?' };
+        let rb = RBatis::new();
+        rb.init(crate::driver::postgres_driver::PgDriver {}, &format!("{}{}options=-c%20search_path%3D{}", url, separator, schema)).expect("init rbatis error");
+        return (rb, TestDb::Postgres { url, schema });
+    }
+
+    let db_path = std::env::temp_dir().join(format!("salvo-admin-test-{}-{}.db", std::process::id(), seq));
+    let _ = std::fs::remove_file(&db_path);
+    let rb = RBatis::new();
+    rb.init(SqliteDriver {}, &format!("sqlite://{}", db_path.display())).expect("init rbatis error");
+    (rb, TestDb::Sqlite(db_path))
+}
+
+impl TestApp {
+    pub async fn new() -> Self {
+        let (rb, db) = empty_db().await;
+        migrate(&rb, migrations(db.kind())).await.expect("migrate error");
+        seed(&rb, SEEDS).await.expect("seed error");
+
+        let mut config = AppConfig::default();
+        config.jwt.secret = "salvo-admin-test".to_string();
+        config.login_guard.delay_step_ms = 0;
+        let state = Arc::new(AppState::new(rb, SessionStore::memory(), config).expect("create app state error"));
+        let service = Service::new(route(state.clone()));
+        TestApp { state, service, _db: db }
+    }
+
+    /// 登录,返回完整的响应
+    pub async fn login(&self, mobile: &str, password: &str) -> Value {
+        self.send(TestClient::post(format!("{}/api/system/user/login", BASE_URL)).json(&json!({"mobile": mobile, "password": password}))).await
+    }
+
+    /// 登录成功后返回访问token
+    pub async fn token(&self, mobile: &str, password: &str) -> String {
+        let resp = self.login(mobile, password).await;
+        assert_eq!(resp["code"], 0, "login failed: {}", resp);
+        resp["data"]["token"].as_str().expect("token missing").to_string()
+    }
+
+    pub async fn post(&self, token: &str, path: &str, body: Value) -> Value {
+        self.send(TestClient::post(format!("{}{}", BASE_URL, path)).bearer_auth(token).json(&body)).await
+    }
+
+    pub async fn get(&self, token: &str, path: &str) -> Value {
+        self.send(TestClient::get(format!("{}{}", BASE_URL, path)).bearer_auth(token)).await
+    }
+
+    /// 返回响应的文本内容,用于不是json的页面
+    pub async fn get_text(&self, path: &str) -> String {
+        let mut res = TestClient::get(format!("{}{}", BASE_URL, path)).send(&self.service).await;
+        res.take_string().await.expect("read response error")
+    }
+
+    /// 请求成功(code为0)时返回data
+    pub async fn post_ok(&self, token: &str, path: &str, body: Value) -> Value {
+        let resp = self.post(token, path, body).await;
+        assert_eq!(resp["code"], 0, "{} failed: {}", path, resp);
+        resp["data"].clone()
+    }
+
+    async fn send(&self, request: salvo::test::RequestBuilder) -> Value {
+        let mut res = request.send(&self.service).await;
+        res.take_json::<Value>().await.expect("response is not json")
+    }
+
+    /// 给演示账号分配一个只拥有perms权限的角色,返回演示账号的token
+    pub async fn grant_test_user(&self, admin: &str, perms: &[&str]) -> String {
+        let role_name = format!("测试角色{}", DB_SEQ.fetch_add(1, Ordering::SeqCst));
+        self.post_ok(admin, "/api/system/role/addRole", json!({"roleName": role_name, "roleKey": "test", "dataScope": 1, "status": 1, "remark": ""})).await;
+        let roles = self.post_ok(admin, "/api/system/role/queryRoleList", json!({"pageNo": 1, "pageSize": 100, "roleName": role_name})).await;
+        let role_id = roles[0]["id"].as_i64().expect("role id missing");
+
+        let menu_ids = self.menu_ids(perms).await;
+        self.post_ok(admin, "/api/system/role/updateRoleMenu", json!({"roleId": role_id, "menuIds": menu_ids})).await;
+        self.post_ok(admin, "/api/system/user/updateUserRole", json!({"userId": 2, "roleIds": [role_id]})).await;
+        self.token(TEST_MOBILE, PASSWORD).await
+    }
+
+    /// 按权限标识查询初始数据中的按钮菜单id
+    async fn menu_ids(&self, perms: &[&str]) -> Vec<i64> {
+        let mut ids = Vec::new();
+        for perm in perms {
+            let sql = "select id from sys_menu where perms = ? limit 1";
+            let id: i64 = self.state.rb.query_decode(sql, vec![(*perm).into()]).await.unwrap_or_else(|e| panic!("menu {} not found: {}", perm, e));
+            ids.push(id);
+        }
+        ids
+    }
+
+    /// 等待后台任务写入操作日志
+    pub async fn flush_operate_log(&self) {
+        self.state.operate_log_writer.shutdown(std::time::Duration::from_secs(5)).await;
+    }
+}
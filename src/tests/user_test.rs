@@ -0,0 +1,44 @@
+use crate::tests::test_app::{TestApp, ADMIN_MOBILE, PASSWORD};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_user() {
+    let app = TestApp::new().await;
+    let admin = app.token(ADMIN_MOBILE, PASSWORD).await;
+
+    let user = json!({"mobile": "13800000001", "userName": "zhangsan", "nickName": "张三", "email": "zs@qq.com", "password": "Zs@123456", "status": 1, "deptId": 4, "remark": "测试", "postIds": [1, 2]});
+    app.post_ok(&admin, "/api/system/user/addUser", user.clone()).await;
+    assert_eq!(app.post(&admin, "/api/system/user/addUser", user).await["msg"], "业务异常: 登录账号已存在");
+
+    let list = app.post(&admin, "/api/system/user/queryUserList", json!({"pageNo": 1, "pageSize": 10, "mobile": "13800000001"})).await;
+    assert_eq!(list["total"], 1, "{}", list);
+    let id = list["data"][0]["id"].as_i64().unwrap();
+
+    //按部门查询时包含下级部门的用户
+    let list = app.post(&admin, "/api/system/user/queryUserList", json!({"pageNo": 1, "pageSize": 10, "deptId": 2})).await;
+    assert_eq!(list["total"], 1, "{}", list);
+
+    let detail = app.post_ok(&admin, "/api/system/user/queryUserDetail", json!({"id": id})).await;
+    assert_eq!(detail["userName"], "zhangsan");
+    assert_eq!(detail["deptInfo"]["deptName"], "研发部门");
+    assert_eq!(detail["postIds"], json!([1, 2]));
+
+    let update = json!({"id": id, "mobile": "13800000001", "userName": "zhangsan", "nickName": "张三丰", "email": "zs@qq.com", "status": 1, "deptId": 4, "remark": "测试", "postIds": [3]});
+    app.post_ok(&admin, "/api/system/user/updateUser", update).await;
+    let detail = app.post_ok(&admin, "/api/system/user/queryUserDetail", json!({"id": id})).await;
+    assert_eq!(detail["nickName"], "张三丰");
+    assert_eq!(detail["postIds"], json!([3]));
+
+    app.post_ok(&admin, "/api/system/user/updateUserStatus", json!({"ids": [id], "status": 0})).await;
+    assert_eq!(app.post_ok(&admin, "/api/system/user/queryUserDetail", json!({"id": id})).await["status"], 0);
+
+    let roles = app.post_ok(&admin, "/api/system/user/queryUserRole", json!({"userId": 1})).await;
+    assert_eq!(roles["userRoleIds"], json!([1]));
+
+    assert_eq!(app.login("13800000001", "Zs@123456").await["msg"], "业务异常: 用户已被禁用");
+
+    assert_eq!(app.post(&admin, "/api/system/user/deleteUser", json!({"ids": [1]})).await["msg"], "业务异常: 当前用户不能删除");
+    app.post_ok(&admin, "/api/system/user/deleteUser", json!({"ids": [id]})).await;
+    let list = app.post(&admin, "/api/system/user/queryUserList", json!({"pageNo": 1, "pageSize": 10, "mobile": "13800000001"})).await;
+    assert_eq!(list["total"], 0);
+}
@@ -0,0 +1,205 @@
+use deadpool_redis::redis::{cmd, RedisError};
+use serde::{Deserialize, Serialize};
+
+use crate::common::error::AppError;
+use crate::utils::session_store_util::StoreConnection;
+
+//去掉了容易混淆的0/O、1/I
+const CHARSET: &[u8] = b"23456789ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+//5x7点阵字体,每行低5位有效,最高位在左
+const GLYPHS: [(u8, [u8; 7]); 32] = [
+    (b'2', [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F]),
+    (b'3', [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E]),
+    (b'4', [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02]),
+    (b'5', [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E]),
+    (b'6', [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E]),
+    (b'7', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08]),
+    (b'8', [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E]),
+    (b'9', [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C]),
+    (b'A', [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11]),
+    (b'B', [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E]),
+    (b'C', [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E]),
+    (b'D', [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C]),
+    (b'E', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F]),
+    (b'F', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10]),
+    (b'G', [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F]),
+    (b'H', [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11]),
+    (b'J', [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C]),
+    (b'K', [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11]),
+    (b'L', [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F]),
+    (b'M', [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11]),
+    (b'N', [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11]),
+    (b'P', [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10]),
+    (b'Q', [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D]),
+    (b'R', [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11]),
+    (b'S', [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E]),
+    (b'T', [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04]),
+    (b'U', [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E]),
+    (b'V', [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04]),
+    (b'W', [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A]),
+    (b'X', [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11]),
+    (b'Y', [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04]),
+    (b'Z', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F]),
+];
+
+/*
+ *图片验证码配置
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptchaConfig {
+    pub enabled: bool,               //是否启用验证码
+    pub required_after_failures: i64, //账号登录失败达到该次数后才需要验证码(0:每次登录都需要)
+    pub length: usize,               //验证码字符数
+    pub expire: u64,                 //验证码有效期(秒)
+    pub width: u32,                  //图片宽度
+    pub height: u32,                 //图片高度
+}
+
+impl Default for CaptchaConfig {
+    fn default() -> Self {
+        CaptchaConfig {
+            enabled: true,
+            required_after_failures: 3,
+            length: 4,
+            expire: 120,
+            width: 120,
+            height: 40,
+        }
+    }
+}
+
+impl CaptchaConfig {
+    /// 根据账号当前的失败次数判断本次登录是否需要验证码
+    pub fn is_required(&self, failures: i64) -> bool {
+        self.enabled && failures >= self.required_after_failures
+    }
+}
+
+fn captcha_key(captcha_id: &str) -> String {
+    format!("salvo:admin:captcha:{}", captcha_id)
+}
+
+pub fn new_captcha_id() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+/// 生成随机验证码
+pub fn generate_code(length: usize) -> String {
+    (0..length).map(|_| CHARSET[rand::random::<usize>() % CHARSET.len()] as char).collect()
+}
+
+/*
+ *把验证码渲染成PNG图片(点阵字体+随机颜色、位置抖动、干扰线和噪点)
+ */
+pub fn render_png(code: &str, width: u32, height: u32) -> Result<Vec<u8>, AppError> {
+    let (w, h) = (width as i32, height as i32);
+    let mut pixels = vec![0u8; (width * height * 3) as usize];
+    let put = |pixels: &mut Vec<u8>, x: i32, y: i32, color: [u8; 3]| {
+        if x >= 0 && y >= 0 && x < w && y < h {
+            let i = ((y * w + x) * 3) as usize;
+            pixels[i..i + 3].copy_from_slice(&color);
+        }
+    };
+    let random_color = |min: u8, max: u8| -> [u8; 3] { [0, 0, 0].map(|_: u8| min + rand::random::<u8>() % (max - min)) };
+
+    //浅色背景
+    let background = random_color(220, 255);
+    for y in 0..h {
+        for x in 0..w {
+            put(&mut pixels, x, y, background);
+        }
+    }
+
+    //字符,每个点阵按比例放大,随机颜色和上下抖动
+    let count = code.len().max(1) as i32;
+    let cell = w / count;
+    let scale = (cell / 7).min(h / 9).max(1);
+    for (n, c) in code.bytes().enumerate() {
+        let rows = match GLYPHS.iter().find(|(g, _)| *g == c) {
+            Some((_, rows)) => rows,
+            None => continue,
+        };
+        let color = random_color(20, 120);
+        let left = n as i32 * cell + (cell - 5 * scale) / 2 + (rand::random::<u8>() % 5) as i32 - 2;
+        let top = (h - 7 * scale) / 2 + (rand::random::<u8>() % 7) as i32 - 3;
+        let slant = rand::random::<u8>() % 3;
+        for (row, bits) in rows.iter().enumerate() {
+            //倾斜: 越往上偏移越多
+            let shift = (6 - row as i32) * slant as i32 / 3;
+            for col in 0..5 {
+                if bits & (0x10 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        put(&mut pixels, left + shift + col * scale + dx, top + row as i32 * scale + dy, color);
+                    }
+                }
+            }
+        }
+    }
+
+    //干扰线
+    for _ in 0..4 {
+        let color = random_color(80, 200);
+        let (x0, y0) = (0, (rand::random::<u32>() % height) as i32);
+        let (x1, y1) = (w - 1, (rand::random::<u32>() % height) as i32);
+        let steps = (x1 - x0).max(1);
+        for step in 0..=steps {
+            put(&mut pixels, x0 + step, y0 + (y1 - y0) * step / steps, color);
+        }
+    }
+
+    //噪点
+    for _ in 0..(width * height / 20) {
+        let color = random_color(60, 220);
+        put(&mut pixels, (rand::random::<u32>() % width) as i32, (rand::random::<u32>() % height) as i32, color);
+    }
+
+    let mut buf: Vec<u8> = Vec::new();
+    let mut encoder = png::Encoder::new(&mut buf, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let encode = || -> Result<(), png::EncodingError> {
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&pixels)?;
+        writer.finish()
+    };
+    encode().map_err(|e| {
+        log::error!("render captcha png error: {:?}", e);
+        AppError::BusinessError("生成验证码图片异常")
+    })?;
+    Ok(buf)
+}
+
+/*
+ *保存验证码答案
+ */
+pub async fn save_captcha(conn: &mut StoreConnection, captcha_id: &str, code: &str, expire: u64) -> Result<(), RedisError> {
+    cmd("SET").arg(captcha_key(captcha_id)).arg(code).arg("EX").arg(expire).query_async::<()>(conn).await
+}
+
+/*
+ *校验验证码(不区分大小写),无论成功与否验证码都会作废
+ */
+pub async fn verify_captcha(conn: &mut StoreConnection, captcha_id: &str, code: &str) -> Result<bool, RedisError> {
+    let answer: Option<String> = cmd("GETDEL").arg(captcha_key(captcha_id)).query_async(conn).await?;
+    Ok(answer.is_some_and(|x| !code.is_empty() && x.eq_ignore_ascii_case(code.trim())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::captcha_util::{generate_code, render_png, CHARSET, GLYPHS};
+
+    #[test]
+    fn test_render_png() {
+        assert!(CHARSET.iter().all(|c| GLYPHS.iter().any(|(g, _)| g == c)));
+
+        let code = generate_code(4);
+        assert_eq!(code.len(), 4);
+        let png = render_png(&code, 120, 40).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
@@ -0,0 +1,147 @@
+use rbatis::RBatis;
+use rbs::value;
+use salvo::Depot;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::common::app_state::app_state;
+use crate::common::error::{AppError, AppResult};
+use crate::model::system::sys_dept_model::select_children_dept_by_id;
+use crate::model::system::sys_role_dept_model::RoleDept;
+use crate::model::system::sys_role_model::select_role_by_user_id;
+use crate::model::system::sys_user_model::User;
+use crate::model::system::sys_user_role_model::{is_admin, UserRole};
+
+//数据范围（1：全部数据权限 2：自定数据权限 3：本部门数据权限 4：本部门及以下数据权限）
+pub const DATA_SCOPE_ALL: i8 = 1;
+pub const DATA_SCOPE_CUSTOM: i8 = 2;
+pub const DATA_SCOPE_DEPT: i8 = 3;
+pub const DATA_SCOPE_DEPT_AND_CHILD: i8 = 4;
+
+/*
+ *当前用户的数据权限,多个角色的数据范围取并集
+ */
+#[derive(Debug, Clone, Serialize)]
+pub struct DataScope {
+    pub all: bool,          //是否拥有全部数据权限
+    pub user_id: i64,       //当前用户id(用户自己的数据总是可以访问)
+    pub dept_ids: Vec<i64>, //可以访问的部门
+}
+
+impl DataScope {
+    pub fn allows_dept(&self, dept_id: i64) -> bool {
+        self.all || self.dept_ids.contains(&dept_id)
+    }
+
+    pub fn allows_user(&self, user: &User) -> bool {
+        user.id == Some(self.user_id) || self.allows_dept(user.dept_id)
+    }
+
+    pub fn check_dept(&self, dept_id: i64) -> AppResult<()> {
+        if self.allows_dept(dept_id) {
+            return Ok(());
+        }
+        Err(AppError::BusinessError("没有权限访问该部门的数据"))
+    }
+
+    pub fn check_user(&self, user: &User) -> AppResult<()> {
+        if self.allows_user(user) {
+            return Ok(());
+        }
+        Err(AppError::BusinessError("没有权限访问该用户的数据"))
+    }
+}
+
+/*
+ *根据用户的角色、角色关联的部门和部门的祖级列表计算数据权限
+ *超级管理员和拥有全部数据权限角色的用户不受限制,禁用的角色不生效
+ */
+pub async fn query_data_scope(rb: &RBatis, user_id: i64) -> rbatis::Result<DataScope> {
+    let mut scope = DataScope { all: false, user_id, dept_ids: Vec::new() };
+    if is_admin(rb, &user_id).await? == 1 {
+        scope.all = true;
+        return Ok(scope);
+    }
+
+    let roles = select_role_by_user_id(rb, &user_id).await?;
+    if roles.iter().any(|x| x.data_scope == DATA_SCOPE_ALL) {
+        scope.all = true;
+        return Ok(scope);
+    }
+
+    let mut dept_ids: HashSet<i64> = HashSet::new();
+    let custom_role_ids: Vec<i64> = roles.iter().filter(|x| x.data_scope == DATA_SCOPE_CUSTOM).filter_map(|x| x.id).collect();
+    if !custom_role_ids.is_empty() {
+        dept_ids.extend(RoleDept::select_by_map(rb, value! {"role_id": &custom_role_ids}).await?.iter().map(|x| x.dept_id));
+    }
+
+    let dept_scope = roles.iter().any(|x| x.data_scope == DATA_SCOPE_DEPT);
+    let dept_and_child_scope = roles.iter().any(|x| x.data_scope == DATA_SCOPE_DEPT_AND_CHILD);
+    if dept_scope || dept_and_child_scope {
+        if let Some(user) = User::select_by_id(rb, user_id).await? {
+            dept_ids.insert(user.dept_id);
+            if dept_and_child_scope {
+                dept_ids.extend(select_children_dept_by_id(rb, &user.dept_id).await?.iter().filter_map(|x| x.id));
+            }
+        }
+    }
+
+    scope.dept_ids = dept_ids.into_iter().collect();
+    scope.dept_ids.sort();
+    Ok(scope)
+}
+
+/*
+ *查询当前登录用户的数据权限
+ */
+pub async fn current_data_scope(depot: &Depot) -> AppResult<DataScope> {
+    let user_id = depot.get::<i64>("userId").copied().map_err(|_| AppError::BusinessError("用户ID不能为空"))?;
+    Ok(query_data_scope(&app_state(depot)?.rb, user_id).await?)
+}
+
+/*
+ *校验当前用户是否可以操作这些用户
+ */
+pub async fn check_user_scope(rb: &RBatis, scope: &DataScope, user_ids: &[i64]) -> AppResult<()> {
+    if scope.all || user_ids.is_empty() {
+        return Ok(());
+    }
+    for user in User::select_by_map(rb, value! {"id": user_ids}).await? {
+        scope.check_user(&user)?;
+    }
+    Ok(())
+}
+
+/*
+ *校验当前用户是否可以把角色的数据范围改为data_scope
+ *没有全部数据权限时: 不能授予全部数据权限,不能修改自己拥有的角色,
+ *自定义数据权限的部门和本部门(及以下)数据权限覆盖的、角色下用户所在的部门都必须在自己的数据权限内
+ */
+pub async fn check_role_scope(rb: &RBatis, scope: &DataScope, role_id: i64, data_scope: i8, dept_ids: &[i64]) -> AppResult<()> {
+    if scope.all {
+        return Ok(());
+    }
+    if data_scope == DATA_SCOPE_ALL {
+        return Err(AppError::BusinessError("没有权限授予全部数据权限"));
+    }
+    if !UserRole::select_by_map(rb, value! {"user_id": scope.user_id, "role_id": role_id}).await?.is_empty() {
+        return Err(AppError::BusinessError("不能修改自己拥有的角色的数据权限"));
+    }
+
+    let mut depts: HashSet<i64> = dept_ids.iter().copied().collect();
+    if data_scope == DATA_SCOPE_DEPT || data_scope == DATA_SCOPE_DEPT_AND_CHILD {
+        let user_ids: Vec<i64> = UserRole::select_by_map(rb, value! {"role_id": role_id}).await?.iter().map(|x| x.user_id).collect();
+        if !user_ids.is_empty() {
+            for user in User::select_by_map(rb, value! {"id": &user_ids}).await? {
+                depts.insert(user.dept_id);
+                if data_scope == DATA_SCOPE_DEPT_AND_CHILD {
+                    depts.extend(select_children_dept_by_id(rb, &user.dept_id).await?.iter().filter_map(|x| x.id));
+                }
+            }
+        }
+    }
+    for dept_id in depts {
+        scope.check_dept(dept_id)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::utils::session_store_util::SessionStore;
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::timeout;
+
+pub const STATUS_UP: &str = "UP";
+pub const STATUS_DOWN: &str = "DOWN";
+
+/*
+ *健康检查配置
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    pub timeout_ms: u64, //就绪检查时每个依赖的超时时间(毫秒)
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        HealthConfig { timeout_ms: 2000 }
+    }
+}
+
+impl HealthConfig {
+    /// 检查数据库: 从连接池获取连接并执行select 1
+    pub async fn check_db(&self, rb: &RBatis) -> Result<(), String> {
+        self.with_timeout(async { rb.query("select 1", vec![]).await.map(|_| ()).map_err(|e| e.to_string()) }).await
+    }
+
+    /// 检查redis: 从连接池获取连接并执行PING
+    pub async fn check_redis(&self, store: &SessionStore) -> Result<(), String> {
+        self.with_timeout(async {
+            let mut conn = store.get().await.map_err(|e| e.to_string())?;
+            deadpool_redis::redis::cmd("PING").query_async::<String>(&mut conn).await.map(|_| ()).map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    async fn with_timeout<F: Future<Output = Result<(), String>>>(&self, check: F) -> Result<(), String> {
+        match timeout(Duration::from_millis(self.timeout_ms.max(1)), check).await {
+            Ok(result) => result,
+            Err(_) => Err(format!("timeout after {}ms", self.timeout_ms)),
+        }
+    }
+}
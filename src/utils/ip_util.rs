@@ -0,0 +1,281 @@
+use crate::common::app_state::app_state;
+use salvo::{Depot, Request};
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::sync::Arc;
+
+//ip2region xdb文件: 256字节的头部,256x256的向量索引(每项8字节),二分查找的段索引(每项14字节)
+const XDB_HEADER_SIZE: usize = 256;
+const XDB_VECTOR_INDEX_COLS: usize = 256;
+const XDB_VECTOR_INDEX_SIZE: usize = 8;
+const XDB_SEGMENT_INDEX_SIZE: usize = 14;
+
+const LOCATION_INTERNAL: &str = "内网IP";
+const LOCATION_UNKNOWN: &str = "未知";
+
+/*
+ *客户端IP配置
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IpConfig {
+    pub trusted_proxies: Vec<String>, //受信任的代理(IP或者CIDR),只有来自这些地址的请求才读取X-Forwarded-For/X-Real-IP
+    pub ip2region_path: String,       //ip2region xdb离线库文件路径,为空时不解析归属地
+}
+
+impl Default for IpConfig {
+    fn default() -> Self {
+        IpConfig {
+            trusted_proxies: vec!["127.0.0.1".to_string(), "::1".to_string()],
+            ip2region_path: "".to_string(),
+        }
+    }
+}
+
+/*
+ *IP网段,单个IP视为/32或者/128
+ */
+#[derive(Debug, Clone, Copy)]
+pub struct Cidr {
+    addr: IpAddr, //网络地址
+    prefix: u32,  //前缀长度
+}
+
+impl Cidr {
+    pub fn parse(text: &str) -> Option<Cidr> {
+        let (addr, prefix) = match text.trim().split_once('/') {
+            Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse::<u32>().ok()?)),
+            None => (text.trim().parse::<IpAddr>().ok()?, None),
+        };
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        if prefix > max {
+            return None;
+        }
+        Some(Cidr { addr: addr.to_canonical(), prefix })
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => prefix_eq(u32::from(net) as u128, u32::from(ip) as u128, 32, self.prefix),
+            (IpAddr::V6(net), IpAddr::V6(ip)) => prefix_eq(u128::from(net), u128::from(ip), 128, self.prefix),
+            _ => false,
+        }
+    }
+}
+
+fn prefix_eq(net: u128, ip: u128, bits: u32, prefix: u32) -> bool {
+    let shift = bits - prefix;
+    shift >= bits || (net >> shift) == (ip >> shift)
+}
+
+/*
+ *请求的客户端IP和归属地
+ */
+#[derive(Debug, Clone, Default)]
+pub struct ClientIp {
+    pub ipaddr: String,   //客户端IP
+    pub location: String, //归属地
+}
+
+/*
+ *客户端IP解析: 受信任代理的转发头和ip2region离线库(只加载一次,全部放在内存中)
+ */
+#[derive(Debug, Clone, Default)]
+pub struct IpResolver {
+    trusted_proxies: Vec<Cidr>, //受信任的代理
+    xdb: Option<Arc<Vec<u8>>>,  //ip2region xdb文件内容
+}
+
+impl IpResolver {
+    pub fn from_config(config: &IpConfig) -> Self {
+        let trusted_proxies = config
+            .trusted_proxies
+            .iter()
+            .filter_map(|x| {
+                let cidr = Cidr::parse(x);
+                if cidr.is_none() {
+                    log::warn!("invalid trusted proxy: {}", x);
+                }
+                cidr
+            })
+            .collect();
+
+        let xdb = match config.ip2region_path.as_str() {
+            "" => None,
+            path => match std::fs::read(path) {
+                Ok(data) if data.len() >= XDB_HEADER_SIZE + XDB_VECTOR_INDEX_COLS * XDB_VECTOR_INDEX_COLS * XDB_VECTOR_INDEX_SIZE => Some(Arc::new(data)),
+                Ok(_) => {
+                    log::error!("invalid ip2region xdb file: {}", path);
+                    None
+                }
+                Err(e) => {
+                    log::error!("load ip2region xdb file {} error: {}", path, e);
+                    None
+                }
+            },
+        };
+
+        IpResolver { trusted_proxies, xdb }
+    }
+
+    fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|x| x.contains(ip))
+    }
+
+    /*
+     *计算客户端IP: 直连地址不是受信任的代理时直接使用直连地址
+     *否则从右往左跳过受信任的代理,取X-Forwarded-For中第一个不受信任的地址,没有X-Forwarded-For时使用X-Real-IP
+     */
+    pub fn client_ip(&self, remote: Option<IpAddr>, forwarded_for: Option<&str>, real_ip: Option<&str>) -> Option<IpAddr> {
+        let remote = remote?.to_canonical();
+        if !self.is_trusted(&remote) {
+            return Some(remote);
+        }
+
+        if let Some(forwarded_for) = forwarded_for.filter(|x| !x.trim().is_empty()) {
+            let mut client = remote;
+            for item in forwarded_for.split(',').rev() {
+                match item.trim().parse::<IpAddr>() {
+                    Ok(ip) => {
+                        client = ip.to_canonical();
+                        if !self.is_trusted(&client) {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+            return Some(client);
+        }
+
+        match real_ip.and_then(|x| x.trim().parse::<IpAddr>().ok()) {
+            Some(ip) => Some(ip.to_canonical()),
+            None => Some(remote),
+        }
+    }
+
+    /// 查询IP归属地,内网地址返回"内网IP",没有离线库或者查不到时返回"未知"
+    pub fn location(&self, ip: &IpAddr) -> String {
+        if is_internal(ip) {
+            return LOCATION_INTERNAL.to_string();
+        }
+        let region = match (ip, &self.xdb) {
+            (IpAddr::V4(v4), Some(xdb)) => xdb_search(xdb, u32::from(*v4)),
+            _ => None,
+        };
+        region.map(|x| format_region(&x)).filter(|x| !x.is_empty()).unwrap_or_else(|| LOCATION_UNKNOWN.to_string())
+    }
+
+    pub fn resolve(&self, req: &Request) -> ClientIp {
+        let remote = req.remote_addr().clone().into_std().map(|x| x.ip());
+        let forwarded_for = req.header::<String>("x-forwarded-for");
+        let real_ip = req.header::<String>("x-real-ip");
+        match self.client_ip(remote, forwarded_for.as_deref(), real_ip.as_deref()) {
+            Some(ip) => ClientIp {
+                ipaddr: ip.to_string(),
+                location: self.location(&ip),
+            },
+            None => ClientIp::default(),
+        }
+    }
+}
+
+/*
+ *查询请求的客户端IP和归属地
+ */
+pub fn client_ip(depot: &Depot, req: &Request) -> ClientIp {
+    match app_state(depot) {
+        Ok(state) => state.ip_resolver.resolve(req),
+        Err(_) => IpResolver::default().resolve(req),
+    }
+}
+
+fn is_internal(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_private() || v4.is_loopback() || v4.is_link_local() || v4.is_unspecified(),
+        IpAddr::V6(v6) => v6.is_loopback() || v6.is_unspecified() || (v6.segments()[0] & 0xfe00) == 0xfc00 || (v6.segments()[0] & 0xffc0) == 0xfe80,
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+/// 在ip2region xdb(2.0格式)中查询IPv4地址,返回"国家|区域|省份|城市|ISP"
+fn xdb_search(data: &[u8], ip: u32) -> Option<String> {
+    let (il0, il1) = ((ip >> 24) as usize, ((ip >> 16) & 0xFF) as usize);
+    let index = XDB_HEADER_SIZE + (il0 * XDB_VECTOR_INDEX_COLS + il1) * XDB_VECTOR_INDEX_SIZE;
+    let start = read_u32(data, index)? as usize;
+    let end = read_u32(data, index + 4)? as usize;
+    if end < start {
+        return None;
+    }
+
+    let (mut low, mut high) = (0i64, ((end - start) / XDB_SEGMENT_INDEX_SIZE) as i64);
+    while low <= high {
+        let middle = (low + high) / 2;
+        let offset = start + middle as usize * XDB_SEGMENT_INDEX_SIZE;
+        let (start_ip, end_ip) = (read_u32(data, offset)?, read_u32(data, offset + 4)?);
+        if ip < start_ip {
+            high = middle - 1;
+        } else if ip > end_ip {
+            low = middle + 1;
+        } else {
+            let length = read_u16(data, offset + 8)? as usize;
+            let pointer = read_u32(data, offset + 10)? as usize;
+            return data.get(pointer..pointer + length).map(|x| String::from_utf8_lossy(x).to_string());
+        }
+    }
+    None
+}
+
+/// "中国|0|广东省|深圳市|电信" => "中国 广东省 深圳市",去掉为0的部分和重复的部分
+fn format_region(region: &str) -> String {
+    let parts: Vec<&str> = region.split('|').collect();
+    let mut result: Vec<&str> = Vec::new();
+    for index in [0, 2, 3] {
+        match parts.get(index) {
+            Some(part) if !part.is_empty() && *part != "0" && !result.contains(part) => result.push(part),
+            _ => {}
+        }
+    }
+    result.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::ip_util::{format_region, Cidr, IpConfig, IpResolver};
+    use std::net::IpAddr;
+
+    #[test]
+    fn test_client_ip() {
+        let cidr = Cidr::parse("10.0.0.0/8").unwrap();
+        assert!(cidr.contains(&"10.1.2.3".parse().unwrap()));
+        assert!(!cidr.contains(&"11.1.2.3".parse().unwrap()));
+        assert!(cidr.contains(&"::ffff:10.1.2.3".parse().unwrap()));
+        assert!(Cidr::parse("10.0.0.0/33").is_none());
+
+        let config = IpConfig {
+            trusted_proxies: vec!["127.0.0.1".to_string(), "10.0.0.0/8".to_string()],
+            ip2region_path: "".to_string(),
+        };
+        let resolver = IpResolver::from_config(&config);
+        let ip = |x: &str| -> Option<IpAddr> { x.parse().ok() };
+        //不受信任的直连地址不读取转发头
+        assert_eq!(resolver.client_ip(ip("1.1.1.1"), Some("2.2.2.2"), None), ip("1.1.1.1"));
+        //跳过受信任的代理,伪造的最左边地址不生效
+        assert_eq!(resolver.client_ip(ip("127.0.0.1"), Some("6.6.6.6, 2.2.2.2, 10.0.0.2"), None), ip("2.2.2.2"));
+        assert_eq!(resolver.client_ip(ip("127.0.0.1"), None, Some("3.3.3.3")), ip("3.3.3.3"));
+        assert_eq!(resolver.client_ip(ip("127.0.0.1"), None, None), ip("127.0.0.1"));
+
+        assert_eq!(resolver.location(&"192.168.1.1".parse().unwrap()), "内网IP");
+        assert_eq!(resolver.location(&"8.8.8.8".parse().unwrap()), "未知");
+        assert_eq!(format_region("中国|0|广东省|深圳市|电信"), "中国 广东省 深圳市");
+        assert_eq!(format_region("美国|0|0|0|0"), "美国");
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, EllipticCurveKeyParameters, EllipticCurveKeyType, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters,
+    OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use simple_asn1::{from_der, ASN1Block};
+
+use crate::common::error::AppError;
+use crate::common::error::AppError::JwtTokenError;
+use crate::utils::jwt_util::JwtConfig;
+
+/*
+ *jwt签名和验签密钥
+ *HS256使用secret,RS256/ES256/EdDSA使用PEM文件,旧密钥通过verify_keys配置以支持密钥轮换
+ *每个验签密钥单独记录算法,轮换时可以更换签名算法
+ */
+#[derive(Clone)]
+pub struct JwtKeys {
+    algorithm: Algorithm,
+    kid: String,
+    encoding_key: EncodingKey,
+    decoding_keys: HashMap<String, (Algorithm, DecodingKey)>,
+    jwks: JwkSet,
+}
+
+impl JwtKeys {
+    pub fn from_config(config: &JwtConfig) -> Result<JwtKeys, AppError> {
+        let algorithm = parse_algorithm(&config.algorithm)?;
+
+        let encoding_key = match algorithm {
+            Algorithm::HS256 => {
+                if config.secret.is_empty() {
+                    return Err(JwtTokenError("HS256算法必须配置jwt.secret".to_string()));
+                }
+                EncodingKey::from_secret(config.secret.as_bytes())
+            }
+            _ => {
+                let private_pem = read_pem_file(&config.private_key)?;
+                match algorithm {
+                    Algorithm::RS256 => EncodingKey::from_rsa_pem(&private_pem),
+                    Algorithm::ES256 => EncodingKey::from_ec_pem(&private_pem),
+                    _ => EncodingKey::from_ed_pem(&private_pem),
+                }
+                .map_err(|e| JwtTokenError(format!("加载jwt私钥失败 {}: {}", config.private_key, e)))?
+            }
+        };
+
+        let mut verify_keys = vec![(config.kid.clone(), algorithm, config.secret.clone(), config.public_key.clone())];
+        for key in &config.verify_keys {
+            //没有配置算法时和当前签名算法一致
+            let key_algorithm = match &key.algorithm {
+                Some(x) => parse_algorithm(x)?,
+                None => algorithm,
+            };
+            verify_keys.push((key.kid.clone(), key_algorithm, key.secret.clone(), key.public_key.clone()));
+        }
+
+        let mut decoding_keys: HashMap<String, (Algorithm, DecodingKey)> = HashMap::new();
+        let mut jwks = JwkSet { keys: Vec::new() };
+        for (kid, key_algorithm, secret, path) in verify_keys {
+            let decoding_key = match key_algorithm {
+                Algorithm::HS256 => {
+                    if secret.is_empty() {
+                        return Err(JwtTokenError(format!("HS256验签密钥必须配置secret: {}", kid)));
+                    }
+                    //对称密钥不能公开,不写入jwks
+                    DecodingKey::from_secret(secret.as_bytes())
+                }
+                _ => {
+                    let public_pem = read_pem_file(&path)?;
+                    let decoding_key = match key_algorithm {
+                        Algorithm::RS256 => DecodingKey::from_rsa_pem(&public_pem),
+                        Algorithm::ES256 => DecodingKey::from_ec_pem(&public_pem),
+                        _ => DecodingKey::from_ed_pem(&public_pem),
+                    }
+                    .map_err(|e| JwtTokenError(format!("加载jwt公钥失败 {}: {}", path, e)))?;
+                    jwks.keys.push(public_key_to_jwk(&kid, key_algorithm, &public_pem)?);
+                    decoding_key
+                }
+            };
+            decoding_keys.insert(kid, (key_algorithm, decoding_key));
+        }
+
+        Ok(JwtKeys {
+            algorithm,
+            kid: config.kid.clone(),
+            encoding_key,
+            decoding_keys,
+            jwks,
+        })
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn kid(&self) -> &str {
+        &self.kid
+    }
+
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.encoding_key
+    }
+
+    /// 验签密钥及其算法
+    pub fn decoding_key(&self, kid: &str) -> Option<(Algorithm, &DecodingKey)> {
+        self.decoding_keys.get(kid).map(|(algorithm, key)| (*algorithm, key))
+    }
+
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+}
+
+fn parse_algorithm(name: &str) -> Result<Algorithm, AppError> {
+    match Algorithm::from_str(name) {
+        Ok(x @ (Algorithm::HS256 | Algorithm::RS256 | Algorithm::ES256 | Algorithm::EdDSA)) => Ok(x),
+        _ => Err(JwtTokenError(format!("不支持的jwt签名算法: {}", name))),
+    }
+}
+
+fn read_pem_file(path: &str) -> Result<Vec<u8>, AppError> {
+    if path.is_empty() {
+        return Err(JwtTokenError("非对称算法必须配置jwt密钥文件路径".to_string()));
+    }
+    fs::read(path).map_err(|e| JwtTokenError(format!("读取jwt密钥文件失败 {}: {}", path, e)))
+}
+
+/// 从SubjectPublicKeyInfo格式的公钥中提取JWK参数
+fn public_key_to_jwk(kid: &str, algorithm: Algorithm, public_pem: &[u8]) -> Result<Jwk, AppError> {
+    let invalid = || JwtTokenError(format!("无法解析jwt公钥: {}", kid));
+    let pem = pem::parse(public_pem).map_err(|_| invalid())?;
+
+    //PKCS#1格式的RSA公钥本身就是RSAPublicKey,否则从SubjectPublicKeyInfo的BIT STRING中取出
+    let key_bytes = if pem.tag() == "RSA PUBLIC KEY" {
+        pem.contents().to_vec()
+    } else {
+        match from_der(pem.contents()).map_err(|_| invalid())?.first() {
+            Some(ASN1Block::Sequence(_, items)) => match items.get(1) {
+                Some(ASN1Block::BitString(_, _, bytes)) => bytes.clone(),
+                _ => return Err(invalid()),
+            },
+            _ => return Err(invalid()),
+        }
+    };
+
+    let (key_algorithm, parameters) = match algorithm {
+        Algorithm::RS256 => {
+            let (n, e) = match from_der(&key_bytes).map_err(|_| invalid())?.first() {
+                Some(ASN1Block::Sequence(_, items)) => match (items.first(), items.get(1)) {
+                    (Some(ASN1Block::Integer(_, n)), Some(ASN1Block::Integer(_, e))) => (n.to_bytes_be().1, e.to_bytes_be().1),
+                    _ => return Err(invalid()),
+                },
+                _ => return Err(invalid()),
+            };
+            (
+                KeyAlgorithm::RS256,
+                AlgorithmParameters::RSA(RSAKeyParameters {
+                    key_type: RSAKeyType::RSA,
+                    n: URL_SAFE_NO_PAD.encode(n),
+                    e: URL_SAFE_NO_PAD.encode(e),
+                }),
+            )
+        }
+        Algorithm::ES256 => {
+            //未压缩的椭圆曲线点: 0x04 || X || Y
+            if key_bytes.len() != 65 || key_bytes[0] != 0x04 {
+                return Err(invalid());
+            }
+            (
+                KeyAlgorithm::ES256,
+                AlgorithmParameters::EllipticCurve(EllipticCurveKeyParameters {
+                    key_type: EllipticCurveKeyType::EC,
+                    curve: EllipticCurve::P256,
+                    x: URL_SAFE_NO_PAD.encode(&key_bytes[1..33]),
+                    y: URL_SAFE_NO_PAD.encode(&key_bytes[33..65]),
+                }),
+            )
+        }
+        _ => {
+            if key_bytes.len() != 32 {
+                return Err(invalid());
+            }
+            (
+                KeyAlgorithm::EdDSA,
+                AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                    key_type: OctetKeyPairType::OctetKeyPair,
+                    curve: EllipticCurve::Ed25519,
+                    x: URL_SAFE_NO_PAD.encode(&key_bytes),
+                }),
+            )
+        }
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(kid.to_string()),
+            ..Default::default()
+        },
+        algorithm: parameters,
+    })
+}
@@ -1,12 +1,79 @@
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use jsonwebtoken::{
-    decode, encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey, Header, Validation,
-};
+use jsonwebtoken::{decode, decode_header, encode, errors::ErrorKind, Header, Validation};
 use serde::{Deserialize, Serialize};
 
 use crate::common::error::AppError;
 use crate::common::error::AppError::JwtTokenError;
+use crate::utils::jwt_key_util::JwtKeys;
+
+/*
+ *jwt配置
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtConfig {
+    #[serde(default = "default_algorithm")]
+    pub algorithm: String, //签名算法(HS256, RS256, ES256, EdDSA)
+    #[serde(default)]
+    pub secret: String, //签名密钥(仅HS256使用)
+    #[serde(default = "default_kid")]
+    pub kid: String, //当前签名密钥的id,写入token头部的kid
+    #[serde(default)]
+    pub private_key: String, //当前签名私钥PEM文件路径(非对称算法使用)
+    #[serde(default)]
+    pub public_key: String, //当前签名公钥PEM文件路径(非对称算法使用)
+    #[serde(default)]
+    pub verify_keys: Vec<JwtVerifyKeyConfig>, //轮换期间仍然有效的旧公钥
+    #[serde(default = "default_access_token_expire")]
+    pub access_token_expire: u64, //访问token有效期(秒)
+    #[serde(default = "default_refresh_token_expire")]
+    pub refresh_token_expire: u64, //刷新token有效期(秒),每次刷新后顺延
+}
+
+/*
+ *jwt验签公钥配置
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtVerifyKeyConfig {
+    pub kid: String, //密钥id
+    #[serde(default)]
+    pub algorithm: Option<String>, //签名算法,不配置时和jwt.algorithm一致
+    #[serde(default)]
+    pub secret: String, //签名密钥(仅HS256使用)
+    #[serde(default)]
+    pub public_key: String, //公钥PEM文件路径(非对称算法使用)
+}
+
+impl Default for JwtConfig {
+    fn default() -> Self {
+        JwtConfig {
+            algorithm: default_algorithm(),
+            secret: "".to_string(),
+            kid: default_kid(),
+            private_key: "".to_string(),
+            public_key: "".to_string(),
+            verify_keys: Vec::new(),
+            access_token_expire: default_access_token_expire(),
+            refresh_token_expire: default_refresh_token_expire(),
+        }
+    }
+}
+
+fn default_algorithm() -> String {
+    "HS256".to_string()
+}
+
+fn default_kid() -> String {
+    "default".to_string()
+}
+
+fn default_access_token_expire() -> u64 {
+    30 * 60
+}
+
+fn default_refresh_token_expire() -> u64 {
+    7 * 24 * 60 * 60
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct JwtToken {
@@ -27,74 +94,168 @@ pub struct JwtToken {
 }
 
 impl JwtToken {
-    pub fn new(id: i64, username: &str) -> JwtToken {
+    pub fn new(id: i64, username: &str, session_id: &str, expire: u64) -> JwtToken {
         let now = SystemTime::now();
         //过期时间
-        let m30 = Duration::from_secs(1800000);
+        let expire = Duration::from_secs(expire);
         let now = now.duration_since(UNIX_EPOCH).expect("获取系统时间失败");
 
         JwtToken {
             id,
             username: String::from(username),
             aud: String::from("rust_admin"), // (audience)：受众
-            exp: (now + m30).as_secs() as usize,
+            exp: (now + expire).as_secs() as usize,
             iat: now.as_secs() as usize,     // (Issued At)：签发时间
             iss: String::from("koobe"),      // (issuer)：签发人
             nbf: now.as_secs() as usize,     // (Not Before)：生效时间
             sub: String::from("rust_admin"), // (subject)：主题
-            jti: String::from("ignore"),     // (JWT ID)：编号
+            jti: String::from(session_id),   // (JWT ID)：编号,即会话id
         }
     }
 
+    /// 会话id
+    pub fn session_id(&self) -> &str {
+        &self.jti
+    }
+
     /// create token
-    /// secret: your secret string
-    pub fn create_token(&self, secret: &str) -> Result<String, AppError> {
-        return match encode(
-            &Header::default(),
-            self,
-            &EncodingKey::from_secret(secret.as_ref()),
-        ) {
-            Ok(t) => Ok(t),
-            Err(_) => Err(JwtTokenError("create token error".to_string())),
-        };
+    /// keys: 当前签名密钥,kid写入token头部
+    pub fn create_token(&self, keys: &JwtKeys) -> Result<String, AppError> {
+        let mut header = Header::new(keys.algorithm());
+        header.kid = Some(keys.kid().to_string());
+        encode(&header, self, keys.encoding_key()).map_err(|_| JwtTokenError("create token error".to_string()))
     }
     /// verify token invalid
-    /// secret: your secret string
-    pub fn verify(secret: &str, token: &str) -> Result<JwtToken, AppError> {
-        let mut validation = Validation::new(Algorithm::HS256);
+    /// keys: 根据token头部的kid选择验签公钥,没有kid时使用当前签名密钥
+    pub fn verify(keys: &JwtKeys, token: &str) -> Result<JwtToken, AppError> {
+        let header = decode_header(token).map_err(|_| JwtTokenError("InvalidToken".to_string()))?;
+        let (algorithm, decoding_key) = match keys.decoding_key(header.kid.as_deref().unwrap_or(keys.kid())) {
+            None => return Err(JwtTokenError("InvalidKeyId".to_string())),
+            Some(key) => key,
+        };
+        //只接受该密钥对应的算法,防止算法混淆
+        if header.alg != algorithm {
+            return Err(JwtTokenError("InvalidAlgorithm".to_string()));
+        }
+
+        let mut validation = Validation::new(algorithm);
         validation.sub = Some("rust_admin".to_string());
         validation.set_audience(&["rust_admin"]);
         validation.set_required_spec_claims(&["exp", "sub", "aud"]);
-        return match decode::<JwtToken>(
-            &token,
-            &DecodingKey::from_secret(secret.as_ref()),
-            &validation,
-        ) {
+        match decode::<JwtToken>(token, decoding_key, &validation) {
             Ok(c) => Ok(c.claims),
 
             Err(err) => match *err.kind() {
-                ErrorKind::InvalidToken => return Err(JwtTokenError("InvalidToken".to_string())), // Example on how to handle a specific error
-                ErrorKind::InvalidIssuer => return Err(JwtTokenError("InvalidIssuer".to_string())), // Example on how to handle a specific error
-                ErrorKind::ExpiredSignature => {
-                    return Err(JwtTokenError("token 已经超时了".to_string()))
-                } // Example on how to handle a specific error
+                ErrorKind::InvalidToken => Err(JwtTokenError("InvalidToken".to_string())), // Example on how to handle a specific error
+                ErrorKind::InvalidIssuer => Err(JwtTokenError("InvalidIssuer".to_string())), // Example on how to handle a specific error
+                ErrorKind::ExpiredSignature => Err(JwtTokenError("token 已经超时了".to_string())), // Example on how to handle a specific error
                 // _ => return Err(Error::from("InvalidToken other errors")),
                 _ => Err(JwtTokenError("create token error".to_string())),
             },
-        };
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::utils::jwt_util::JwtToken;
+    use crate::utils::jwt_key_util::JwtKeys;
+    use crate::utils::jwt_util::{JwtConfig, JwtToken, JwtVerifyKeyConfig};
+    use jsonwebtoken::jwk::AlgorithmParameters;
+    use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+
+    fn key_config(algorithm: &str, kid: &str) -> JwtConfig {
+        let name = algorithm.to_lowercase();
+        JwtConfig {
+            algorithm: algorithm.to_string(),
+            secret: "123".to_string(),
+            kid: kid.to_string(),
+            private_key: format!("src/tests/keys/{}_private.pem", name),
+            public_key: format!("src/tests/keys/{}_public.pem", name),
+            ..Default::default()
+        }
+    }
 
     #[test]
     fn test_jwt() {
-        let jwt = JwtToken::new(1, "koobe");
-        let res = jwt.create_token("123");
-        println!("{:?}", res);
-        let token = JwtToken::verify("123", &res.unwrap_or_default());
-        println!("{:?}", token)
+        let keys = JwtKeys::from_config(&key_config("HS256", "default")).unwrap();
+        let token = JwtToken::new(1, "koobe", "session", 1800).create_token(&keys).unwrap();
+        let jwt = JwtToken::verify(&keys, &token).unwrap();
+        assert_eq!((jwt.id, jwt.username.as_str(), jwt.session_id()), (1, "koobe", "session"));
+        //对称密钥不公开
+        assert!(keys.jwks().keys.is_empty());
+
+        let other = JwtKeys::from_config(&JwtConfig { secret: "456".to_string(), ..key_config("HS256", "default") }).unwrap();
+        assert!(JwtToken::verify(&other, &token).is_err());
+        assert!(JwtToken::verify(&keys, "invalid").is_err());
+    }
+
+    #[test]
+    fn test_asymmetric_jwt() {
+        for algorithm in [Algorithm::RS256, Algorithm::ES256, Algorithm::EdDSA] {
+            let keys = JwtKeys::from_config(&key_config(&format!("{:?}", algorithm), "2026-10")).unwrap();
+            let token = JwtToken::new(1, "koobe", "session", 1800).create_token(&keys).unwrap();
+            assert_eq!(JwtToken::verify(&keys, &token).unwrap().id, 1);
+
+            //jwks中的公钥可以独立验签,说明从PEM中提取的参数正确
+            let jwks = keys.jwks();
+            assert_eq!(jwks.keys.len(), 1);
+            let jwk = jwks.find("2026-10").unwrap();
+            match (&jwk.algorithm, algorithm) {
+                (AlgorithmParameters::RSA(_), Algorithm::RS256) | (AlgorithmParameters::EllipticCurve(_), Algorithm::ES256) | (AlgorithmParameters::OctetKeyPair(_), Algorithm::EdDSA) => {}
+                _ => panic!("{:?} jwk参数类型错误", algorithm),
+            }
+            let json = serde_json::to_value(jwks).unwrap();
+            assert_eq!(json["keys"][0]["kid"], "2026-10");
+            assert_eq!(json["keys"][0]["use"], "sig");
+            let jwk = serde_json::from_value(json["keys"][0].clone()).unwrap();
+            let mut validation = Validation::new(algorithm);
+            validation.set_audience(&["rust_admin"]);
+            let claims = decode::<JwtToken>(&token, &DecodingKey::from_jwk(&jwk).unwrap(), &validation).unwrap().claims;
+            assert_eq!(claims.username, "koobe");
+        }
+    }
+
+    #[test]
+    fn test_pkcs1_public_key() {
+        let config = JwtConfig {
+            public_key: "src/tests/keys/rs256_public_pkcs1.pem".to_string(),
+            ..key_config("RS256", "pkcs1")
+        };
+        let keys = JwtKeys::from_config(&config).unwrap();
+        let token = JwtToken::new(1, "koobe", "session", 1800).create_token(&keys).unwrap();
+        let jwk = keys.jwks().find("pkcs1").unwrap();
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&["rust_admin"]);
+        assert!(decode::<JwtToken>(&token, &DecodingKey::from_jwk(jwk).unwrap(), &validation).is_ok());
+    }
+
+    #[test]
+    fn test_rotate_algorithm() {
+        //旧token使用HS256签发
+        let old_keys = JwtKeys::from_config(&key_config("HS256", "old")).unwrap();
+        let old_token = JwtToken::new(1, "koobe", "session", 1800).create_token(&old_keys).unwrap();
+
+        //切换到ES256后,旧HS256密钥仍然可以验签
+        let config = JwtConfig {
+            verify_keys: vec![JwtVerifyKeyConfig {
+                kid: "old".to_string(),
+                algorithm: Some("HS256".to_string()),
+                secret: "123".to_string(),
+                public_key: "".to_string(),
+            }],
+            ..key_config("ES256", "new")
+        };
+        let keys = JwtKeys::from_config(&config).unwrap();
+        assert_eq!(JwtToken::verify(&keys, &old_token).unwrap().id, 1);
+        let token = JwtToken::new(2, "admin", "session", 1800).create_token(&keys).unwrap();
+        assert_eq!(JwtToken::verify(&keys, &token).unwrap().id, 2);
+        //只公开非对称公钥
+        assert_eq!(keys.jwks().keys.len(), 1);
+
+        //kid和算法不匹配的token被拒绝
+        let forged = JwtToken::new(1, "koobe", "session", 1800)
+            .create_token(&JwtKeys::from_config(&JwtConfig { secret: "123".to_string(), ..key_config("HS256", "new") }).unwrap())
+            .unwrap();
+        assert!(JwtToken::verify(&keys, &forged).is_err());
     }
 }
@@ -0,0 +1,186 @@
+use crate::utils::session_store_util::StoreConnection;
+use deadpool_redis::redis::{cmd, RedisError};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/*
+ *登录防暴力破解配置
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoginGuardConfig {
+    pub max_mobile_failures: i64, //同一账号在统计窗口内允许的失败次数,达到后锁定账号(0:不限制)
+    pub max_ip_failures: i64,     //同一IP在统计窗口内允许的失败次数,达到后锁定IP(0:不限制)
+    pub failure_window: u64,      //失败次数统计窗口(秒)
+    pub lockout_duration: u64,    //锁定时长(秒),到期自动解锁
+    pub delay_after: i64,         //连续失败超过该次数后开始延迟响应
+    pub delay_step_ms: u64,       //每多失败一次增加的延迟(毫秒)
+    pub max_delay_ms: u64,        //最大延迟(毫秒)
+}
+
+impl Default for LoginGuardConfig {
+    fn default() -> Self {
+        LoginGuardConfig {
+            max_mobile_failures: 5,
+            max_ip_failures: 20,
+            failure_window: 15 * 60,
+            lockout_duration: 30 * 60,
+            delay_after: 2,
+            delay_step_ms: 1000,
+            max_delay_ms: 5000,
+        }
+    }
+}
+
+/*
+ *锁定信息
+ */
+#[derive(Debug)]
+pub enum LoginLock {
+    Mobile(i64), //账号被锁定,剩余锁定时间(秒)
+    Ip(i64),     //IP被锁定,剩余锁定时间(秒)
+}
+
+impl LoginLock {
+    pub fn reason(&self) -> String {
+        match self {
+            LoginLock::Mobile(ttl) => format!("登录失败次数过多,账号已锁定,请{}分钟后再试", minutes(*ttl)),
+            LoginLock::Ip(ttl) => format!("登录失败次数过多,IP已锁定,请{}分钟后再试", minutes(*ttl)),
+        }
+    }
+}
+
+/*
+ *记录失败后的结果
+ */
+#[derive(Debug)]
+pub struct LoginFailure {
+    pub failures: i64,           //账号在统计窗口内的失败次数
+    pub lock: Option<LoginLock>, //本次失败触发的锁定
+}
+
+fn minutes(seconds: i64) -> i64 {
+    (seconds + 59) / 60
+}
+
+fn mobile_failure_key(mobile: &str) -> String {
+    format!("salvo:admin:login:failure:mobile:{}", mobile)
+}
+
+fn ip_failure_key(ip: &str) -> String {
+    format!("salvo:admin:login:failure:ip:{}", ip)
+}
+
+fn mobile_lock_key(mobile: &str) -> String {
+    format!("salvo:admin:login:lock:mobile:{}", mobile)
+}
+
+fn ip_lock_key(ip: &str) -> String {
+    format!("salvo:admin:login:lock:ip:{}", ip)
+}
+
+/*
+ *查询账号或者IP是否被锁定
+ */
+pub async fn check_lock(conn: &mut StoreConnection, mobile: &str, ip: &str) -> Result<Option<LoginLock>, RedisError> {
+    let ttl: i64 = cmd("TTL").arg(mobile_lock_key(mobile)).query_async(conn).await?;
+    if ttl > 0 {
+        return Ok(Some(LoginLock::Mobile(ttl)));
+    }
+    if !ip.is_empty() {
+        let ttl: i64 = cmd("TTL").arg(ip_lock_key(ip)).query_async(conn).await?;
+        if ttl > 0 {
+            return Ok(Some(LoginLock::Ip(ttl)));
+        }
+    }
+    Ok(None)
+}
+
+/*
+ *查询账号在统计窗口内的失败次数
+ */
+pub async fn failure_count(conn: &mut StoreConnection, mobile: &str) -> Result<i64, RedisError> {
+    let failures: Option<i64> = cmd("GET").arg(mobile_failure_key(mobile)).query_async(conn).await?;
+    Ok(failures.unwrap_or_default())
+}
+
+/*
+ *记录一次登录失败,达到阈值时锁定账号或者IP
+ */
+pub async fn record_failure(conn: &mut StoreConnection, config: &LoginGuardConfig, mobile: &str, ip: &str) -> Result<LoginFailure, RedisError> {
+    let failures = incr_failure(conn, &mobile_failure_key(mobile), config.failure_window).await?;
+    let mut lock = None;
+    if config.max_mobile_failures > 0 && failures >= config.max_mobile_failures {
+        lock_key(conn, &mobile_lock_key(mobile), &mobile_failure_key(mobile), config.lockout_duration).await?;
+        lock = Some(LoginLock::Mobile(config.lockout_duration as i64));
+    }
+
+    if !ip.is_empty() {
+        let ip_failures = incr_failure(conn, &ip_failure_key(ip), config.failure_window).await?;
+        if config.max_ip_failures > 0 && ip_failures >= config.max_ip_failures {
+            lock_key(conn, &ip_lock_key(ip), &ip_failure_key(ip), config.lockout_duration).await?;
+            lock = lock.or(Some(LoginLock::Ip(config.lockout_duration as i64)));
+        }
+    }
+
+    Ok(LoginFailure { failures, lock })
+}
+
+async fn incr_failure(conn: &mut StoreConnection, key: &str, window: u64) -> Result<i64, RedisError> {
+    let failures: i64 = cmd("INCR").arg(key).query_async(conn).await?;
+    //第一次失败时开始计时,窗口内的后续失败不顺延
+    if failures == 1 {
+        cmd("EXPIRE").arg(key).arg(window).query_async::<()>(conn).await?;
+    }
+    Ok(failures)
+}
+
+async fn lock_key(conn: &mut StoreConnection, lock_key: &str, failure_key: &str, duration: u64) -> Result<(), RedisError> {
+    cmd("SET").arg(lock_key).arg(1).arg("EX").arg(duration).query_async::<()>(conn).await?;
+    cmd("DEL").arg(failure_key).query_async::<()>(conn).await
+}
+
+/*
+ *登录成功后清除账号的失败次数(IP的失败次数不清除,避免攻击者用自己的账号重置计数)
+ */
+pub async fn clear_failures(conn: &mut StoreConnection, mobile: &str) -> Result<(), RedisError> {
+    cmd("DEL").arg(mobile_failure_key(mobile)).query_async::<()>(conn).await
+}
+
+/*
+ *解锁账号,ip不为空时同时解锁IP
+ */
+pub async fn unlock(conn: &mut StoreConnection, mobile: &str, ip: Option<&str>) -> Result<(), RedisError> {
+    cmd("DEL").arg(mobile_lock_key(mobile)).arg(mobile_failure_key(mobile)).query_async::<()>(conn).await?;
+    if let Some(ip) = ip.filter(|x| !x.is_empty()) {
+        cmd("DEL").arg(ip_lock_key(ip)).arg(ip_failure_key(ip)).query_async::<()>(conn).await?;
+    }
+    Ok(())
+}
+
+/*
+ *根据失败次数计算渐进式延迟
+ */
+pub fn failure_delay(config: &LoginGuardConfig, failures: i64) -> Duration {
+    if failures <= config.delay_after {
+        return Duration::ZERO;
+    }
+    let delay = config.delay_step_ms.saturating_mul((failures - config.delay_after) as u64);
+    Duration::from_millis(delay.min(config.max_delay_ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::login_guard_util::{failure_delay, LoginGuardConfig};
+    use std::time::Duration;
+
+    #[test]
+    fn test_failure_delay() {
+        let config = LoginGuardConfig::default();
+        assert_eq!(failure_delay(&config, 1), Duration::ZERO);
+        assert_eq!(failure_delay(&config, 2), Duration::ZERO);
+        assert_eq!(failure_delay(&config, 3), Duration::from_millis(1000));
+        assert_eq!(failure_delay(&config, 4), Duration::from_millis(2000));
+        assert_eq!(failure_delay(&config, 100), Duration::from_millis(5000));
+    }
+}
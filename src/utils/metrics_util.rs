@@ -0,0 +1,162 @@
+use crate::utils::operate_log_util::OperateLogWriter;
+use crate::utils::session_store_util::SessionStore;
+use once_cell::sync::Lazy;
+use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder};
+use rbatis::RBatis;
+use serde::{Deserialize, Serialize};
+
+//请求耗时的分桶(秒)
+const DURATION_BUCKETS: [f64; 12] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+//没有匹配到路由的请求统一记为该值,避免扫描请求产生大量的标签
+const ROUTE_UNMATCHED: &str = "unmatched";
+
+pub static METRICS: Lazy<Metrics> = Lazy::new(Metrics::new);
+
+/*
+ *监控指标配置
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub enabled: bool,          //是否开放 /metrics
+    pub allow_ips: Vec<String>, //允许直接访问的IP或者CIDR
+    pub token: String,          //其它地址需要携带 Authorization: Bearer <token>,为空时只允许allow_ips访问
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig {
+            enabled: true,
+            allow_ips: vec!["127.0.0.1".to_string(), "::1".to_string()],
+            token: "".to_string(),
+        }
+    }
+}
+
+/*
+ *Prometheus监控指标
+ */
+pub struct Metrics {
+    registry: Registry,           //指标注册表
+    http_requests: IntCounterVec, //请求数
+    http_duration: HistogramVec,  //请求耗时
+    auth_failures: IntCounterVec, //认证失败次数
+    logins: IntCounterVec,        //登录次数
+    db_pool: IntGaugeVec,         //数据库连接池
+    redis_pool: IntGaugeVec,      //redis连接池
+    queue_depth: IntGaugeVec,     //后台队列中等待处理的数量
+    queue_capacity: IntGaugeVec,  //后台队列容量
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+        let http_requests = IntCounterVec::new(Opts::new("http_requests_total", "Total number of HTTP requests"), &["method", "route", "status"]).expect("create metric error");
+        let http_duration = HistogramVec::new(HistogramOpts::new("http_request_duration_seconds", "HTTP request latency in seconds").buckets(DURATION_BUCKETS.to_vec()), &["method", "route", "status"]).expect("create metric error");
+        let auth_failures = IntCounterVec::new(Opts::new("auth_failures_total", "Total number of rejected authentications"), &["reason"]).expect("create metric error");
+        let logins = IntCounterVec::new(Opts::new("login_attempts_total", "Total number of login attempts"), &["result"]).expect("create metric error");
+        let db_pool = IntGaugeVec::new(Opts::new("db_pool_connections", "Database pool connections"), &["state"]).expect("create metric error");
+        let redis_pool = IntGaugeVec::new(Opts::new("redis_pool_connections", "Redis pool connections"), &["state"]).expect("create metric error");
+        let queue_depth = IntGaugeVec::new(Opts::new("background_queue_depth", "Items waiting in background queues"), &["queue"]).expect("create metric error");
+        let queue_capacity = IntGaugeVec::new(Opts::new("background_queue_capacity", "Capacity of background queues"), &["queue"]).expect("create metric error");
+
+        registry.register(Box::new(http_requests.clone())).expect("register metric error");
+        registry.register(Box::new(http_duration.clone())).expect("register metric error");
+        registry.register(Box::new(auth_failures.clone())).expect("register metric error");
+        registry.register(Box::new(logins.clone())).expect("register metric error");
+        registry.register(Box::new(db_pool.clone())).expect("register metric error");
+        registry.register(Box::new(redis_pool.clone())).expect("register metric error");
+        registry.register(Box::new(queue_depth.clone())).expect("register metric error");
+        registry.register(Box::new(queue_capacity.clone())).expect("register metric error");
+
+        Metrics {
+            registry,
+            http_requests,
+            http_duration,
+            auth_failures,
+            logins,
+            db_pool,
+            redis_pool,
+            queue_depth,
+            queue_capacity,
+        }
+    }
+
+    /// 记录一次请求
+    pub fn observe_request(&self, method: &str, path: &str, status: u16, seconds: f64) {
+        let route = route_label(path, status);
+        let status = status.to_string();
+        let labels = [method, route.as_str(), status.as_str()];
+        self.http_requests.with_label_values(&labels).inc();
+        self.http_duration.with_label_values(&labels).observe(seconds);
+    }
+
+    /// 记录一次认证失败
+    pub fn auth_failure(&self, reason: &str) {
+        self.auth_failures.with_label_values(&[reason]).inc();
+    }
+
+    /// 记录一次登录
+    pub fn login(&self, success: bool) {
+        self.logins.with_label_values(&[if success { "success" } else { "failure" }]).inc();
+    }
+
+    /// 采集连接池和后台队列的当前状态,在每次抓取时调用
+    pub async fn collect(&self, rb: &RBatis, redis: &SessionStore, operate_log_writer: &OperateLogWriter) {
+        if let Ok(pool) = rb.get_pool() {
+            let state = pool.state().await;
+            for (label, key) in [("max", "max_open"), ("open", "connections"), ("in_use", "in_use"), ("idle", "idle"), ("waiting", "waits")] {
+                self.db_pool.with_label_values(&[label]).set(state[key].as_i64().unwrap_or_default());
+            }
+        }
+        if let Some(pool) = redis.redis_pool() {
+            let status = pool.status();
+            let in_use = status.size.saturating_sub(status.available);
+            for (label, value) in [("max", status.max_size), ("open", status.size), ("in_use", in_use), ("idle", status.available), ("waiting", status.waiting)] {
+                self.redis_pool.with_label_values(&[label]).set(value as i64);
+            }
+        }
+        let (depth, capacity) = operate_log_writer.queue_depth();
+        self.queue_depth.with_label_values(&["operate_log"]).set(depth as i64);
+        self.queue_capacity.with_label_values(&["operate_log"]).set(capacity as i64);
+    }
+
+    /// Prometheus文本格式
+    pub fn render(&self) -> String {
+        TextEncoder::new().encode_to_string(&self.registry.gather()).unwrap_or_else(|e| {
+            log::error!("encode metrics error: {}", e);
+            "".to_string()
+        })
+    }
+}
+
+/// 接口路径中的数字(例如id)替换为{id},没有匹配到路由的请求记为unmatched
+fn route_label(path: &str, status: u16) -> String {
+    if status == 404 {
+        return ROUTE_UNMATCHED.to_string();
+    }
+    path.split('/').map(|x| if !x.is_empty() && x.chars().all(|c| c.is_ascii_digit()) { "{id}" } else { x }).collect::<Vec<&str>>().join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::metrics_util::{route_label, Metrics};
+
+    #[test]
+    fn test_metrics() {
+        assert_eq!(route_label("/api/system/user/queryUserList", 200), "/api/system/user/queryUserList");
+        assert_eq!(route_label("/api/system/user/12/roles", 200), "/api/system/user/{id}/roles");
+        assert_eq!(route_label("/wp-login.php", 404), "unmatched");
+
+        //集成测试也会记录全局的METRICS,这里使用单独的实例
+        let metrics = Metrics::new();
+        metrics.observe_request("POST", "/api/system/user/login", 200, 0.02);
+        metrics.auth_failure("missing_token");
+        metrics.login(false);
+        let text = metrics.render();
+        assert!(text.contains(r#"http_requests_total{method="POST",route="/api/system/user/login",status="200"} 1"#));
+        assert!(text.contains(r#"http_request_duration_seconds_bucket{method="POST",route="/api/system/user/login",status="200",le="0.025"} 1"#));
+        assert!(text.contains(r#"auth_failures_total{reason="missing_token"} 1"#));
+        assert!(text.contains(r#"login_attempts_total{result="failure"} 1"#));
+    }
+}
@@ -0,0 +1,250 @@
+use deadpool_redis::redis::{cmd, RedisError};
+use hmac::{Hmac, Mac};
+use qrcode::{Color, QrCode};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+use crate::common::error::AppError;
+use crate::utils::session_store_util::StoreConnection;
+
+//RFC 6238: 6位数字,30秒一个时间步,与常见的身份验证器App兼容
+const TOTP_DIGITS: u32 = 6;
+const TOTP_PERIOD: u64 = 30;
+const BASE32_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const RECOVERY_CODE_CHARSET: &[u8] = b"23456789abcdefghjkmnpqrstuvwxyz";
+
+/*
+ *双因素认证配置
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MfaConfig {
+    pub issuer: String,        //身份验证器App中显示的发行方
+    pub skew: u64,             //允许前后偏差的时间步数
+    pub challenge_expire: u64, //登录第二步的挑战token有效期(秒)
+    pub max_attempts: i64,     //每个挑战token允许尝试的次数
+    pub recovery_codes: usize, //恢复码数量
+}
+
+impl Default for MfaConfig {
+    fn default() -> Self {
+        MfaConfig {
+            issuer: "salvo-admin".to_string(),
+            skew: 1,
+            challenge_expire: 300,
+            max_attempts: 5,
+            recovery_codes: 10,
+        }
+    }
+}
+
+fn challenge_key(challenge_token: &str) -> String {
+    format!("salvo:admin:mfa:challenge:{}", challenge_token)
+}
+
+/// 生成160位的TOTP密钥,Base32编码
+pub fn generate_secret() -> String {
+    base32_encode(&rand::random::<[u8; 20]>())
+}
+
+fn base32_encode(data: &[u8]) -> String {
+    let mut out = String::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for byte in data {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            out.push(BASE32_ALPHABET[((buffer >> (bits - 5)) & 0x1F) as usize] as char);
+            bits -= 5;
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1F) as usize] as char);
+    }
+    out
+}
+
+fn base32_decode(data: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for c in data.bytes().filter(|c| *c != b'=' && *c != b' ') {
+        let value = BASE32_ALPHABET.iter().position(|x| *x == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            out.push((buffer >> (bits - 8)) as u8);
+            bits -= 8;
+        }
+    }
+    Some(out)
+}
+
+/// 计算指定时间步的动态码(HMAC-SHA1 + 动态截断)
+fn hotp(key: &[u8], counter: u64, digits: u32) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("hmac accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    let offset = (hash[hash.len() - 1] & 0x0F) as usize;
+    let value = u32::from_be_bytes([hash[offset] & 0x7F, hash[offset + 1], hash[offset + 2], hash[offset + 3]]);
+    format!("{:0width$}", value % 10u32.pow(digits), width = digits as usize)
+}
+
+/*
+ *校验动态码,返回匹配的时间步
+ *时间步必须大于last_step,同一个动态码不能重复使用
+ */
+pub fn verify_totp(config: &MfaConfig, secret: &str, code: &str, now: u64, last_step: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let key = base32_decode(secret)?;
+    let current = now / TOTP_PERIOD;
+    (current.saturating_sub(config.skew)..=current + config.skew)
+        .filter(|step| *step > last_step)
+        .find(|step| hotp(&key, *step, TOTP_DIGITS) == code)
+}
+
+/// 生成身份验证器App使用的otpauth URI
+pub fn otpauth_uri(config: &MfaConfig, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(&config.issuer),
+        percent_encode(account),
+        secret,
+        percent_encode(&config.issuer),
+        TOTP_DIGITS,
+        TOTP_PERIOD
+    )
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/*
+ *把文本渲染成二维码PNG图片(每个模块6像素,四周留4个模块的空白)
+ */
+pub fn render_qr_png(text: &str) -> Result<Vec<u8>, AppError> {
+    let code = QrCode::new(text.as_bytes()).map_err(|e| {
+        log::error!("generate qr code error: {:?}", e);
+        AppError::BusinessError("生成二维码异常")
+    })?;
+    let (modules, scale, quiet) = (code.width(), 6usize, 4usize);
+    let colors = code.to_colors();
+    let size = (modules + quiet * 2) * scale;
+    let mut pixels = vec![255u8; size * size];
+    for (i, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let (x, y) = ((i % modules + quiet) * scale, (i / modules + quiet) * scale);
+        for dy in 0..scale {
+            let row = (y + dy) * size;
+            pixels[row + x..row + x + scale].fill(0);
+        }
+    }
+
+    let mut buf: Vec<u8> = Vec::new();
+    let mut encoder = png::Encoder::new(&mut buf, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let encode = || -> Result<(), png::EncodingError> {
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&pixels)?;
+        writer.finish()
+    };
+    encode().map_err(|e| {
+        log::error!("render qr code png error: {:?}", e);
+        AppError::BusinessError("生成二维码异常")
+    })?;
+    Ok(buf)
+}
+
+/// 生成恢复码,格式: xxxxx-xxxxx
+pub fn generate_recovery_codes(count: usize) -> Vec<String> {
+    let random_part = || -> String { (0..5).map(|_| RECOVERY_CODE_CHARSET[rand::random::<usize>() % RECOVERY_CODE_CHARSET.len()] as char).collect() };
+    (0..count).map(|_| format!("{}-{}", random_part(), random_part())).collect()
+}
+
+/// 恢复码只保存SHA-256摘要,忽略大小写和分隔符
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code.chars().filter(|c| c.is_ascii_alphanumeric()).map(|c| c.to_ascii_lowercase()).collect();
+    Sha256::digest(normalized.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/*
+ *保存登录第二步的挑战,返回挑战token
+ */
+pub async fn create_challenge(conn: &mut StoreConnection, user_id: i64, expire: u64) -> Result<String, RedisError> {
+    let challenge_token = format!("{:032x}{:032x}", rand::random::<u128>(), rand::random::<u128>());
+    let key = challenge_key(&challenge_token);
+    cmd("HSET").arg(&key).arg("user_id").arg(user_id).arg("attempts").arg(0).query_async::<()>(conn).await?;
+    cmd("EXPIRE").arg(&key).arg(expire).query_async::<()>(conn).await?;
+    Ok(challenge_token)
+}
+
+/*
+ *查询挑战对应的用户,同时累加尝试次数,超过次数时挑战作废
+ */
+pub async fn take_challenge_attempt(conn: &mut StoreConnection, config: &MfaConfig, challenge_token: &str) -> Result<Option<i64>, RedisError> {
+    let key = challenge_key(challenge_token);
+    let values: HashMap<String, String> = cmd("HGETALL").arg(&key).query_async(conn).await?;
+    let user_id = match values.get("user_id").and_then(|x| x.parse::<i64>().ok()) {
+        None => return Ok(None),
+        Some(x) => x,
+    };
+    let attempts: i64 = cmd("HINCRBY").arg(&key).arg("attempts").arg(1).query_async(conn).await?;
+    if attempts > config.max_attempts {
+        remove_challenge(conn, challenge_token).await?;
+        return Ok(None);
+    }
+    Ok(Some(user_id))
+}
+
+/*
+ *查询挑战对应的用户(不累加尝试次数)
+ */
+pub async fn get_challenge_user(conn: &mut StoreConnection, challenge_token: &str) -> Result<Option<i64>, RedisError> {
+    let user_id: Option<String> = cmd("HGET").arg(challenge_key(challenge_token)).arg("user_id").query_async(conn).await?;
+    Ok(user_id.and_then(|x| x.parse::<i64>().ok()))
+}
+
+pub async fn remove_challenge(conn: &mut StoreConnection, challenge_token: &str) -> Result<(), RedisError> {
+    cmd("DEL").arg(challenge_key(challenge_token)).query_async::<()>(conn).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::mfa_util::{base32_decode, base32_encode, hash_recovery_code, hotp, verify_totp, MfaConfig};
+
+    #[test]
+    fn test_totp() {
+        //RFC 6238 附录B的SHA1测试向量
+        let key = b"12345678901234567890";
+        assert_eq!(hotp(key, 59 / 30, 8), "94287082");
+        assert_eq!(hotp(key, 1111111109 / 30, 8), "07081804");
+        assert_eq!(hotp(key, 1234567890 / 30, 8), "89005924");
+
+        let secret = base32_encode(key);
+        assert_eq!(secret, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(base32_decode(&secret).unwrap(), key.to_vec());
+
+        let config = MfaConfig::default();
+        let code = hotp(key, 1234567890 / 30, 6);
+        assert_eq!(verify_totp(&config, &secret, &code, 1234567890, 0), Some(1234567890 / 30));
+        assert_eq!(verify_totp(&config, &secret, &code, 1234567890 + 30, 0), Some(1234567890 / 30));
+        assert_eq!(verify_totp(&config, &secret, &code, 1234567890 + 90, 0), None);
+        assert_eq!(verify_totp(&config, &secret, &code, 1234567890, 1234567890 / 30), None);
+
+        assert_eq!(hash_recovery_code("ABCDE-fghjk"), hash_recovery_code("abcdefghjk"));
+    }
+}
@@ -0,0 +1,368 @@
+use crate::utils::db_util::DbKind;
+use rbatis::executor::RBatisConnExecutor;
+use rbatis::RBatis;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::future::Future;
+
+/*
+ *数据库迁移: 版本号从小到大依次执行,up为升级脚本,down为可选的回滚脚本
+ *执行过的迁移记录在schema_migrations表中,并保存脚本的checksum,脚本被修改后拒绝继续执行
+ */
+pub struct Migration {
+    pub version: i64,               //版本号
+    pub name: &'static str,         //名称
+    pub up: &'static str,           //升级脚本
+    pub down: Option<&'static str>, //回滚脚本
+}
+
+/*
+ *初始数据: 只在表中没有数据时导入,可以重复执行
+ */
+pub struct Seed {
+    pub table: &'static str, //表名
+    pub sql: &'static str,   //insert和update语句
+}
+
+/// 编译进程序的迁移脚本,新增迁移时版本号必须大于已有的版本号,三种数据库需要同时新增
+pub const MYSQL_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init_schema",
+        up: include_str!("../../migrations/mysql/V1__init_schema.up.sql"),
+        down: Some(include_str!("../../migrations/mysql/V1__init_schema.down.sql")),
+    },
+    Migration {
+        version: 2,
+        name: "auth_schema",
+        up: include_str!("../../migrations/mysql/V2__auth_schema.up.sql"),
+        down: Some(include_str!("../../migrations/mysql/V2__auth_schema.down.sql")),
+    },
+];
+
+pub const POSTGRES_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init_schema",
+        up: include_str!("../../migrations/postgres/V1__init_schema.up.sql"),
+        down: Some(include_str!("../../migrations/postgres/V1__init_schema.down.sql")),
+    },
+    Migration {
+        version: 2,
+        name: "auth_schema",
+        up: include_str!("../../migrations/postgres/V2__auth_schema.up.sql"),
+        down: Some(include_str!("../../migrations/postgres/V2__auth_schema.down.sql")),
+    },
+];
+
+pub const SQLITE_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "init_schema",
+        up: include_str!("../../migrations/sqlite/V1__init_schema.up.sql"),
+        down: Some(include_str!("../../migrations/sqlite/V1__init_schema.down.sql")),
+    },
+    Migration {
+        version: 2,
+        name: "auth_schema",
+        up: include_str!("../../migrations/sqlite/V2__auth_schema.up.sql"),
+        down: Some(include_str!("../../migrations/sqlite/V2__auth_schema.down.sql")),
+    },
+];
+
+/// 初始数据,按依赖顺序导入(菜单指定了id,上级id引用这些id),三种数据库共用,只能使用通用的insert语法
+pub const SEEDS: &[Seed] = &[
+    Seed { table: "sys_dept", sql: include_str!("../../migrations/seed/sys_dept.sql") },
+    Seed { table: "sys_dict_type", sql: include_str!("../../migrations/seed/sys_dict_type.sql") },
+    Seed { table: "sys_dict_data", sql: include_str!("../../migrations/seed/sys_dict_data.sql") },
+    Seed { table: "sys_menu", sql: include_str!("../../migrations/seed/sys_menu.sql") },
+    Seed { table: "sys_notice", sql: include_str!("../../migrations/seed/sys_notice.sql") },
+    Seed { table: "sys_post", sql: include_str!("../../migrations/seed/sys_post.sql") },
+    Seed { table: "sys_role", sql: include_str!("../../migrations/seed/sys_role.sql") },
+    Seed { table: "sys_user", sql: include_str!("../../migrations/seed/sys_user.sql") },
+    Seed { table: "sys_user_post", sql: include_str!("../../migrations/seed/sys_user_post.sql") },
+    Seed { table: "sys_user_role", sql: include_str!("../../migrations/seed/sys_user_role.sql") },
+];
+
+const CREATE_MIGRATION_TABLE: &str = "create table if not exists schema_migrations
+(
+    version    bigint                             not null primary key,
+    name       varchar(100)                       not null,
+    checksum   char(64)                           not null,
+    applied_at timestamp default CURRENT_TIMESTAMP not null
+)";
+
+//迁移锁: MySQL的GET_LOCK名称和PostgreSQL的advisory lock编号
+const MIGRATION_LOCK_NAME: &str = "salvo_admin_schema_migrations";
+const MIGRATION_LOCK_ID: i64 = 5_317_204_918_113_604_021;
+//MySQL等待迁移锁的秒数
+const MIGRATION_LOCK_TIMEOUT: i64 = 600;
+
+#[derive(Debug, Deserialize)]
+struct AppliedMigration {
+    version: i64,     //版本号
+    name: String,     //名称
+    checksum: String, //执行时升级脚本的checksum
+}
+
+/// 数据库对应的迁移脚本
+pub fn migrations(kind: DbKind) -> &'static [Migration] {
+    match kind {
+        DbKind::Mysql => MYSQL_MIGRATIONS,
+        DbKind::Postgres => POSTGRES_MIGRATIONS,
+        DbKind::Sqlite => SQLITE_MIGRATIONS,
+    }
+}
+
+impl Migration {
+    /// 升级脚本的SHA-256
+    pub fn checksum(&self) -> String {
+        Sha256::digest(self.up.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn label(&self) -> String {
+        format!("V{}__{}", self.version, self.name)
+    }
+}
+
+/// 执行所有未执行的迁移,返回本次执行的版本号
+pub async fn migrate(rb: &RBatis, migrations: &[Migration]) -> Result<Vec<i64>, String> {
+    with_lock(rb, apply_migrations(rb, migrations)).await
+}
+
+async fn apply_migrations(rb: &RBatis, migrations: &[Migration]) -> Result<Vec<i64>, String> {
+    let applied = applied_migrations(rb).await?;
+    verify(migrations, &applied)?;
+
+    let mut result = Vec::new();
+    for migration in migrations.iter().filter(|x| !applied.iter().any(|a| a.version == x.version)) {
+        log::info!("apply migration {}", migration.label());
+        //MySQL的DDL会隐式提交事务,脚本执行到一半失败时需要手工处理
+        execute_script(rb, migration.up).await.map_err(|e| format!("执行迁移{}失败: {}", migration.label(), e))?;
+        rb.exec("insert into schema_migrations (version, name, checksum) values (?, ?, ?)", vec![migration.version.into(), migration.name.into(), migration.checksum().into()])
+            .await
+            .map_err(|e| format!("记录迁移{}失败: {}", migration.label(), e))?;
+        result.push(migration.version);
+    }
+    Ok(result)
+}
+
+/// 回滚最近一次执行的迁移,没有执行过的迁移时返回None
+pub async fn rollback(rb: &RBatis, migrations: &[Migration]) -> Result<Option<i64>, String> {
+    with_lock(rb, rollback_last(rb, migrations)).await
+}
+
+async fn rollback_last(rb: &RBatis, migrations: &[Migration]) -> Result<Option<i64>, String> {
+    let applied = applied_migrations(rb).await?;
+    verify(migrations, &applied)?;
+
+    let last = match applied.last() {
+        Some(x) => x,
+        None => return Ok(None),
+    };
+    let migration = migrations.iter().find(|x| x.version == last.version).ok_or_else(|| format!("迁移V{}__{}不存在", last.version, last.name))?;
+    let down = migration.down.ok_or_else(|| format!("迁移{}没有回滚脚本", migration.label()))?;
+
+    log::info!("rollback migration {}", migration.label());
+    execute_script(rb, down).await.map_err(|e| format!("回滚迁移{}失败: {}", migration.label(), e))?;
+    rb.exec("delete from schema_migrations where version = ?", vec![migration.version.into()])
+        .await
+        .map_err(|e| format!("删除迁移记录{}失败: {}", migration.label(), e))?;
+    Ok(Some(migration.version))
+}
+
+/// 导入初始数据,已经有数据的表跳过,返回本次导入的表
+pub async fn seed(rb: &RBatis, seeds: &[Seed]) -> Result<Vec<&'static str>, String> {
+    with_lock(rb, import_seeds(rb, seeds)).await
+}
+
+async fn import_seeds(rb: &RBatis, seeds: &[Seed]) -> Result<Vec<&'static str>, String> {
+    let mut result = Vec::new();
+    for seed in seeds {
+        let count: u64 = rb.query_decode(&format!("select count(1) from {}", seed.table), vec![]).await.map_err(|e| format!("查询{}失败: {}", seed.table, e))?;
+        if count > 0 {
+            continue;
+        }
+
+        log::info!("seed table {}", seed.table);
+        let tx = rb.acquire_begin().await.map_err(|e| format!("开启事务失败: {}", e))?;
+        for statement in split_statements(seed.sql) {
+            if let Err(e) = tx.exec(&statement, vec![]).await {
+                let _ = tx.rollback().await;
+                return Err(format!("导入{}的初始数据失败: {}\n{}", seed.table, e, statement));
+            }
+        }
+        tx.commit().await.map_err(|e| format!("导入{}的初始数据失败: {}", seed.table, e))?;
+        if rb.driver_type().ok().and_then(DbKind::from_driver) == Some(DbKind::Postgres) {
+            sync_sequence(rb, seed.table).await?;
+        }
+        result.push(seed.table);
+    }
+    Ok(result)
+}
+
+/*
+ *在迁移锁中执行task,多个实例同时启动并自动迁移时,后拿到锁的实例会跳过已经执行的迁移和已经导入的数据
+ *锁在单独的连接上持有(MySQL的GET_LOCK和PostgreSQL的advisory lock都属于连接),task使用连接池中的其它连接
+ *SQLite只能由一个进程使用,不加锁
+ */
+async fn with_lock<T>(rb: &RBatis, task: impl Future<Output = Result<T, String>>) -> Result<T, String> {
+    let kind = rb.driver_type().ok().and_then(DbKind::from_driver);
+    let conn = match kind {
+        Some(DbKind::Mysql) | Some(DbKind::Postgres) => Some(rb.acquire().await.map_err(|e| format!("获取迁移锁的连接失败: {}", e))?),
+        _ => None,
+    };
+    if let Some(conn) = &conn {
+        lock(conn, kind).await?;
+    }
+    let result = task.await;
+    if let Some(conn) = &conn {
+        if let Err(e) = unlock(conn, kind).await {
+            log::error!("release migration lock error: {}", e);
+        }
+    }
+    result
+}
+
+async fn lock(conn: &RBatisConnExecutor, kind: Option<DbKind>) -> Result<(), String> {
+    log::info!("acquire migration lock");
+    if kind == Some(DbKind::Mysql) {
+        //1: 拿到锁, 0: 等待超时, NULL: 出错
+        let locked: Option<i64> =
+            conn.query_decode("select get_lock(?, ?)", vec![MIGRATION_LOCK_NAME.into(), MIGRATION_LOCK_TIMEOUT.into()]).await.map_err(|e| format!("获取迁移锁失败: {}", e))?;
+        if locked != Some(1) {
+            return Err(format!("等待迁移锁超时({}秒),其它实例可能正在执行迁移", MIGRATION_LOCK_TIMEOUT));
+        }
+    } else {
+        //pg_advisory_lock返回void,一直等待到拿到锁
+        let _: i64 = conn
+            .query_decode("select cast(1 as bigint) from (select pg_advisory_lock(?)) x", vec![MIGRATION_LOCK_ID.into()])
+            .await
+            .map_err(|e| format!("获取迁移锁失败: {}", e))?;
+    }
+    Ok(())
+}
+
+async fn unlock(conn: &RBatisConnExecutor, kind: Option<DbKind>) -> Result<(), String> {
+    let result = if kind == Some(DbKind::Mysql) {
+        conn.query("select release_lock(?)", vec![MIGRATION_LOCK_NAME.into()]).await
+    } else {
+        conn.query("select pg_advisory_unlock(?)", vec![MIGRATION_LOCK_ID.into()]).await
+    };
+    result.map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// PostgreSQL插入指定id的数据后不会更新自增序列,需要把序列设置为当前最大的id
+async fn sync_sequence(rb: &RBatis, table: &str) -> Result<(), String> {
+    let sql = "select count(1) from information_schema.columns where table_schema = current_schema() and table_name = ? and column_name = 'id'";
+    let count: u64 = rb.query_decode(sql, vec![table.into()]).await.map_err(|e| format!("查询{}的字段失败: {}", table, e))?;
+    if count > 0 {
+        let sql = format!("select setval(pg_get_serial_sequence(?, 'id'), (select max(id) from {}))", table);
+        rb.exec(&sql, vec![table.into()]).await.map_err(|e| format!("同步{}的自增序列失败: {}", table, e))?;
+    }
+    Ok(())
+}
+
+async fn applied_migrations(rb: &RBatis) -> Result<Vec<AppliedMigration>, String> {
+    rb.exec(CREATE_MIGRATION_TABLE, vec![]).await.map_err(|e| format!("创建schema_migrations表失败: {}", e))?;
+    rb.query_decode("select version, name, checksum from schema_migrations order by version", vec![])
+        .await
+        .map_err(|e| format!("查询schema_migrations失败: {}", e))
+}
+
+/// 已执行的迁移必须存在且没有被修改,未执行的迁移版本号必须大于已执行的版本号
+fn verify(migrations: &[Migration], applied: &[AppliedMigration]) -> Result<(), String> {
+    if migrations.windows(2).any(|x| x[0].version >= x[1].version) {
+        return Err("迁移的版本号必须从小到大且不能重复".to_string());
+    }
+    for item in applied {
+        let migration = migrations
+            .iter()
+            .find(|x| x.version == item.version)
+            .ok_or_else(|| format!("数据库中的迁移V{}__{}在当前程序中不存在,请使用更新版本的程序", item.version, item.name))?;
+        if migration.checksum() != item.checksum {
+            return Err(format!("迁移{}在执行后被修改过(checksum不一致),请新增迁移而不是修改已执行的迁移", migration.label()));
+        }
+    }
+    let last = applied.iter().map(|x| x.version).max().unwrap_or_default();
+    if let Some(x) = migrations.iter().find(|x| x.version < last && !applied.iter().any(|a| a.version == x.version)) {
+        return Err(format!("迁移{}的版本号小于已执行的V{}", x.label(), last));
+    }
+    Ok(())
+}
+
+async fn execute_script(rb: &RBatis, sql: &str) -> Result<(), String> {
+    for statement in split_statements(sql) {
+        rb.exec(&statement, vec![]).await.map_err(|e| format!("{}\n{}", e, statement))?;
+    }
+    Ok(())
+}
+
+/// 按分号拆分脚本中的语句,忽略引号中的分号和--注释
+pub fn split_statements(sql: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut push = |current: &mut String| {
+        let statement = current.trim();
+        if !statement.is_empty() {
+            result.push(statement.to_string());
+        }
+        current.clear();
+    };
+    for line in sql.lines() {
+        if quote.is_none() && line.trim_start().starts_with("--") {
+            continue;
+        }
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None if c == '-' && chars.peek() == Some(&'-') => break,
+                None if c == '\'' || c == '"' || c == '`' => quote = Some(c),
+                None if c == ';' => {
+                    push(&mut current);
+                    continue;
+                }
+                None => {}
+            }
+            current.push(c);
+        }
+        current.push('\n');
+    }
+    push(&mut current);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::migration_util::{split_statements, MYSQL_MIGRATIONS, POSTGRES_MIGRATIONS, SEEDS, SQLITE_MIGRATIONS};
+
+    #[test]
+    fn test_split_statements() {
+        let sql = "-- 注释;\ncreate table a\n(\n    id bigint -- 主键;\n);\n\ninsert into a values ('x;y', '--', `k`);\n-- 注释\nupdate a set id = 1";
+        let statements = split_statements(sql);
+        assert_eq!(statements, vec!["create table a\n(\n    id bigint \n)", "insert into a values ('x;y', '--', `k`)", "update a set id = 1"]);
+        assert!(split_statements("-- 只有注释\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_embedded_scripts() {
+        for migrations in [MYSQL_MIGRATIONS, POSTGRES_MIGRATIONS, SQLITE_MIGRATIONS] {
+            assert!(migrations.windows(2).all(|x| x[0].version < x[1].version));
+            //三种数据库的迁移版本必须一致
+            assert_eq!(migrations.iter().map(|x| x.version).collect::<Vec<_>>(), MYSQL_MIGRATIONS.iter().map(|x| x.version).collect::<Vec<_>>());
+            for migration in migrations {
+                assert_eq!(migration.checksum().len(), 64);
+                assert!(!split_statements(migration.up).is_empty());
+            }
+        }
+        for seed in SEEDS {
+            let statements = split_statements(seed.sql);
+            assert!(!statements.is_empty(), "{}", seed.table);
+            assert!(statements.iter().all(|x| !x.to_lowercase().starts_with("drop") && !x.to_lowercase().starts_with("create")), "{}", seed.table);
+            assert!(!seed.sql.contains('`'), "{}: PostgreSQL不支持反引号", seed.table);
+        }
+    }
+}
@@ -1,4 +1,20 @@
+pub mod captcha_util;
+pub mod data_scope_util;
+pub mod db_util;
+pub mod health_util;
+pub mod ip_util;
+pub mod jwt_key_util;
 pub mod jwt_util;
+pub mod login_guard_util;
+pub mod metrics_util;
+pub mod mfa_util;
+pub mod migration_util;
+pub mod operate_log_util;
+pub mod password_util;
+pub mod permission_util;
+pub mod rate_limit_util;
 pub mod redis_util;
+pub mod session_store_util;
+pub mod session_util;
 pub mod time_util;
 pub mod user_agent_util;
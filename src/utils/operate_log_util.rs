@@ -0,0 +1,348 @@
+use rbatis::rbdc::datetime::DateTime;
+use rbatis::RBatis;
+use rbs::value;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tokio::time::{timeout, timeout_at, Instant};
+
+use crate::model::system::sys_dept_model::Dept;
+use crate::model::system::sys_menu_model::Menu;
+use crate::model::system::sys_operate_log_model::OperateLog;
+use crate::model::system::sys_user_model::User;
+
+//业务类型（0其它 1新增 2修改 3删除）
+pub const BUSINESS_TYPE_OTHER: i8 = 0;
+pub const BUSINESS_TYPE_INSERT: i8 = 1;
+pub const BUSINESS_TYPE_UPDATE: i8 = 2;
+pub const BUSINESS_TYPE_DELETE: i8 = 3;
+
+//sys_operate_log各字段的长度
+const TITLE_SIZE: usize = 50;
+const METHOD_SIZE: usize = 200;
+const REQUEST_METHOD_SIZE: usize = 10;
+const NAME_SIZE: usize = 50;
+const URL_SIZE: usize = 255;
+const IP_SIZE: usize = 128;
+const LOCATION_SIZE: usize = 255;
+const TEXT_SIZE: usize = 2000;
+
+const REDACTED: &str = "******";
+
+/*
+ *操作日志配置
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OperateLogConfig {
+    pub enabled: bool,                 //是否记录操作日志
+    pub batch_size: usize,             //每批写入的最大条数
+    pub flush_interval_ms: u64,        //未满一批时最长等待多久写入(毫秒)
+    pub queue_size: usize,             //待写入队列长度,队列满时丢弃日志
+    pub sensitive_fields: Vec<String>, //需要脱敏的参数名(不区分大小写)
+}
+
+impl Default for OperateLogConfig {
+    fn default() -> Self {
+        OperateLogConfig {
+            enabled: true,
+            batch_size: 100,
+            flush_interval_ms: 1000,
+            queue_size: 10000,
+            sensitive_fields: ["password", "pwd", "rePwd", "oldPassword", "newPassword", "token", "accessToken", "refreshToken", "secret", "captchaCode", "recoveryCodes"]
+                .iter()
+                .map(|x| x.to_string())
+                .collect(),
+        }
+    }
+}
+
+/*
+ *待写入的操作日志,标题和部门名称由后台任务批量查询
+ */
+#[derive(Debug, Clone)]
+pub struct OperateRecord {
+    pub log: OperateLog, //操作日志
+    pub user_id: i64,    //操作人员id
+    pub perm: String,    //权限标识
+}
+
+/*
+ *操作日志写入器: 请求只负责把日志放进队列,由后台任务批量写入数据库
+ */
+#[derive(Debug, Clone)]
+pub struct OperateLogWriter {
+    pub config: OperateLogConfig,             //操作日志配置
+    sender: Sender<OperateRecord>,            //待写入队列
+    stop: Arc<Notify>,                        //停止信号
+    task: Arc<Mutex<Option<JoinHandle<()>>>>, //后台写入任务
+}
+
+impl OperateLogWriter {
+    /// 创建写入器并启动后台写入任务(需要在tokio运行时中调用)
+    pub fn start(config: OperateLogConfig, rb: RBatis) -> Self {
+        let (sender, receiver) = channel(config.queue_size.max(1));
+        let stop = Arc::new(Notify::new());
+        let task = tokio::spawn(run_writer(rb, receiver, config.batch_size.max(1), Duration::from_millis(config.flush_interval_ms.max(1)), stop.clone()));
+        OperateLogWriter {
+            config,
+            sender,
+            stop,
+            task: Arc::new(Mutex::new(Some(task))),
+        }
+    }
+
+    /// 放入队列,队列已满或者后台任务已经退出时丢弃,不阻塞请求
+    pub fn send(&self, record: OperateRecord) {
+        if let Err(e) = self.sender.try_send(record) {
+            log::warn!("discard operate log: {}", e);
+        }
+    }
+
+    /// 队列中等待写入的数量和队列容量
+    pub fn queue_depth(&self) -> (usize, usize) {
+        (self.sender.max_capacity() - self.sender.capacity(), self.sender.max_capacity())
+    }
+
+    /// 停止接收新的日志,等待队列中剩余的日志写完,最多等待wait
+    pub async fn shutdown(&self, wait: Duration) {
+        self.stop.notify_one();
+        let task = self.task.lock().ok().and_then(|mut x| x.take());
+        if let Some(task) = task {
+            if timeout(wait, task).await.is_err() {
+                log::warn!("flush operate log timeout after {:?}", wait);
+            }
+        }
+    }
+}
+
+async fn run_writer(rb: RBatis, mut receiver: Receiver<OperateRecord>, batch_size: usize, interval: Duration, stop: Arc<Notify>) {
+    let mut buffer: Vec<OperateRecord> = Vec::with_capacity(batch_size);
+    let mut deadline = Instant::now() + interval;
+    let mut stopping = false;
+    loop {
+        let received = tokio::select! {
+            _ = stop.notified(), if !stopping => {
+                //关闭队列后recv会先返回剩余的日志,全部取完后返回None
+                receiver.close();
+                stopping = true;
+                continue;
+            }
+            received = timeout_at(deadline, receiver.recv()) => received,
+        };
+        match received {
+            Ok(Some(record)) => {
+                buffer.push(record);
+                if buffer.len() < batch_size {
+                    continue;
+                }
+            }
+            Ok(None) => {
+                flush(&rb, &mut buffer).await;
+                return;
+            }
+            Err(_) => {}
+        }
+        flush(&rb, &mut buffer).await;
+        deadline = Instant::now() + interval;
+    }
+}
+
+/// 补全模块标题(菜单名称)和操作人员的部门名称后批量写入
+async fn flush(rb: &RBatis, buffer: &mut Vec<OperateRecord>) {
+    if buffer.is_empty() {
+        return;
+    }
+    let records: Vec<OperateRecord> = std::mem::take(buffer);
+
+    let mut perms: Vec<&str> = records.iter().map(|x| x.perm.as_str()).filter(|x| !x.is_empty()).collect();
+    perms.sort();
+    perms.dedup();
+    let mut titles: HashMap<String, String> = HashMap::new();
+    if !perms.is_empty() {
+        match Menu::select_by_map(rb, value! {"perms": &perms}).await {
+            Ok(menus) => {
+                for menu in menus {
+                    if let Some(perm) = menu.perms {
+                        titles.entry(perm).or_insert(menu.menu_name);
+                    }
+                }
+            }
+            Err(e) => log::error!("query operate log titles error: {}", e),
+        }
+    }
+
+    let mut user_ids: Vec<i64> = records.iter().map(|x| x.user_id).collect();
+    user_ids.sort();
+    user_ids.dedup();
+    let dept_names = query_dept_names(rb, &user_ids).await.unwrap_or_else(|e| {
+        log::error!("query operate log dept names error: {}", e);
+        HashMap::new()
+    });
+
+    let logs: Vec<OperateLog> = records
+        .into_iter()
+        .map(|x| {
+            let mut log = x.log;
+            let title = titles.get(&x.perm).cloned().unwrap_or(x.perm);
+            log.title = Some(truncate(&title, TITLE_SIZE));
+            log.dept_name = dept_names.get(&x.user_id).map(|name| truncate(name, NAME_SIZE));
+            log
+        })
+        .collect();
+
+    if let Err(e) = OperateLog::insert_batch(rb, &logs, logs.len() as u64).await {
+        log::error!("save operate log error: {}", e);
+    }
+}
+
+async fn query_dept_names(rb: &RBatis, user_ids: &[i64]) -> rbatis::Result<HashMap<i64, String>> {
+    let mut result = HashMap::new();
+    if user_ids.is_empty() {
+        return Ok(result);
+    }
+    let users = User::select_by_map(rb, value! {"id": user_ids}).await?;
+    let mut dept_ids: Vec<i64> = users.iter().map(|x| x.dept_id).collect();
+    dept_ids.sort();
+    dept_ids.dedup();
+    if dept_ids.is_empty() {
+        return Ok(result);
+    }
+    let depts: HashMap<i64, String> = Dept::select_by_map(rb, value! {"id": &dept_ids}).await?.into_iter().filter_map(|x| x.id.map(|id| (id, x.dept_name))).collect();
+    for user in users {
+        if let (Some(id), Some(name)) = (user.id, depts.get(&user.dept_id)) {
+            result.insert(id, name.clone());
+        }
+    }
+    Ok(result)
+}
+
+/*
+ *根据权限标识的动作推断业务类型,没有权限标识时根据请求方式推断
+ */
+pub fn business_type(perm: &str, method: &str) -> i8 {
+    match perm.rsplit(':').next().unwrap_or_default() {
+        "add" | "auth" => BUSINESS_TYPE_INSERT,
+        "edit" | "status" | "resetPwd" | "authRole" | "authMenu" | "authDept" | "unlock" => BUSINESS_TYPE_UPDATE,
+        "remove" | "cancelAuth" | "forceLogout" | "clean" => BUSINESS_TYPE_DELETE,
+        _ => match method {
+            "PUT" | "PATCH" => BUSINESS_TYPE_UPDATE,
+            "DELETE" => BUSINESS_TYPE_DELETE,
+            _ => BUSINESS_TYPE_OTHER,
+        },
+    }
+}
+
+/// 是否需要记录操作日志: 只记录新增、修改、删除类的请求
+pub fn is_mutating(business_type: i8) -> bool {
+    business_type != BUSINESS_TYPE_OTHER
+}
+
+/*
+ *参数脱敏: JSON中名称属于敏感字段的值(任意层级)替换为******
+ *不是JSON时按查询字符串或者表单(key=value&key=value)处理
+ */
+pub fn redact(text: &str, sensitive_fields: &[String]) -> String {
+    match serde_json::from_str::<Value>(text) {
+        Ok(mut value) => {
+            redact_value(&mut value, sensitive_fields);
+            value.to_string()
+        }
+        Err(_) => redact_pairs(text, sensitive_fields),
+    }
+}
+
+fn redact_pairs(text: &str, sensitive_fields: &[String]) -> String {
+    text.split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if sensitive_fields.iter().any(|x| x.eq_ignore_ascii_case(key)) => format!("{}={}", key, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn redact_value(value: &mut Value, sensitive_fields: &[String]) {
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                if sensitive_fields.iter().any(|x| x.eq_ignore_ascii_case(key)) {
+                    *item = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(item, sensitive_fields);
+                }
+            }
+        }
+        Value::Array(list) => list.iter_mut().for_each(|x| redact_value(x, sensitive_fields)),
+        _ => {}
+    }
+}
+
+/// 按字符截断到字段长度(varchar按字符计算长度)
+pub fn truncate(text: &str, max: usize) -> String {
+    text.chars().take(max).collect()
+}
+
+/*
+ *构造操作日志,各字段截断到数据库字段长度
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn build_operate_log(
+    business_type: i8,
+    perm: &str,
+    request_method: &str,
+    operate_name: &str,
+    operate_url: &str,
+    operate_ip: &str,
+    operate_location: &str,
+    operate_param: &str,
+    json_result: &str,
+    error_msg: Option<&str>,
+    cost_time: i64,
+) -> OperateLog {
+    OperateLog {
+        id: None,                                                            //日志主键
+        title: None,                                                         //模块标题
+        business_type: Some(business_type),                                  //业务类型（0其它 1新增 2修改 3删除）
+        method: Some(truncate(perm, METHOD_SIZE)),                           //方法名称
+        request_method: Some(truncate(request_method, REQUEST_METHOD_SIZE)), //请求方式
+        operator_type: Some(1),                                              //操作类别（0其它 1后台用户 2手机端用户）
+        operate_name: Some(truncate(operate_name, NAME_SIZE)),               //操作人员
+        dept_name: None,                                                     //部门名称
+        operate_url: Some(truncate(operate_url, URL_SIZE)),                  //请求URL
+        operate_ip: Some(truncate(operate_ip, IP_SIZE)),                     //主机地址
+        operate_location: Some(truncate(operate_location, LOCATION_SIZE)),   //操作地点
+        operate_param: Some(truncate(operate_param, TEXT_SIZE)),             //请求参数
+        json_result: Some(truncate(json_result, TEXT_SIZE)),                 //返回参数
+        status: Some(if error_msg.is_some() { 0 } else { 1 }),               //操作状态(0:异常,正常)
+        error_msg: error_msg.map(|x| truncate(x, TEXT_SIZE)),                //错误消息
+        operate_time: Some(DateTime::now()),                                 //操作时间
+        cost_time: Some(cost_time),                                          //消耗时间
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::operate_log_util::{business_type, redact, truncate, OperateLogConfig};
+
+    #[test]
+    fn test_redact_and_truncate() {
+        let config = OperateLogConfig::default();
+        let text = r#"{"mobile":"13800000000","password":"123456","list":[{"rePwd":"abc"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(&redact(text, &config.sensitive_fields)).unwrap();
+        assert_eq!(value["mobile"], "13800000000");
+        assert_eq!(value["password"], "******");
+        assert_eq!(value["list"][0]["rePwd"], "******");
+        assert_eq!(redact("id=1", &config.sensitive_fields), "id=1");
+        assert_eq!(redact("mobile=13800000000&Password=123456&token=", &config.sensitive_fields), "mobile=13800000000&Password=******&token=******");
+
+        assert_eq!(truncate("操作日志", 2), "操作");
+        assert_eq!(business_type("system:user:remove", "POST"), 3);
+        assert_eq!(business_type("system:user:list", "POST"), 0);
+    }
+}
@@ -0,0 +1,132 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+use crate::common::error::AppError;
+use crate::common::error::AppError::PasswordHashError;
+
+//用户不存在时用来校验的密文,让响应时间和密码错误时一致
+static DUMMY_HASH: OnceCell<String> = OnceCell::new();
+
+/*
+ *密码加密配置(Argon2id)
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PasswordConfig {
+    pub memory_cost: u32, //内存开销(KiB)
+    pub time_cost: u32,   //迭代次数
+    pub parallelism: u32, //并行度
+}
+
+impl Default for PasswordConfig {
+    fn default() -> Self {
+        PasswordConfig {
+            memory_cost: Params::DEFAULT_M_COST,
+            time_cost: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl PasswordConfig {
+    fn argon2(&self) -> Result<Argon2<'static>, AppError> {
+        let params = Params::new(self.memory_cost, self.time_cost, self.parallelism, None).map_err(|e| PasswordHashError(e.to_string()))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    /// hash password
+    /// 返回PHC格式的字符串,如: $argon2id$v=19$m=19456,t=2,p=1$salt$hash
+    pub fn hash(&self, password: &str) -> Result<String, AppError> {
+        let salt = SaltString::encode_b64(&rand::random::<[u8; 16]>()).map_err(|e| PasswordHashError(e.to_string()))?;
+        match self.argon2()?.hash_password(password.as_bytes(), &salt) {
+            Ok(hash) => Ok(hash.to_string()),
+            Err(e) => Err(PasswordHashError(e.to_string())),
+        }
+    }
+
+    /// verify password
+    /// stored不是PHC格式时按历史明文密码比较,空密码总是校验失败
+    pub fn verify(&self, password: &str, stored: &str) -> bool {
+        if password.is_empty() || stored.is_empty() {
+            return false;
+        }
+        if is_legacy(stored) {
+            return constant_time_eq(password.as_bytes(), stored.as_bytes());
+        }
+        match PasswordHash::new(stored) {
+            Ok(hash) => Argon2::default().verify_password(password.as_bytes(), &hash).is_ok(),
+            Err(e) => {
+                log::error!("parse password hash error: {:?}", e);
+                false
+            }
+        }
+    }
+
+    /// 用户不存在时按当前配置校验一次固定的密文,避免通过响应时间判断账号是否存在
+    pub fn verify_dummy(&self, password: &str) {
+        match DUMMY_HASH.get_or_try_init(|| self.hash("salvo-admin-dummy-password")) {
+            Ok(hash) => {
+                self.verify(password, hash);
+            }
+            Err(e) => log::error!("create dummy password hash error: {:?}", e),
+        }
+    }
+
+    /// 明文密码或者加密参数与当前配置不一致时需要重新加密
+    pub fn needs_rehash(&self, stored: &str) -> bool {
+        if is_legacy(stored) {
+            return true;
+        }
+        match PasswordHash::new(stored) {
+            Ok(hash) => match Params::try_from(&hash) {
+                Ok(params) => {
+                    hash.algorithm != Algorithm::Argon2id.ident()
+                        || params.m_cost() != self.memory_cost
+                        || params.t_cost() != self.time_cost
+                        || params.p_cost() != self.parallelism
+                }
+                Err(_) => true,
+            },
+            Err(_) => true,
+        }
+    }
+}
+
+/// 是否为历史遗留的明文密码
+pub fn is_legacy(stored: &str) -> bool {
+    !stored.starts_with("$argon2")
+}
+
+/// 按固定时间比较,避免通过响应时间猜测密码或者token
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::password_util::{is_legacy, PasswordConfig};
+
+    #[test]
+    fn test_password() {
+        let config = PasswordConfig { memory_cost: 1024, time_cost: 1, parallelism: 1 };
+        let hash = config.hash("123456").unwrap();
+        assert!(!is_legacy(&hash));
+        assert!(config.verify("123456", &hash));
+        assert!(!config.verify("1234567", &hash));
+        assert!(!config.needs_rehash(&hash));
+        assert!(PasswordConfig::default().needs_rehash(&hash));
+
+        assert!(config.verify("123456", "123456"));
+        assert!(config.needs_rehash("123456"));
+
+        //密码字段为空的历史数据不能用空密码登录
+        assert!(!config.verify("", ""));
+        assert!(!config.verify("123456", ""));
+        assert!(!config.verify("", &hash));
+    }
+}
@@ -0,0 +1,329 @@
+use deadpool_redis::redis::cmd;
+use rbatis::RBatis;
+use rbs::value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::common::error::AppResult;
+use crate::model::system::sys_menu_model::{select_menu_by_user_id, Menu};
+use crate::model::system::sys_user_role_model::{is_admin, UserRole};
+use crate::utils::session_store_util::StoreConnection;
+use crate::utils::session_util::{list_sessions, user_info_key};
+
+/*
+ *用户的权限,由auth_token放入Depot,require_perm据此校验
+ */
+#[derive(Debug)]
+pub struct PermissionSet {
+    is_admin: bool,              //是否超级管理员
+    perms: HashSet<String>,      //权限标识
+    api_urls: PermissionMatcher, //接口权限(兼容只配置了api_url的菜单)
+}
+
+impl PermissionSet {
+    /// 超级管理员拥有所有权限,其他用户需要拥有权限标识,或者菜单的api_url匹配当前请求
+    pub fn allows(&self, perm: &str, method: &str, path: &str) -> bool {
+        self.is_admin || self.perms.contains(perm) || self.api_urls.matches(method, path)
+    }
+}
+
+/*
+ *用户的权限数据
+ */
+#[derive(Debug, Default)]
+pub struct UserPermissions {
+    pub api_urls: Vec<String>, //接口URL
+    pub perms: Vec<String>,    //权限标识
+    pub is_admin: bool,        //是否超级管理员
+}
+
+impl UserPermissions {
+    pub fn is_empty(&self) -> bool {
+        self.api_urls.is_empty() && self.perms.is_empty()
+    }
+}
+
+/*
+ *接口权限匹配器
+ */
+//api_url支持以下写法:
+//  /api/system/user/addUser      精确匹配
+//  /api/system/user/{id}         {param}匹配一个路径段
+//  /api/system/dict*             *匹配一个路径段内的任意字符,在末尾时同时匹配后面的所有路径段(可以匹配/api/system/dictType/addDictType)
+//  /api/system/**                **匹配任意多个路径段
+//  GET|POST /api/system/user/*   可以在前面指定HTTP方法,不指定时匹配所有方法
+#[derive(Debug, Default)]
+pub struct PermissionMatcher {
+    exact: HashSet<String>,           //不含通配符并且不限制方法的接口
+    patterns: Vec<PermissionPattern>, //其它需要逐个匹配的接口
+}
+
+#[derive(Debug)]
+struct PermissionPattern {
+    methods: Vec<String>,   //允许的HTTP方法(空:不限制)
+    segments: Vec<Segment>, //路径段
+}
+
+#[derive(Debug)]
+enum Segment {
+    Literal(String),   //普通路径段
+    Glob(Vec<String>), //含*或者{param}的路径段,按*切分后的文本片段
+    Any,               //**
+}
+
+impl PermissionMatcher {
+    pub fn compile<'a>(permissions: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut matcher = PermissionMatcher::default();
+        for permission in permissions.into_iter().map(str::trim).filter(|x| !x.is_empty()) {
+            let (methods, path) = match permission.split_once(char::is_whitespace) {
+                Some((methods, path)) => (methods.split('|').map(|x| x.trim().to_ascii_uppercase()).filter(|x| !x.is_empty()).collect(), path.trim()),
+                None => (Vec::new(), permission),
+            };
+            let mut segments: Vec<Segment> = path.trim_matches('/').split('/').map(Segment::parse).collect();
+            //末尾的*作为前缀匹配,后面可以有任意多个路径段
+            if path.ends_with('*') && !path.ends_with("**") {
+                segments.push(Segment::Any);
+            }
+            if methods.is_empty() && segments.iter().all(|x| matches!(x, Segment::Literal(_))) {
+                matcher.exact.insert(path.to_string());
+            } else {
+                matcher.patterns.push(PermissionPattern { methods, segments });
+            }
+        }
+        matcher
+    }
+
+    pub fn matches(&self, method: &str, path: &str) -> bool {
+        if self.exact.contains(path) {
+            return true;
+        }
+        let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+        self.patterns
+            .iter()
+            .any(|x| (x.methods.is_empty() || x.methods.iter().any(|m| m.eq_ignore_ascii_case(method))) && match_segments(&x.segments, &parts))
+    }
+}
+
+impl Segment {
+    fn parse(segment: &str) -> Segment {
+        if segment == "**" {
+            return Segment::Any;
+        }
+        if !segment.contains('*') && !segment.contains('{') {
+            return Segment::Literal(segment.to_string());
+        }
+        //{param}等同于*
+        let mut glob = String::new();
+        let mut in_param = false;
+        for c in segment.chars() {
+            match c {
+                '{' => in_param = true,
+                '}' if in_param => {
+                    in_param = false;
+                    glob.push('*');
+                }
+                _ if in_param => {}
+                _ => glob.push(c),
+            }
+        }
+        Segment::Glob(glob.split('*').map(str::to_string).collect())
+    }
+
+    fn matches(&self, text: &str) -> bool {
+        match self {
+            Segment::Literal(x) => x == text,
+            Segment::Glob(parts) => glob_match(parts, text),
+            Segment::Any => true,
+        }
+    }
+}
+
+fn glob_match(parts: &[String], text: &str) -> bool {
+    if parts.len() == 1 {
+        return parts[0] == text;
+    }
+    let (first, last) = (&parts[0], &parts[parts.len() - 1]);
+    if text.len() < first.len() + last.len() || !text.starts_with(first.as_str()) || !text.ends_with(last.as_str()) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part.as_str()) {
+            None => return false,
+            Some(index) => rest = &rest[index + part.len()..],
+        }
+    }
+    true
+}
+
+fn match_segments(segments: &[Segment], parts: &[&str]) -> bool {
+    match segments.split_first() {
+        None => parts.is_empty(),
+        Some((Segment::Any, rest)) => (0..=parts.len()).any(|i| match_segments(rest, &parts[i..])),
+        Some((segment, rest)) => match parts.split_first() {
+            None => false,
+            Some((part, parts)) => segment.matches(part) && match_segments(rest, parts),
+        },
+    }
+}
+
+//编译后的权限在缓存中保留的时间,过期后重新编译,不活跃用户的缓存在写入时清理
+const PERMISSION_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+//用户id -> (原始权限, 编译后的权限, 编译时间)
+type PermissionMap = HashMap<i64, (String, Arc<PermissionSet>, Instant)>;
+
+/*
+ *已编译的权限,按用户缓存,权限变化时重新编译
+ */
+#[derive(Debug, Clone, Default)]
+pub struct PermissionCache {
+    cache: Arc<RwLock<PermissionMap>>, //用户id -> (原始权限, 编译后的权限, 编译时间)
+}
+
+impl PermissionCache {
+    /// 获取用户编译后的权限,参数为redis中保存的用户信息(逗号分隔)
+    /// 权限没有变化时直接使用缓存,避免每次请求重新解析
+    pub fn permission_set(&self, user_id: i64, is_admin: bool, api_urls: &str, perms: &str) -> Arc<PermissionSet> {
+        let raw = format!("{}\n{}\n{}", is_admin, api_urls, perms);
+        let now = Instant::now();
+        if let Some((cached, permission_set, compiled)) = self.cache.read().unwrap_or_else(|e| e.into_inner()).get(&user_id) {
+            if *cached == raw && now.duration_since(*compiled) < PERMISSION_CACHE_TTL {
+                return permission_set.clone();
+            }
+        }
+        let permission_set = Arc::new(PermissionSet {
+            is_admin,
+            perms: perms.split(',').map(str::trim).filter(|x| !x.is_empty()).map(str::to_string).collect(),
+            api_urls: PermissionMatcher::compile(api_urls.split(',')),
+        });
+        let mut cache = self.cache.write().unwrap_or_else(|e| e.into_inner());
+        cache.retain(|_, (_, _, compiled)| now.duration_since(*compiled) < PERMISSION_CACHE_TTL);
+        cache.insert(user_id, (raw, permission_set.clone(), now));
+        permission_set
+    }
+
+    /// 删除用户的缓存(退出登录时调用)
+    pub fn evict(&self, user_id: i64) {
+        self.cache.write().unwrap_or_else(|e| e.into_inner()).remove(&user_id);
+    }
+}
+
+/*
+ *查询用户的接口权限和权限标识
+ *只有启用的角色和启用的菜单才授予权限
+ */
+pub async fn query_user_permissions(rb: &RBatis, user_id: i64) -> rbatis::Result<UserPermissions> {
+    let is_super = is_admin(rb, &user_id).await? == 1;
+    let menus = if is_super { Menu::select_enabled_menu(rb).await? } else { select_menu_by_user_id(rb, &user_id).await? };
+
+    let mut permissions = UserPermissions { is_admin: is_super, ..Default::default() };
+    for menu in menus {
+        for (value, list) in [(menu.api_url, &mut permissions.api_urls), (menu.perms, &mut permissions.perms)] {
+            let value = value.unwrap_or_default().trim().to_string();
+            if !value.is_empty() && !list.contains(&value) {
+                list.push(value);
+            }
+        }
+    }
+    Ok(permissions)
+}
+
+/*
+ *重新计算在线用户的权限,下一次请求立即生效(没有登录信息的用户跳过)
+ */
+pub async fn refresh_user_permissions(rb: &RBatis, conn: &mut StoreConnection, user_ids: &[i64]) -> AppResult<()> {
+    for user_id in user_ids.iter().copied().collect::<HashSet<i64>>() {
+        let key = user_info_key(user_id);
+        if !cmd("EXISTS").arg(&key).query_async::<bool>(conn).await? {
+            continue;
+        }
+        let permissions = query_user_permissions(rb, user_id).await?;
+        cmd("HSET")
+            .arg(&key)
+            .arg("permissions")
+            .arg(permissions.api_urls.join(","))
+            .arg("perms")
+            .arg(permissions.perms.join(","))
+            .arg("is_admin")
+            .arg(permissions.is_admin)
+            .query_async::<()>(conn)
+            .await?;
+        log::info!("refresh permissions of user {}, api urls: {}, perms: {}", user_id, permissions.api_urls.len(), permissions.perms.len());
+    }
+    Ok(())
+}
+
+/*
+ *重新计算拥有这些角色的用户的权限
+ */
+pub async fn refresh_role_permissions(rb: &RBatis, conn: &mut StoreConnection, role_ids: &[i64]) -> AppResult<()> {
+    let user_roles = UserRole::select_by_map(rb, value! {"role_id": role_ids}).await?;
+    let user_ids: Vec<i64> = user_roles.iter().map(|x| x.user_id).collect();
+    refresh_user_permissions(rb, conn, &user_ids).await
+}
+
+/*
+ *重新计算所有在线用户的权限(菜单变更影响所有用户)
+ */
+pub async fn refresh_online_permissions(rb: &RBatis, conn: &mut StoreConnection) -> AppResult<()> {
+    let user_ids: Vec<i64> = list_sessions(conn).await?.iter().map(|x| x.user_id).collect();
+    refresh_user_permissions(rb, conn, &user_ids).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::permission_util::{PermissionCache, PermissionMatcher};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_permission_matcher() {
+        let matcher = PermissionMatcher::compile([
+            "/api/system/user/addUser",
+            "GET|DELETE /api/system/post/{id}",
+            "/api/system/dict*",
+            "/api/system/notice/**",
+            "POST /api/system/dept/*/children",
+        ]);
+
+        assert!(matcher.matches("POST", "/api/system/user/addUser"));
+        assert!(!matcher.matches("POST", "/api/system/user/deleteUser"));
+
+        assert!(matcher.matches("GET", "/api/system/post/12"));
+        assert!(matcher.matches("delete", "/api/system/post/12"));
+        assert!(!matcher.matches("POST", "/api/system/post/12"));
+        assert!(!matcher.matches("GET", "/api/system/post/12/detail"));
+
+        assert!(matcher.matches("POST", "/api/system/dictType"));
+        assert!(matcher.matches("POST", "/api/system/dict"));
+        assert!(matcher.matches("POST", "/api/system/dictType/addDictType"));
+        assert!(matcher.matches("POST", "/api/system/dictData/list/all"));
+        assert!(!matcher.matches("POST", "/api/system/notic"));
+
+        assert!(matcher.matches("POST", "/api/system/notice"));
+        assert!(matcher.matches("POST", "/api/system/notice/a/b/c"));
+
+        assert!(matcher.matches("POST", "/api/system/dept/3/children"));
+        assert!(!matcher.matches("GET", "/api/system/dept/3/children"));
+        assert!(!matcher.matches("POST", "/api/system/dept/children"));
+    }
+
+    #[test]
+    fn test_permission_cache() {
+        let cache = PermissionCache::default();
+        let first = cache.permission_set(1, false, "/api/system/user/*", "system:user:list");
+        assert!(first.allows("system:user:list", "GET", "/api/system/post/list"));
+        assert!(first.allows("", "POST", "/api/system/user/list"));
+        //权限没有变化时使用缓存
+        assert!(Arc::ptr_eq(&first, &cache.permission_set(1, false, "/api/system/user/*", "system:user:list")));
+        //权限变化时重新编译
+        let changed = cache.permission_set(1, false, "", "system:user:list");
+        assert!(!changed.allows("", "POST", "/api/system/user/list"));
+
+        cache.permission_set(2, true, "", "");
+        assert_eq!(cache.cache.read().unwrap().len(), 2);
+        cache.evict(1);
+        assert_eq!(cache.cache.read().unwrap().len(), 1);
+    }
+}
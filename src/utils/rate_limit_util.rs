@@ -0,0 +1,299 @@
+use deadpool_redis::redis::cmd;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::utils::permission_util::PermissionMatcher;
+use crate::utils::session_store_util::SessionStore;
+
+//内存存储的key超过该数量时清理已经过期的key
+const MEMORY_CLEANUP_THRESHOLD: usize = 10000;
+
+//令牌桶: ARGV = 容量, 每毫秒生成的令牌数; 返回 {是否通过, 剩余令牌, 需要等待的毫秒数}
+const TOKEN_BUCKET_SCRIPT: &str = r"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local capacity = tonumber(ARGV[1])
+local rate = tonumber(ARGV[2])
+local data = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(data[1]) or capacity
+local ts = tonumber(data[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - ts) * rate)
+local allowed, wait = 0, 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+else
+    wait = math.ceil((1 - tokens) / rate)
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil(capacity / rate) + 1000)
+return {allowed, math.floor(tokens), wait}
+";
+
+//滑动窗口: ARGV = 窗口(毫秒), 次数, 本次请求的唯一标识; 返回 {是否通过, 剩余次数, 需要等待的毫秒数}
+const SLIDING_WINDOW_SCRIPT: &str = r"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local window = tonumber(ARGV[1])
+local limit = tonumber(ARGV[2])
+redis.call('ZREMRANGEBYSCORE', KEYS[1], 0, now - window)
+local count = redis.call('ZCARD', KEYS[1])
+if count < limit then
+    redis.call('ZADD', KEYS[1], now, now .. '-' .. ARGV[3])
+    redis.call('PEXPIRE', KEYS[1], window)
+    return {1, limit - count - 1, 0}
+end
+local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+return {0, 0, math.max(1, tonumber(oldest[2]) + window - now)}
+";
+
+/*
+ *限流配置
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    pub enabled: bool,             //是否启用限流
+    pub backend: RateLimitBackend, //计数存储(redis:多实例共享,memory:单实例或者测试)
+    pub rules: Vec<RateLimitRule>, //限流规则,一个请求匹配多条规则时每条规则都要通过
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            enabled: true,
+            backend: RateLimitBackend::Redis,
+            rules: vec![
+                RateLimitRule {
+                    name: "login".to_string(),
+                    paths: vec!["POST /api/system/user/login".to_string(), "POST /api/system/user/loginMfa".to_string()],
+                    key: RateLimitKey::Ip,
+                    algorithm: RateLimitAlgorithm::SlidingWindow,
+                    limit: 10,
+                    window: 60,
+                },
+                RateLimitRule {
+                    name: "api".to_string(),
+                    paths: vec!["/api/**".to_string()],
+                    key: RateLimitKey::User,
+                    algorithm: RateLimitAlgorithm::TokenBucket,
+                    limit: 300,
+                    window: 60,
+                },
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitBackend {
+    Redis,  //redis,多个实例共享计数
+    Memory, //进程内存,只适用于单实例或者测试
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitKey {
+    Ip,    //按客户端IP计数
+    User,  //按登录用户计数(只对需要登录的接口生效)
+    Route, //按接口计数(所有调用方共享)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitAlgorithm {
+    TokenBucket,   //令牌桶: 容量为limit,每window秒匀速补充limit个令牌,允许突发
+    SlidingWindow, //滑动窗口: 任意window秒内最多limit次
+}
+
+/*
+ *限流规则
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitRule {
+    pub name: String,                  //规则名称,用于区分计数
+    pub paths: Vec<String>,            //生效的接口,写法同菜单的api_url(支持方法前缀、*、{param}、**)
+    pub key: RateLimitKey,             //计数维度
+    pub algorithm: RateLimitAlgorithm, //限流算法
+    pub limit: u64,                    //窗口内允许的请求数(令牌桶的容量)
+    pub window: u64,                   //窗口(秒)
+}
+
+/*
+ *限流结果
+ */
+#[derive(Debug, Clone)]
+pub struct RateLimitDecision {
+    pub allowed: bool,    //是否通过
+    pub limit: u64,       //规则允许的请求数
+    pub remaining: u64,   //剩余的请求数
+    pub retry_after: u64, //被限流时多少秒之后可以重试
+}
+
+struct CompiledRule {
+    rule: RateLimitRule,        //限流规则
+    matcher: PermissionMatcher, //接口匹配
+}
+
+enum MemoryState {
+    TokenBucket { tokens: f64, updated: Instant }, //剩余令牌和上次更新时间
+    SlidingWindow(VecDeque<Instant>),              //窗口内每次请求的时间
+}
+
+#[derive(Clone)]
+enum Store {
+    Redis(deadpool_redis::Pool),
+    Memory(Arc<Mutex<HashMap<String, (MemoryState, Instant)>>>),
+}
+
+/*
+ *限流器
+ */
+#[derive(Clone)]
+pub struct RateLimiter {
+    enabled: bool,                 //是否启用限流
+    rules: Arc<Vec<CompiledRule>>, //限流规则
+    store: Store,                  //计数存储
+}
+
+impl RateLimiter {
+    /// 配置为redis计数但会话存储不是redis时,退回到内存计数
+    pub fn new(config: &RateLimitConfig, store: &SessionStore) -> Self {
+        let rules = config
+            .rules
+            .iter()
+            .filter(|x| x.limit > 0 && x.window > 0)
+            .map(|x| CompiledRule {
+                rule: x.clone(),
+                matcher: PermissionMatcher::compile(x.paths.iter().map(String::as_str)),
+            })
+            .collect();
+        let store = match (&config.backend, store.redis_pool()) {
+            (RateLimitBackend::Redis, Some(pool)) => Store::Redis(pool.clone()),
+            _ => Store::Memory(Arc::new(Mutex::new(HashMap::new()))),
+        };
+        RateLimiter { enabled: config.enabled, rules: Arc::new(rules), store }
+    }
+
+    /// 匹配当前请求并且计数维度为keys之一的规则
+    pub fn matched_rules(&self, keys: &[RateLimitKey], method: &str, path: &str) -> Vec<&RateLimitRule> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        self.rules.iter().filter(|x| keys.contains(&x.rule.key) && x.matcher.matches(method, path)).map(|x| &x.rule).collect()
+    }
+
+    /*
+     *按规则计数一次,identity为IP、用户id或者接口路径
+     *存储异常时放行,只记录日志
+     */
+    pub async fn check(&self, rule: &RateLimitRule, identity: &str) -> RateLimitDecision {
+        let key = format!("salvo:admin:rate_limit:{}:{}", rule.name, identity);
+        let window_ms = rule.window * 1000;
+        let result = match &self.store {
+            Store::Redis(pool) => check_redis(pool, rule, &key, window_ms).await,
+            Store::Memory(store) => Ok(check_memory(store, rule, &key, Duration::from_millis(window_ms), Instant::now())),
+        };
+        match result {
+            Ok((allowed, remaining, wait_ms)) => RateLimitDecision {
+                allowed,
+                limit: rule.limit,
+                remaining,
+                retry_after: wait_ms.div_ceil(1000).max(if allowed { 0 } else { 1 }),
+            },
+            Err(e) => {
+                log::error!("rate limit {} error: {}", rule.name, e);
+                RateLimitDecision { allowed: true, limit: rule.limit, remaining: rule.limit, retry_after: 0 }
+            }
+        }
+    }
+}
+
+async fn check_redis(pool: &deadpool_redis::Pool, rule: &RateLimitRule, key: &str, window_ms: u64) -> Result<(bool, u64, u64), String> {
+    let mut conn = pool.get().await.map_err(|e| e.to_string())?;
+    let mut command = cmd("EVAL");
+    match rule.algorithm {
+        RateLimitAlgorithm::TokenBucket => command.arg(TOKEN_BUCKET_SCRIPT).arg(1).arg(key).arg(rule.limit).arg(rule.limit as f64 / window_ms as f64),
+        RateLimitAlgorithm::SlidingWindow => command.arg(SLIDING_WINDOW_SCRIPT).arg(1).arg(key).arg(window_ms).arg(rule.limit).arg(format!("{:016x}", rand::random::<u64>())),
+    };
+    let (allowed, remaining, wait_ms): (i64, i64, i64) = command.query_async(&mut conn).await.map_err(|e| e.to_string())?;
+    Ok((allowed == 1, remaining.max(0) as u64, wait_ms.max(0) as u64))
+}
+
+/// 内存计数,算法和redis脚本一致
+fn check_memory(store: &Mutex<HashMap<String, (MemoryState, Instant)>>, rule: &RateLimitRule, key: &str, window: Duration, now: Instant) -> (bool, u64, u64) {
+    let mut store = store.lock().unwrap_or_else(|e| e.into_inner());
+    if store.len() > MEMORY_CLEANUP_THRESHOLD {
+        store.retain(|_, (_, expire)| *expire > now);
+    }
+
+    let capacity = rule.limit as f64;
+    let (state, expire) = store.entry(key.to_string()).or_insert_with(|| match rule.algorithm {
+        RateLimitAlgorithm::TokenBucket => (MemoryState::TokenBucket { tokens: capacity, updated: now }, now + window),
+        RateLimitAlgorithm::SlidingWindow => (MemoryState::SlidingWindow(VecDeque::new()), now + window),
+    });
+    *expire = now + window;
+
+    match state {
+        MemoryState::TokenBucket { tokens, updated } => {
+            let rate = capacity / window.as_millis() as f64;
+            *tokens = (*tokens + now.saturating_duration_since(*updated).as_millis() as f64 * rate).min(capacity);
+            *updated = now;
+            if *tokens >= 1.0 {
+                *tokens -= 1.0;
+                (true, *tokens as u64, 0)
+            } else {
+                (false, 0, ((1.0 - *tokens) / rate).ceil() as u64)
+            }
+        }
+        MemoryState::SlidingWindow(requests) => {
+            while requests.front().is_some_and(|x| now.saturating_duration_since(*x) >= window) {
+                requests.pop_front();
+            }
+            if (requests.len() as u64) < rule.limit {
+                requests.push_back(now);
+                (true, rule.limit - requests.len() as u64, 0)
+            } else {
+                let oldest = requests.front().copied().unwrap_or(now);
+                (false, 0, (window.saturating_sub(now.saturating_duration_since(oldest))).as_millis() as u64)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::rate_limit_util::{check_memory, RateLimitAlgorithm, RateLimitKey, RateLimitRule};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_memory_rate_limit() {
+        let store = Mutex::new(HashMap::new());
+        let window = Duration::from_secs(10);
+        let mut rule = RateLimitRule {
+            name: "test".to_string(),
+            paths: vec![],
+            key: RateLimitKey::Ip,
+            algorithm: RateLimitAlgorithm::SlidingWindow,
+            limit: 2,
+            window: 10,
+        };
+
+        let now = Instant::now();
+        assert_eq!(check_memory(&store, &rule, "a", window, now), (true, 1, 0));
+        assert_eq!(check_memory(&store, &rule, "a", window, now + Duration::from_secs(4)), (true, 0, 0));
+        assert_eq!(check_memory(&store, &rule, "a", window, now + Duration::from_secs(5)), (false, 0, 5000));
+        assert_eq!(check_memory(&store, &rule, "a", window, now + Duration::from_secs(10)), (true, 0, 0));
+
+        rule.algorithm = RateLimitAlgorithm::TokenBucket;
+        assert_eq!(check_memory(&store, &rule, "b", window, now), (true, 1, 0));
+        assert_eq!(check_memory(&store, &rule, "b", window, now), (true, 0, 0));
+        assert_eq!(check_memory(&store, &rule, "b", window, now), (false, 0, 5000));
+        assert_eq!(check_memory(&store, &rule, "b", window, now + Duration::from_secs(5)), (true, 0, 0));
+    }
+}
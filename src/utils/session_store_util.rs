@@ -0,0 +1,292 @@
+use deadpool_redis::redis::aio::ConnectionLike;
+use deadpool_redis::redis::{Arg, Cmd, ErrorKind, Pipeline, RedisError, RedisFuture, RedisResult, Value};
+use deadpool_redis::{Pool, PoolError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/*
+ *会话、验证码、登录失败次数等短期数据的存储
+ *生产环境使用redis,测试和单机调试可以使用内存存储,内存存储只支持项目中用到的命令
+ */
+#[derive(Clone)]
+pub enum SessionStore {
+    Redis(Pool),         //redis连接池
+    Memory(MemoryStore), //进程内存储
+}
+
+/*
+ *从SessionStore获取的连接,和redis连接一样通过cmd(...).query_async(&mut conn)执行命令
+ */
+pub enum StoreConnection {
+    Redis(deadpool_redis::Connection),
+    Memory(MemoryStore),
+}
+
+impl SessionStore {
+    pub fn memory() -> Self {
+        SessionStore::Memory(MemoryStore::default())
+    }
+
+    pub async fn get(&self) -> Result<StoreConnection, PoolError> {
+        match self {
+            SessionStore::Redis(pool) => pool.get().await.map(StoreConnection::Redis),
+            SessionStore::Memory(store) => Ok(StoreConnection::Memory(store.clone())),
+        }
+    }
+
+    /// redis连接池,内存存储时为None
+    pub fn redis_pool(&self) -> Option<&Pool> {
+        match self {
+            SessionStore::Redis(pool) => Some(pool),
+            SessionStore::Memory(_) => None,
+        }
+    }
+}
+
+impl ConnectionLike for StoreConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            StoreConnection::Redis(conn) => conn.req_packed_command(cmd),
+            StoreConnection::Memory(store) => {
+                let result = store.execute(cmd);
+                Box::pin(async move { result })
+            }
+        }
+    }
+
+    fn req_packed_commands<'a>(&'a mut self, cmd: &'a Pipeline, offset: usize, count: usize) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            StoreConnection::Redis(conn) => conn.req_packed_commands(cmd, offset, count),
+            StoreConnection::Memory(_) => Box::pin(async { Err(RedisError::from((ErrorKind::ClientError, "memory store does not support pipeline"))) }),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            StoreConnection::Redis(conn) => conn.get_db(),
+            StoreConnection::Memory(_) => 0,
+        }
+    }
+}
+
+enum Entry {
+    String(String),                //字符串
+    Hash(HashMap<String, String>), //哈希
+    ZSet(Vec<(f64, String)>),      //有序集合,按分数升序
+}
+
+type EntryMap = HashMap<String, (Entry, Option<Instant>)>; //key -> (值, 过期时间)
+
+/*
+ *内存存储,模拟redis的字符串、哈希和有序集合命令以及过期时间
+ */
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    data: Arc<Mutex<EntryMap>>,
+}
+
+fn wrong_type() -> RedisError {
+    RedisError::from((ErrorKind::TypeError, "WRONGTYPE Operation against a key holding the wrong kind of value"))
+}
+
+fn syntax_error(command: &str) -> RedisError {
+    RedisError::from((ErrorKind::ResponseError, "syntax error", command.to_string()))
+}
+
+fn bulk(value: &str) -> Value {
+    Value::BulkString(value.as_bytes().to_vec())
+}
+
+fn parse<T: std::str::FromStr>(command: &str, value: Option<&String>) -> RedisResult<T> {
+    value.and_then(|x| x.parse().ok()).ok_or_else(|| syntax_error(command))
+}
+
+/// redis的区间下标,负数表示从末尾开始
+fn range(len: usize, start: i64, stop: i64) -> std::ops::Range<usize> {
+    let index = |x: i64| if x < 0 { (len as i64 + x).max(0) } else { x.min(len as i64) } as usize;
+    let (start, stop) = (index(start), (index(stop) + 1).min(len));
+    if start < stop {
+        start..stop
+    } else {
+        0..0
+    }
+}
+
+impl MemoryStore {
+    /// key存在并且是哈希时写入字段,和redis脚本中的EXISTS+HSET一样在一次加锁内完成
+    pub fn hset_if_exists(&self, key: &str, field: &str, value: &str) {
+        let mut data = self.data.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((Entry::Hash(hash), expire)) = data.get_mut(key) {
+            if expire.is_none_or(|x| x > Instant::now()) {
+                hash.insert(field.to_string(), value.to_string());
+            }
+        }
+    }
+
+    fn execute(&self, cmd: &Cmd) -> RedisResult<Value> {
+        let mut args = cmd.args_iter().filter_map(|x| match x {
+            Arg::Simple(x) => Some(String::from_utf8_lossy(x).to_string()),
+            Arg::Cursor => None,
+        });
+        let name = args.next().unwrap_or_default().to_uppercase();
+        let args = args.collect::<Vec<String>>();
+        let mut data = self.data.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        data.retain(|_, (_, expire)| expire.is_none_or(|x| x > now));
+        let key = args.first().cloned().unwrap_or_default();
+
+        match (name.as_str(), args.len()) {
+            ("PING", _) => Ok(Value::SimpleString("PONG".to_string())),
+            ("GET", 1) | ("GETDEL", 1) => {
+                let value = match data.get(&key) {
+                    None => Value::Nil,
+                    Some((Entry::String(x), _)) => bulk(x),
+                    Some(_) => return Err(wrong_type()),
+                };
+                if name == "GETDEL" {
+                    data.remove(&key);
+                }
+                Ok(value)
+            }
+            ("SET", 2) | ("SET", 4) => {
+                let expire = match args.get(2).map(|x| x.to_uppercase()) {
+                    None => None,
+                    Some(x) if x == "EX" => Some(now + Duration::from_secs(parse(&name, args.get(3))?)),
+                    Some(_) => return Err(syntax_error(&name)),
+                };
+                data.insert(key, (Entry::String(args[1].clone()), expire));
+                Ok(Value::Okay)
+            }
+            ("INCR", 1) => {
+                let (entry, _) = data.entry(key).or_insert_with(|| (Entry::String("0".to_string()), None));
+                match entry {
+                    Entry::String(x) => {
+                        let value = parse::<i64>(&name, Some(x))? + 1;
+                        *x = value.to_string();
+                        Ok(Value::Int(value))
+                    }
+                    _ => Err(wrong_type()),
+                }
+            }
+            ("DEL", n) if n > 0 => Ok(Value::Int(args.iter().filter(|x| data.remove(*x).is_some()).count() as i64)),
+            ("EXISTS", n) if n > 0 => Ok(Value::Int(args.iter().filter(|x| data.contains_key(*x)).count() as i64)),
+            ("EXPIRE", 2) => {
+                let seconds = parse::<u64>(&name, args.get(1))?;
+                match data.get_mut(&key) {
+                    Some((_, expire)) => {
+                        *expire = Some(now + Duration::from_secs(seconds));
+                        Ok(Value::Int(1))
+                    }
+                    None => Ok(Value::Int(0)),
+                }
+            }
+            ("TTL", 1) => Ok(Value::Int(match data.get(&key) {
+                None => -2,
+                Some((_, None)) => -1,
+                Some((_, Some(expire))) => expire.duration_since(now).as_secs_f64().ceil() as i64,
+            })),
+            ("HSET", n) if n >= 3 && n % 2 == 1 => {
+                let (entry, _) = data.entry(key).or_insert_with(|| (Entry::Hash(HashMap::new()), None));
+                let Entry::Hash(hash) = entry else { return Err(wrong_type()) };
+                let added = args[1..].chunks(2).filter(|x| hash.insert(x[0].clone(), x[1].clone()).is_none()).count();
+                Ok(Value::Int(added as i64))
+            }
+            ("HGET", 2) => match data.get(&key) {
+                None => Ok(Value::Nil),
+                Some((Entry::Hash(hash), _)) => Ok(hash.get(&args[1]).map(|x| bulk(x)).unwrap_or(Value::Nil)),
+                Some(_) => Err(wrong_type()),
+            },
+            ("HGETALL", 1) => match data.get(&key) {
+                None => Ok(Value::Array(Vec::new())),
+                Some((Entry::Hash(hash), _)) => Ok(Value::Array(hash.iter().flat_map(|(k, v)| [bulk(k), bulk(v)]).collect())),
+                Some(_) => Err(wrong_type()),
+            },
+            ("HINCRBY", 3) => {
+                let increment = parse::<i64>(&name, args.get(2))?;
+                let (entry, _) = data.entry(key).or_insert_with(|| (Entry::Hash(HashMap::new()), None));
+                let Entry::Hash(hash) = entry else { return Err(wrong_type()) };
+                let value = hash.get(&args[1]).map(|x| parse::<i64>(&name, Some(x))).transpose()?.unwrap_or_default() + increment;
+                hash.insert(args[1].clone(), value.to_string());
+                Ok(Value::Int(value))
+            }
+            ("ZADD", n) if n >= 3 && n % 2 == 1 => {
+                let (entry, _) = data.entry(key).or_insert_with(|| (Entry::ZSet(Vec::new()), None));
+                let Entry::ZSet(set) = entry else { return Err(wrong_type()) };
+                let mut added = 0;
+                for pair in args[1..].chunks(2) {
+                    let score = parse::<f64>(&name, Some(&pair[0]))?;
+                    match set.iter_mut().find(|(_, member)| *member == pair[1]) {
+                        Some(item) => item.0 = score,
+                        None => {
+                            set.push((score, pair[1].clone()));
+                            added += 1;
+                        }
+                    }
+                }
+                set.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+                Ok(Value::Int(added))
+            }
+            ("ZRANGE", 3) | ("ZREVRANGE", 3) => {
+                let (start, stop) = (parse::<i64>(&name, args.get(1))?, parse::<i64>(&name, args.get(2))?);
+                let mut members = match data.get(&key) {
+                    None => Vec::new(),
+                    Some((Entry::ZSet(set), _)) => set.iter().map(|(_, member)| member.clone()).collect(),
+                    Some(_) => return Err(wrong_type()),
+                };
+                if name == "ZREVRANGE" {
+                    members.reverse();
+                }
+                Ok(Value::Array(members[range(members.len(), start, stop)].iter().map(|x| bulk(x)).collect()))
+            }
+            ("ZREM", n) if n >= 2 => match data.get_mut(&key) {
+                None => Ok(Value::Int(0)),
+                Some((Entry::ZSet(set), _)) => {
+                    let len = set.len();
+                    set.retain(|(_, member)| !args[1..].contains(member));
+                    let removed = (len - set.len()) as i64;
+                    if set.is_empty() {
+                        data.remove(&key);
+                    }
+                    Ok(Value::Int(removed))
+                }
+                Some(_) => Err(wrong_type()),
+            },
+            _ => Err(RedisError::from((ErrorKind::ClientError, "memory store does not support command", name))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::session_store_util::SessionStore;
+    use deadpool_redis::redis::cmd;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_memory_store() {
+        let store = SessionStore::memory();
+        let mut conn = store.get().await.unwrap();
+
+        cmd("SET").arg("captcha").arg("1234").arg("EX").arg(60).query_async::<()>(&mut conn).await.unwrap();
+        assert_eq!(cmd("TTL").arg("captcha").query_async::<i64>(&mut conn).await.unwrap(), 60);
+        assert_eq!(cmd("GETDEL").arg("captcha").query_async::<Option<String>>(&mut conn).await.unwrap(), Some("1234".to_string()));
+        assert!(!cmd("EXISTS").arg("captcha").query_async::<bool>(&mut conn).await.unwrap());
+
+        cmd("HSET").arg("session").arg("user_id").arg(1).arg("used").arg(0).query_async::<()>(&mut conn).await.unwrap();
+        assert_eq!(cmd("HINCRBY").arg("session").arg("used").arg(1).query_async::<i64>(&mut conn).await.unwrap(), 1);
+        let values: HashMap<String, String> = cmd("HGETALL").arg("session").query_async(&mut conn).await.unwrap();
+        assert_eq!(values.get("user_id").map(String::as_str), Some("1"));
+        assert!(cmd("INCR").arg("session").query_async::<i64>(&mut conn).await.is_err());
+
+        for (score, member) in [(3, "c"), (1, "a"), (2, "b")] {
+            cmd("ZADD").arg("online").arg(score).arg(member).query_async::<()>(&mut conn).await.unwrap();
+        }
+        cmd("ZREM").arg("online").arg("b").query_async::<()>(&mut conn).await.unwrap();
+        assert_eq!(cmd("ZRANGE").arg("online").arg(0).arg(-1).query_async::<Vec<String>>(&mut conn).await.unwrap(), vec!["a", "c"]);
+        assert_eq!(cmd("ZREVRANGE").arg("online").arg(0).arg(0).query_async::<Vec<String>>(&mut conn).await.unwrap(), vec!["c"]);
+
+        cmd("EXPIRE").arg("online").arg(0).query_async::<()>(&mut conn).await.unwrap();
+        assert_eq!(cmd("TTL").arg("online").query_async::<i64>(&mut conn).await.unwrap(), -2);
+    }
+}
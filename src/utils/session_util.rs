@@ -0,0 +1,296 @@
+use crate::utils::session_store_util::StoreConnection;
+use deadpool_redis::redis::{cmd, RedisError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+//所有在线会话(zset, member: 会话id, score: 登录时间戳)
+pub const ONLINE_SESSIONS_KEY: &str = "salvo:admin:online:sessions";
+
+//会话存在时更新最后访问时间,检查和写入在redis中原子执行
+const TOUCH_SESSION_SCRIPT: &str = "if redis.call('EXISTS', KEYS[1]) == 1 then redis.call('HSET', KEYS[1], 'last_activity', ARGV[1]) end return 0";
+
+/*
+ *会话配置
+ */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    pub max_sessions: u32, //每个用户允许同时在线的会话数(0:不限制, 1:单会话, N:最多N个,超出时踢掉最早登录的会话)
+}
+
+/*
+ *登录会话
+ */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Session {
+    pub session_id: String,     //会话id(jwt中的jti)
+    pub user_id: i64,           //用户id
+    pub user_name: String,      //用户账号
+    pub ipaddr: String,         //登录IP地址
+    pub login_location: String, //登录地点
+    pub platform: String,       //平台信息
+    pub browser: String,        //浏览器类型
+    pub os: String,             //操作系统
+    pub login_time: i64,        //登录时间(时间戳,秒)
+    pub last_activity: i64,     //最后访问时间(时间戳,秒)
+}
+
+impl Session {
+    fn from_map(session_id: &str, values: HashMap<String, String>) -> Option<Session> {
+        let user_id = values.get("user_id")?.parse::<i64>().ok()?;
+        let get = |k: &str| values.get(k).cloned().unwrap_or_default();
+        Some(Session {
+            session_id: session_id.to_string(),
+            user_id,
+            user_name: get("user_name"),
+            ipaddr: get("ipaddr"),
+            login_location: get("login_location"),
+            platform: get("platform"),
+            browser: get("browser"),
+            os: get("os"),
+            login_time: get("login_time").parse().unwrap_or_default(),
+            last_activity: get("last_activity").parse().unwrap_or_default(),
+        })
+    }
+}
+
+pub fn user_info_key(user_id: i64) -> String {
+    format!("salvo:admin:user:info:{:?}", user_id)
+}
+
+pub fn session_key(session_id: &str) -> String {
+    format!("salvo:admin:session:{}", session_id)
+}
+
+pub fn user_sessions_key(user_id: i64) -> String {
+    format!("salvo:admin:user:sessions:{:?}", user_id)
+}
+
+pub fn refresh_token_key(refresh_token: &str) -> String {
+    format!("salvo:admin:refresh:token:{}", refresh_token)
+}
+
+pub fn new_session_id() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+fn new_refresh_token() -> String {
+    format!("{:032x}{:032x}", rand::random::<u128>(), rand::random::<u128>())
+}
+
+/*
+ *刷新token的结果
+ */
+#[derive(Debug)]
+pub enum RefreshResult {
+    Invalid,        //刷新token不存在、已过期或者会话已失效
+    Reused(String), //刷新token被重复使用,对应的会话已被注销
+    Rotated { session_id: String, user_id: i64, refresh_token: String },
+}
+
+pub fn now_timestamp() -> i64 {
+    chrono::Local::now().timestamp()
+}
+
+/*
+ *保存会话,并按照配置踢掉超出数量的旧会话,返回被踢掉的会话id
+ */
+pub async fn create_session(conn: &mut StoreConnection, config: &SessionConfig, session: &Session, ttl: u64) -> Result<Vec<String>, RedisError> {
+    let key = session_key(&session.session_id);
+    cmd("HSET")
+        .arg(&key)
+        .arg("user_id")
+        .arg(session.user_id)
+        .arg("user_name")
+        .arg(&session.user_name)
+        .arg("ipaddr")
+        .arg(&session.ipaddr)
+        .arg("login_location")
+        .arg(&session.login_location)
+        .arg("platform")
+        .arg(&session.platform)
+        .arg("browser")
+        .arg(&session.browser)
+        .arg("os")
+        .arg(&session.os)
+        .arg("login_time")
+        .arg(session.login_time)
+        .arg("last_activity")
+        .arg(session.last_activity)
+        .query_async::<()>(conn)
+        .await?;
+    cmd("EXPIRE").arg(&key).arg(ttl).query_async::<()>(conn).await?;
+
+    let user_key = user_sessions_key(session.user_id);
+    cmd("ZADD").arg(&user_key).arg(session.login_time).arg(&session.session_id).query_async::<()>(conn).await?;
+    cmd("EXPIRE").arg(&user_key).arg(ttl).query_async::<()>(conn).await?;
+    cmd("ZADD").arg(ONLINE_SESSIONS_KEY).arg(session.login_time).arg(&session.session_id).query_async::<()>(conn).await?;
+
+    //按登录时间升序,已过期的会话顺便清理掉
+    let mut alive: Vec<String> = Vec::new();
+    for session_id in cmd("ZRANGE").arg(&user_key).arg(0).arg(-1).query_async::<Vec<String>>(conn).await? {
+        if cmd("EXISTS").arg(session_key(&session_id)).query_async::<bool>(conn).await? {
+            alive.push(session_id);
+        } else {
+            cmd("ZREM").arg(&user_key).arg(&session_id).query_async::<()>(conn).await?;
+            cmd("ZREM").arg(ONLINE_SESSIONS_KEY).arg(&session_id).query_async::<()>(conn).await?;
+        }
+    }
+
+    let max = config.max_sessions as usize;
+    let mut kicked: Vec<String> = Vec::new();
+    if max > 0 && alive.len() > max {
+        let over = alive.len() - max;
+        kicked = alive.into_iter().filter(|x| x != &session.session_id).take(over).collect();
+    }
+    for session_id in &kicked {
+        remove_session(conn, session_id).await?;
+    }
+
+    Ok(kicked)
+}
+
+/*
+ *签发新的刷新token,并顺延会话的有效期
+ */
+pub async fn issue_refresh_token(conn: &mut StoreConnection, session_id: &str, user_id: i64, ttl: u64) -> Result<String, RedisError> {
+    let refresh_token = new_refresh_token();
+    let key = refresh_token_key(&refresh_token);
+    cmd("HSET")
+        .arg(&key)
+        .arg("session_id")
+        .arg(session_id)
+        .arg("user_id")
+        .arg(user_id)
+        .arg("used")
+        .arg(0)
+        .query_async::<()>(conn)
+        .await?;
+    cmd("EXPIRE").arg(&key).arg(ttl).query_async::<()>(conn).await?;
+
+    let key = session_key(session_id);
+    cmd("HSET").arg(&key).arg("refresh_token").arg(&refresh_token).query_async::<()>(conn).await?;
+    cmd("EXPIRE").arg(&key).arg(ttl).query_async::<()>(conn).await?;
+    cmd("EXPIRE").arg(user_sessions_key(user_id)).arg(ttl).query_async::<()>(conn).await?;
+
+    Ok(refresh_token)
+}
+
+/*
+ *轮换刷新token: 旧token作废(保留到过期用于重用检测),签发新token
+ *旧token被再次使用说明可能已泄露,直接注销整个会话
+ */
+pub async fn rotate_refresh_token(conn: &mut StoreConnection, refresh_token: &str, ttl: u64) -> Result<RefreshResult, RedisError> {
+    let key = refresh_token_key(refresh_token);
+    let values: HashMap<String, String> = cmd("HGETALL").arg(&key).query_async(conn).await?;
+    let session_id = values.get("session_id").cloned().unwrap_or_default();
+    let user_id = values.get("user_id").and_then(|x| x.parse::<i64>().ok());
+    let user_id = match user_id {
+        Some(x) if !session_id.is_empty() => x,
+        _ => return Ok(RefreshResult::Invalid),
+    };
+
+    //HINCRBY是原子操作,并发刷新时只有一个请求能成功
+    let used: i64 = cmd("HINCRBY").arg(&key).arg("used").arg(1).query_async(conn).await?;
+    if used > 1 {
+        remove_session(conn, &session_id).await?;
+        return Ok(RefreshResult::Reused(session_id));
+    }
+
+    if !cmd("EXISTS").arg(session_key(&session_id)).query_async::<bool>(conn).await? {
+        return Ok(RefreshResult::Invalid);
+    }
+
+    let refresh_token = issue_refresh_token(conn, &session_id, user_id, ttl).await?;
+    Ok(RefreshResult::Rotated { session_id, user_id, refresh_token })
+}
+
+/*
+ *查询会话
+ */
+pub async fn get_session(conn: &mut StoreConnection, session_id: &str) -> Result<Option<Session>, RedisError> {
+    let values: HashMap<String, String> = cmd("HGETALL").arg(session_key(session_id)).query_async(conn).await?;
+    Ok(Session::from_map(session_id, values))
+}
+
+/*
+ *更新会话的最后访问时间
+ *会话在get_session之后可能刚好过期,直接HSET会重新创建一个没有过期时间的key,所以在脚本中确认会话还在再写入
+ *HSET不会改变已有key的过期时间
+ */
+pub async fn touch_session(conn: &mut StoreConnection, session_id: &str) -> Result<(), RedisError> {
+    let key = session_key(session_id);
+    match conn {
+        StoreConnection::Memory(store) => {
+            store.hset_if_exists(&key, "last_activity", &now_timestamp().to_string());
+            Ok(())
+        }
+        _ => cmd("EVAL").arg(TOUCH_SESSION_SCRIPT).arg(1).arg(&key).arg(now_timestamp()).query_async(conn).await,
+    }
+}
+
+/*
+ *删除会话,对应的token立即失效
+ */
+pub async fn remove_session(conn: &mut StoreConnection, session_id: &str) -> Result<(), RedisError> {
+    let key = session_key(session_id);
+    let user_id: Option<i64> = cmd("HGET").arg(&key).arg("user_id").query_async(conn).await?;
+    let refresh_token: Option<String> = cmd("HGET").arg(&key).arg("refresh_token").query_async(conn).await?;
+    cmd("DEL").arg(&key).query_async::<()>(conn).await?;
+    if let Some(refresh_token) = refresh_token {
+        cmd("DEL").arg(refresh_token_key(&refresh_token)).query_async::<()>(conn).await?;
+    }
+    if let Some(user_id) = user_id {
+        cmd("ZREM").arg(user_sessions_key(user_id)).arg(session_id).query_async::<()>(conn).await?;
+    }
+    cmd("ZREM").arg(ONLINE_SESSIONS_KEY).arg(session_id).query_async::<()>(conn).await
+}
+
+/*
+ *删除用户的所有会话和登录信息,用户的所有token立即失效
+ */
+pub async fn remove_user_sessions(conn: &mut StoreConnection, user_id: i64) -> Result<(), RedisError> {
+    let user_key = user_sessions_key(user_id);
+    for session_id in cmd("ZRANGE").arg(&user_key).arg(0).arg(-1).query_async::<Vec<String>>(conn).await? {
+        remove_session(conn, &session_id).await?;
+    }
+    cmd("DEL").arg(&user_key).arg(user_info_key(user_id)).query_async::<()>(conn).await
+}
+
+/*
+ *查询所有在线会话(按登录时间倒序),已过期的会话会被清理
+ */
+pub async fn list_sessions(conn: &mut StoreConnection) -> Result<Vec<Session>, RedisError> {
+    let mut list: Vec<Session> = Vec::new();
+    for session_id in cmd("ZREVRANGE").arg(ONLINE_SESSIONS_KEY).arg(0).arg(-1).query_async::<Vec<String>>(conn).await? {
+        match get_session(conn, &session_id).await? {
+            Some(session) => list.push(session),
+            None => cmd("ZREM").arg(ONLINE_SESSIONS_KEY).arg(&session_id).query_async::<()>(conn).await?,
+        }
+    }
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::session_store_util::SessionStore;
+    use crate::utils::session_util::{session_key, touch_session};
+    use deadpool_redis::redis::cmd;
+
+    #[tokio::test]
+    async fn test_touch_session() {
+        let store = SessionStore::memory();
+        let mut conn = store.get().await.unwrap();
+
+        //已过期的会话不会被重新创建
+        touch_session(&mut conn, "expired").await.unwrap();
+        assert!(!cmd("EXISTS").arg(session_key("expired")).query_async::<bool>(&mut conn).await.unwrap());
+
+        let key = session_key("active");
+        cmd("HSET").arg(&key).arg("user_id").arg(1).arg("last_activity").arg(0).query_async::<()>(&mut conn).await.unwrap();
+        cmd("EXPIRE").arg(&key).arg(60).query_async::<()>(&mut conn).await.unwrap();
+        touch_session(&mut conn, "active").await.unwrap();
+        assert_ne!(cmd("HGET").arg(&key).arg("last_activity").query_async::<i64>(&mut conn).await.unwrap(), 0);
+        assert_eq!(cmd("TTL").arg(&key).query_async::<i64>(&mut conn).await.unwrap(), 60);
+    }
+}
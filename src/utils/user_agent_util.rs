@@ -1,66 +1,210 @@
-use regex::Regex;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+//sys_login_log中User-Agent各字段的长度
+const FIELD_SIZE: usize = 50;
+
+//设备类型(desktop,mobile,tablet,bot,api)由规则文件中的device_type_parsers决定,没有匹配时为unknown
+pub const DEVICE_TYPE_BOT: &str = "bot";
+pub const DEVICE_TYPE_UNKNOWN: &str = "unknown";
+
+//解析规则编译时嵌入,第一次使用时编译(main启动时会提前加载)
+pub static USER_AGENT_PARSER: Lazy<UserAgentParser> = Lazy::new(|| UserAgentParser::from_yaml(include_str!("../config/user_agent.yaml")).expect("load user agent rules error"));
 
 #[derive(Debug, Clone)]
 pub struct UserAgentUtil {
-    pub platform: String,       //平台信息
+    pub platform: String,       //平台信息(设备名称,例如iPhone、Mac、PC、安卓手机型号)
     pub os: String,             //操作系统信息
     pub arch: String,           //体系结构信息
     pub engine: String,         //渲染引擎信息
-    pub engine_details: String, //渲染引擎详细信息
+    pub engine_details: String, //渲染引擎详细信息(版本)
     pub browser: String,        //浏览器名称
     pub version: String,        //浏览器版本
-    pub extra: String,          //其他信息（可选）
+    pub extra: String,          //其他信息(设备类型)
+    pub device_type: String,    //设备类型(desktop,mobile,tablet,bot,api,unknown)
 }
 
 impl UserAgentUtil {
     pub fn new(user_agent: &str) -> Self {
-        let mut parse = UserAgentUtil {
-            platform: "".to_string(),
-            os: "".to_string(),
-            arch: "".to_string(),
-            engine: "".to_string(),
-            engine_details: "".to_string(),
-            browser: "".to_string(),
-            version: "".to_string(),
-            extra: "".to_string(),
+        USER_AGENT_PARSER.parse(user_agent)
+    }
+
+    pub fn is_bot(&self) -> bool {
+        self.device_type == DEVICE_TYPE_BOT
+    }
+}
+
+/// uap-core格式的规则文件,每一类规则按顺序匹配
+#[derive(Debug, Deserialize)]
+struct RuleFile {
+    user_agent_parsers: Vec<RawRule>,  //浏览器: family, v1, v2, v3
+    os_parsers: Vec<RawRule>,          //操作系统: os, v1, v2, v3
+    device_parsers: Vec<RawRule>,      //设备: device, brand, model
+    device_type_parsers: Vec<RawRule>, //设备类型
+    arch_parsers: Vec<RawRule>,        //体系结构
+    engine_parsers: Vec<RawRule>,      //渲染引擎: engine, version
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRule {
+    regex: String, //正则表达式
+    #[serde(flatten)]
+    replacements: HashMap<String, String>, //xxx_replacement和device_type
+}
+
+#[derive(Debug)]
+struct Rule {
+    regex: Regex,                      //正则表达式
+    replacements: Vec<Option<String>>, //每个输出的替换模板,没有时使用对应的分组
+}
+
+impl Rule {
+    fn compile(raw: &RawRule, keys: &[&str]) -> Result<Rule, String> {
+        let regex = Regex::new(&raw.regex).map_err(|e| format!("invalid user agent rule {}: {}", raw.regex, e))?;
+        let replacements = keys.iter().map(|key| raw.replacements.get(*key).cloned()).collect();
+        Ok(Rule { regex, replacements })
+    }
+
+    /// 匹配成功时依次返回每个输出: 有替换模板时用分组替换$1..$9,否则使用第i个分组
+    fn apply(&self, user_agent: &str) -> Option<Vec<String>> {
+        let captures = self.regex.captures(user_agent)?;
+        let values = self
+            .replacements
+            .iter()
+            .enumerate()
+            .map(|(index, replacement)| match replacement {
+                Some(template) => substitute(template, &captures),
+                None => group(&captures, index + 1).to_string(),
+            })
+            .map(|x| x.trim().to_string())
+            .collect();
+        Some(values)
+    }
+}
+
+fn group<'a>(captures: &Captures<'a>, index: usize) -> &'a str {
+    captures.get(index).map(|x| x.as_str()).unwrap_or_default()
+}
+
+fn substitute(template: &str, captures: &Captures) -> String {
+    let mut result = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek().and_then(|x| x.to_digit(10))) {
+            ('$', Some(index)) => {
+                chars.next();
+                result.push_str(group(captures, index as usize));
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+fn first_match(rules: &[Rule], user_agent: &str) -> Option<Vec<String>> {
+    rules.iter().find_map(|x| x.apply(user_agent))
+}
+
+/*
+ *User-Agent解析器
+ */
+#[derive(Debug)]
+pub struct UserAgentParser {
+    user_agent: Vec<Rule>,  //浏览器规则
+    os: Vec<Rule>,          //操作系统规则
+    device: Vec<Rule>,      //设备规则
+    device_type: Vec<Rule>, //设备类型规则
+    arch: Vec<Rule>,        //体系结构规则
+    engine: Vec<Rule>,      //渲染引擎规则
+}
+
+impl UserAgentParser {
+    pub fn from_yaml(text: &str) -> Result<UserAgentParser, String> {
+        let file: RuleFile = serde_yaml::from_str(text).map_err(|e| format!("parse user agent rules error: {}", e))?;
+        let compile = |rules: &[RawRule], keys: &[&str]| -> Result<Vec<Rule>, String> { rules.iter().map(|x| Rule::compile(x, keys)).collect() };
+        Ok(UserAgentParser {
+            user_agent: compile(&file.user_agent_parsers, &["family_replacement", "v1_replacement", "v2_replacement", "v3_replacement"])?,
+            os: compile(&file.os_parsers, &["os_replacement", "os_v1_replacement", "os_v2_replacement", "os_v3_replacement"])?,
+            device: compile(&file.device_parsers, &["device_replacement", "brand_replacement", "model_replacement"])?,
+            device_type: compile(&file.device_type_parsers, &["device_type"])?,
+            arch: compile(&file.arch_parsers, &["arch_replacement"])?,
+            engine: compile(&file.engine_parsers, &["engine_replacement", "engine_version_replacement"])?,
+        })
+    }
+
+    pub fn parse(&self, user_agent: &str) -> UserAgentUtil {
+        let user_agent = user_agent.trim();
+        let name_and_version = |values: Option<Vec<String>>| -> (String, String) {
+            match values {
+                Some(values) => (values[0].clone(), values[1..].iter().filter(|x| !x.is_empty()).cloned().collect::<Vec<String>>().join(".")),
+                None => ("Other".to_string(), "".to_string()),
+            }
         };
-        // 定义正则表达式来匹配并提取信息
-        let re = Regex::new(
-            r"(?x)
-        Mozilla/5.0 \s*                      # Mozilla标识
-        \((?P<platform>[^;]+); \s*            # 平台信息
-        (?P<os>[^;]+); \s*                    # 操作系统信息
-        (?P<arch>[^\)]+)\) \s*                # 体系结构信息
-        (?P<engine>[^\s]+) \s*                # 渲染引擎信息
-        \((?P<engine_details>[^\)]+)\) \s*    # 渲染引擎详细信息
-        (?P<browser>[^\s/]+)/                 # 浏览器名称
-        (?P<version>[^\s]+) \s*               # 浏览器版本
-        (?P<extra>[^\s]+)?                    # 其他信息（可选）
-    ",
-        )
-        .unwrap();
-
-        // 使用正则表达式进行匹配
-        if let Some(captures) = re.captures(user_agent) {
-            let platform = captures.name("platform").unwrap().as_str();
-            let os = captures.name("os").unwrap().as_str();
-            let arch = captures.name("arch").unwrap().as_str();
-            let engine = captures.name("engine").unwrap().as_str();
-            let engine_details = captures.name("engine_details").unwrap().as_str();
-            let browser = captures.name("browser").unwrap().as_str();
-            let version = captures.name("version").unwrap().as_str();
-            let extra = captures.name("extra").map_or("", |m| m.as_str());
-
-            parse.platform = platform.to_string();
-            parse.os = os.to_string();
-            parse.arch = arch.to_string();
-            parse.engine = engine.to_string();
-            parse.engine_details = engine_details.to_string();
-            parse.browser = browser.to_string();
-            parse.version = version.to_string();
-            parse.extra = extra.to_string();
+
+        let (browser, version) = name_and_version(first_match(&self.user_agent, user_agent));
+        let (os, os_version) = name_and_version(first_match(&self.os, user_agent));
+        let platform = first_match(&self.device, user_agent).map(|x| x[0].clone()).filter(|x| !x.is_empty()).unwrap_or_else(|| "Other".to_string());
+        let device_type = match first_match(&self.device_type, user_agent) {
+            Some(values) => values[0].clone(),
+            None => DEVICE_TYPE_UNKNOWN.to_string(),
+        };
+        let arch = first_match(&self.arch, user_agent).map(|x| x[0].clone()).unwrap_or_default();
+        let (engine, engine_details) = match first_match(&self.engine, user_agent) {
+            Some(values) => (values[0].clone(), values[1].clone()),
+            None => ("".to_string(), "".to_string()),
+        };
+
+        let truncate = |text: String| -> String { text.chars().take(FIELD_SIZE).collect() };
+        UserAgentUtil {
+            platform: truncate(platform),
+            os: truncate(if os_version.is_empty() { os } else { format!("{} {}", os, os_version) }),
+            arch: truncate(arch),
+            engine: truncate(engine),
+            engine_details: truncate(engine_details),
+            browser: truncate(browser),
+            version: truncate(version),
+            extra: device_type.clone(),
+            device_type,
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::user_agent_util::UserAgentUtil;
+    use serde::Deserialize;
 
-        parse
+    #[derive(Debug, Deserialize)]
+    struct Fixture {
+        user_agent: String,
+        browser: String,
+        version: String,
+        os: String,
+        platform: String,
+        device_type: String,
+        #[serde(default)]
+        arch: Option<String>,
+        #[serde(default)]
+        engine: Option<String>,
+    }
+
+    #[test]
+    fn test_user_agent_fixtures() {
+        let fixtures: Vec<Fixture> = serde_yaml::from_str(include_str!("../../tests/fixtures/user_agents.yaml")).unwrap();
+        assert!(!fixtures.is_empty());
+        for fixture in fixtures {
+            let agent = UserAgentUtil::new(&fixture.user_agent);
+            let actual = (&agent.browser, &agent.version, &agent.os, &agent.platform, &agent.device_type);
+            let expected = (&fixture.browser, &fixture.version, &fixture.os, &fixture.platform, &fixture.device_type);
+            assert_eq!(actual, expected, "user agent: {}", fixture.user_agent);
+            if let Some(arch) = &fixture.arch {
+                assert_eq!(&agent.arch, arch, "user agent: {}", fixture.user_agent);
+            }
+            if let Some(engine) = &fixture.engine {
+                assert_eq!(&agent.engine, engine, "user agent: {}", fixture.user_agent);
+            }
+        }
     }
 }
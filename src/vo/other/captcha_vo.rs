@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+/*
+图片验证码响应参数
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptchaResp {
+    pub captcha_id: String, //验证码id,登录时和验证码一起提交
+    pub image: String,      //验证码图片(data:image/png;base64,...)
+    pub expire: u64,        //有效期(秒)
+}
@@ -0,0 +1,25 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/*
+健康检查响应参数
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthResp {
+    pub status: String,                                   //UP: 正常, DOWN: 异常
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub checks: BTreeMap<&'static str, ComponentHealth>, //各依赖的检查结果
+}
+
+/*
+依赖的检查结果
+*/
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentHealth {
+    pub status: String,        //UP: 正常, DOWN: 异常
+    pub duration_ms: u64,      //检查耗时(毫秒)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>, //异常信息
+}
@@ -1 +1,2 @@
-
+pub mod captcha_vo;
+pub mod health_vo;
@@ -7,4 +7,6 @@ pub mod sys_notice_vo;
 pub mod sys_operate_log_vo;
 pub mod sys_post_vo;
 pub mod sys_role_vo;
+pub mod sys_user_mfa_vo;
+pub mod sys_user_online_vo;
 pub mod sys_user_vo;
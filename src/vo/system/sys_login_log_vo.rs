@@ -2,12 +2,13 @@
 // createTime：2024/12/25 10:01:11
 use crate::common::result::serialize_datetime;
 use rbatis::rbdc::DateTime;
+use salvo::oapi::ToSchema;
 use serde::{Deserialize, Serialize};
 
 /*
 删除系统访问记录请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DeleteLoginLogReq {
     pub ids: Vec<i64>,
 }
@@ -15,7 +16,7 @@ pub struct DeleteLoginLogReq {
 /*
 查询系统访问记录详情请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct QueryLoginLogDetailReq {
     pub id: i64,
 }
@@ -23,7 +24,7 @@ pub struct QueryLoginLogDetailReq {
 /*
 查询系统访问记录列表请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryLoginLogListReq {
     pub page_no: u64,
@@ -42,7 +43,7 @@ fn default_status() -> Option<i8> {
 /*
 查询系统访问记录列表响应参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginLogResp {
     pub id: Option<i64>,        //访问ID
@@ -60,5 +61,6 @@ pub struct LoginLogResp {
     pub status: i8,             //登录状态(0:失败,1:成功)
     pub msg: String,            //提示消息
     #[serde(serialize_with = "serialize_datetime")]
+    #[salvo(schema(value_type = Option<String>))]
     pub login_time: Option<DateTime>, //访问时间
 }
@@ -3,12 +3,13 @@
 
 use crate::common::result::serialize_datetime;
 use rbatis::rbdc::DateTime;
+use salvo::oapi::ToSchema;
 use serde::{Deserialize, Serialize};
 
 /*
 删除角色信息请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DeleteRoleReq {
     pub ids: Vec<i64>,
 }
@@ -16,7 +17,7 @@ pub struct DeleteRoleReq {
 /*
 更新角色信息请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RoleReq {
     pub id: Option<i64>,        //主键
@@ -24,13 +25,15 @@ pub struct RoleReq {
     pub role_key: String,       //角色权限字符串
     pub data_scope: i8,         //数据范围（1：全部数据权限 2：自定数据权限 3：本部门数据权限 4：本部门及以下数据权限）
     pub status: i8,             //状态(1:正常，0:禁用)
+    #[serde(default)]
+    pub mfa_required: i8, //是否要求双因素认证(0:否,1:是)
     pub remark: Option<String>, //备注
 }
 
 /*
 更新角色信息状态请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateRoleStatusReq {
     pub ids: Vec<i64>,
     pub status: i8,
@@ -39,7 +42,7 @@ pub struct UpdateRoleStatusReq {
 /*
 查询角色信息详情请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct QueryRoleDetailReq {
     pub id: i64,
 }
@@ -47,7 +50,7 @@ pub struct QueryRoleDetailReq {
 /*
 查询角色信息列表请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryRoleListReq {
     pub page_no: u64,
@@ -63,7 +66,7 @@ fn default_status() -> Option<i8> {
 /*
 查询角色信息列表响应参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RoleResp {
     pub id: Option<i64>,        //主键
@@ -71,17 +74,20 @@ pub struct RoleResp {
     pub role_key: String,       //角色权限字符串
     pub data_scope: i8,         //数据范围（1：全部数据权限 2：自定数据权限 3：本部门数据权限 4：本部门及以下数据权限）
     pub status: i8,             //状态(1:正常，0:禁用)
+    pub mfa_required: i8,       //是否要求双因素认证(0:否,1:是)
     pub remark: Option<String>, //备注
     #[serde(serialize_with = "serialize_datetime")]
+    #[salvo(schema(value_type = Option<String>))]
     pub create_time: Option<DateTime>, //创建时间
     #[serde(serialize_with = "serialize_datetime")]
+    #[salvo(schema(value_type = Option<String>))]
     pub update_time: Option<DateTime>, //修改时间
 }
 
 /*
 查询角色菜单信息参数
 */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryRoleMenuReq {
     pub role_id: i64, //角色id
@@ -90,7 +96,7 @@ pub struct QueryRoleMenuReq {
 /*
 角色菜单信息参数
 */
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryRoleMenuData {
     pub menu_ids: Vec<Option<i64>>,   //菜单Ids
@@ -100,7 +106,7 @@ pub struct QueryRoleMenuData {
 /*
 菜单信息参数
 */
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MenuDataList {
     pub id: Option<i64>,        //主键
@@ -114,17 +120,61 @@ pub struct MenuDataList {
 /*
 更新用户角色信息
 */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateRoleMenuReq {
     pub menu_ids: Vec<i64>,
     pub role_id: i64,
 }
 
+/*
+查询角色数据权限请求参数
+*/
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRoleDeptReq {
+    pub role_id: i64, //角色id
+}
+
+/*
+角色数据权限参数
+*/
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRoleDeptData {
+    pub data_scope: i8,               //数据范围（1：全部数据权限 2：自定数据权限 3：本部门数据权限 4：本部门及以下数据权限）
+    pub dept_ids: Vec<i64>,           //自定数据权限的部门Ids
+    pub dept_list: Vec<DeptDataList>, //部门列表
+}
+
+/*
+部门信息参数
+*/
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeptDataList {
+    pub id: Option<i64>, //主键
+    pub parent_id: i64,  //父ID
+    pub title: String,
+    pub key: String,
+    pub label: String,
+}
+
+/*
+更新角色数据权限请求参数
+*/
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRoleDeptReq {
+    pub role_id: i64,       //角色id
+    pub data_scope: i8,     //数据范围（1：全部数据权限 2：自定数据权限 3：本部门数据权限 4：本部门及以下数据权限）
+    pub dept_ids: Vec<i64>, //自定数据权限的部门Ids
+}
+
 /*
 查询已分配用户角色列表
 */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AllocatedListReq {
     pub page_no: u64,
@@ -137,7 +187,7 @@ pub struct AllocatedListReq {
 /*
 查询未分配用户角色列表
 */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UnallocatedListReq {
     pub page_no: u64,
@@ -150,7 +200,7 @@ pub struct UnallocatedListReq {
 /*
 取消授权用户
 */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CancelAuthUserReq {
     pub user_id: i64,
@@ -160,7 +210,7 @@ pub struct CancelAuthUserReq {
 /*
 批量取消授权用户
 */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CancelAuthUserAllReq {
     pub user_ids: Vec<i64>,
@@ -170,7 +220,7 @@ pub struct CancelAuthUserAllReq {
 /*
 批量选择用户授权
 */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SelectAuthUserAllReq {
     pub user_ids: Vec<i64>,
@@ -0,0 +1,72 @@
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+
+/*
+登录需要双因素认证时的响应参数
+*/
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginChallengeResp {
+    pub mfa_required: bool,      //需要双因素认证
+    pub mfa_enrolled: bool,      //是否已经绑定身份验证器(未绑定时先调用enrollByChallenge绑定)
+    pub challenge_token: String, //挑战token,登录第二步使用
+    pub expires_in: u64,         //挑战token有效期(秒)
+}
+
+/*
+登录第二步请求参数
+*/
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaLoginReq {
+    pub challenge_token: String, //挑战token
+    pub code: String,            //身份验证器中的动态码或者恢复码
+}
+
+/*
+通过挑战token绑定身份验证器请求参数
+*/
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaChallengeReq {
+    pub challenge_token: String, //挑战token
+}
+
+/*
+动态码请求参数
+*/
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MfaCodeReq {
+    pub code: String, //身份验证器中的动态码(关闭时也可以使用恢复码)
+}
+
+/*
+绑定身份验证器响应参数
+*/
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaEnrollResp {
+    pub secret: String,      //TOTP密钥(Base32),无法扫码时手动输入
+    pub otpauth_uri: String, //otpauth URI
+    pub qr_code: String,     //二维码图片(data:image/png;base64,...)
+}
+
+/*
+启用双因素认证响应参数
+*/
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaActivateResp {
+    pub recovery_codes: Vec<String>, //恢复码,只显示一次
+}
+
+/*
+查询双因素认证状态响应参数
+*/
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaStatusResp {
+    pub enabled: bool,         //是否已启用
+    pub required: bool,        //角色是否要求双因素认证
+    pub recovery_codes: usize, //剩余恢复码数量
+}
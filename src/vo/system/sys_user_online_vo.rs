@@ -0,0 +1,67 @@
+use crate::utils::session_util::Session;
+use chrono::{Local, TimeZone};
+use salvo::oapi::ToSchema;
+use serde::{Deserialize, Serialize};
+
+/*
+查询在线用户列表请求参数
+*/
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryUserOnlineListReq {
+    pub page_no: u64,
+    pub page_size: u64,
+    pub user_name: Option<String>, //用户账号
+    pub ipaddr: Option<String>,    //登录IP地址
+}
+
+/*
+强制退出请求参数
+*/
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ForceLogoutReq {
+    pub session_ids: Vec<String>, //会话ids
+}
+
+/*
+查询在线用户列表响应参数
+*/
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOnlineResp {
+    pub session_id: String,     //会话id
+    pub user_id: i64,           //用户id
+    pub user_name: String,      //用户账号
+    pub ipaddr: String,         //登录IP地址
+    pub login_location: String, //登录地点
+    pub platform: String,       //平台信息
+    pub browser: String,        //浏览器类型
+    pub os: String,             //操作系统
+    pub login_time: String,     //登录时间
+    pub last_activity: String,  //最后访问时间
+}
+
+impl From<Session> for UserOnlineResp {
+    fn from(item: Session) -> Self {
+        UserOnlineResp {
+            session_id: item.session_id,
+            user_id: item.user_id,
+            user_name: item.user_name,
+            ipaddr: item.ipaddr,
+            login_location: item.login_location,
+            platform: item.platform,
+            browser: item.browser,
+            os: item.os,
+            login_time: format_timestamp(item.login_time),
+            last_activity: format_timestamp(item.last_activity),
+        }
+    }
+}
+
+fn format_timestamp(t: i64) -> String {
+    match Local.timestamp_opt(t, 0).single() {
+        None => "".to_string(),
+        Some(x) => x.format("%Y-%m-%d %H:%M:%S").to_string(),
+    }
+}
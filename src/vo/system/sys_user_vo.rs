@@ -4,12 +4,17 @@
 use crate::common::result::serialize_datetime;
 use crate::vo::system::sys_dept_vo::DeptResp;
 use rbatis::rbdc::DateTime;
+use salvo::oapi::ToSchema;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use crate::vo::system::sys_role_vo::RoleResp;
+
+//日志中代替密码输出的内容
+const MASKED: &str = "******";
 /*
 删除用户信息请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DeleteUserReq {
     pub ids: Vec<i64>,
 }
@@ -17,7 +22,7 @@ pub struct DeleteUserReq {
 /*
 更新用户信息请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserReq {
     pub id: Option<i64>,   //主键
@@ -33,13 +38,32 @@ pub struct UserReq {
     pub remark: Option<String>, //备注
     pub post_ids: Vec<i64>, //岗位ids
 }
+//打印请求参数时隐藏密码
+impl fmt::Debug for UserReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserReq")
+            .field("id", &self.id)
+            .field("mobile", &self.mobile)
+            .field("user_name", &self.user_name)
+            .field("nick_name", &self.nick_name)
+            .field("password", &self.password.as_ref().map(|_| MASKED))
+            .field("email", &self.email)
+            .field("avatar", &self.avatar)
+            .field("status", &self.status)
+            .field("dept_id", &self.dept_id)
+            .field("remark", &self.remark)
+            .field("post_ids", &self.post_ids)
+            .finish()
+    }
+}
+
 fn default_avatar() -> Option<String> {
     Some("https://gw.alipayobjects.com/zos/antfincdn/XAosXuNZyF/BiazfanxmamNRoxxVxka.png".to_string())
 }
 /*
 更新用户信息状态请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateUserStatusReq {
     pub ids: Vec<i64>,
     pub status: i8,
@@ -48,7 +72,7 @@ pub struct UpdateUserStatusReq {
 /*
 查询用户信息详情请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct QueryUserDetailReq {
     pub id: i64,
 }
@@ -56,7 +80,7 @@ pub struct QueryUserDetailReq {
 /*
 查询用户信息列表请求参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryUserListReq {
     pub page_no: u64,
@@ -73,7 +97,7 @@ fn default_status() -> Option<i8> {
 /*
 查询用户信息列表响应参数
 */
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserResp {
     pub id: Option<i64>,        //主键
@@ -87,15 +111,19 @@ pub struct UserResp {
     pub dept_id: i64,           //部门ID
     pub login_ip: String,       //最后登录IP
     #[serde(serialize_with = "serialize_datetime")]
+    #[salvo(schema(value_type = Option<String>))]
     pub login_date: Option<DateTime>, //最后登录时间
     pub login_browser: String,  //浏览器类型
     pub login_os: String,       //操作系统
     #[serde(serialize_with = "serialize_datetime")]
+    #[salvo(schema(value_type = Option<String>))]
     pub pwd_update_date: Option<DateTime>, //密码最后更新时间
     pub remark: Option<String>, //备注
     #[serde(serialize_with = "serialize_datetime")]
+    #[salvo(schema(value_type = Option<String>))]
     pub create_time: Option<DateTime>, //创建时间
     #[serde(serialize_with = "serialize_datetime")]
+    #[salvo(schema(value_type = Option<String>))]
     pub update_time: Option<DateTime>, //修改时间
     pub dept_info: Option<DeptResp>, //部门详细信息
     pub post_ids: Option<Vec<i64>>, //岗位ids
@@ -103,20 +131,57 @@ pub struct UserResp {
 /*
 登录请求参数
 */
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct UserLoginReq {
-    pub mobile: String,   //手机
-    pub password: String, //密码
+    pub mobile: String,               //手机
+    pub password: String,             //密码
+    pub captcha_id: Option<String>,   //验证码id
+    pub captcha_code: Option<String>, //验证码
+}
+
+impl fmt::Debug for UserLoginReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserLoginReq")
+            .field("mobile", &self.mobile)
+            .field("password", &MASKED)
+            .field("captcha_id", &self.captcha_id)
+            .field("captcha_code", &self.captcha_code)
+            .finish()
+    }
+}
+
+/*
+登录响应参数
+*/
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UserLoginResp {
+    pub token: String,         //访问token
+    pub refresh_token: String, //刷新token
+    pub expires_in: u64,       //访问token有效期(秒)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_codes: Option<Vec<String>>, //首次启用双因素认证时返回的恢复码,只显示一次
+}
+
+/*
+刷新token请求参数
+*/
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshTokenReq {
+    pub refresh_token: String, //刷新token
 }
 
 /*
 查询用户菜单响应参数
 */
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryUserMenuResp {
     pub sys_menu: Vec<MenuList>,
     pub btn_menu: Vec<String>,
+    pub perms: Vec<String>,
     pub avatar: String,
     pub name: String,
 }
@@ -124,7 +189,7 @@ pub struct QueryUserMenuResp {
 /*
 用户菜单参数
 */
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MenuList {
     pub id: Option<i64>,
@@ -139,7 +204,7 @@ pub struct MenuList {
 /*
 查询用户关联角色请求参数
 */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryUserRoleReq {
     pub user_id: i64,
@@ -148,7 +213,7 @@ pub struct QueryUserRoleReq {
 /*
 用户关联角色响应参数
 */
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryUserRoleResp {
     pub sys_role_list: Vec<RoleResp>,
@@ -158,28 +223,49 @@ pub struct QueryUserRoleResp {
 /*
 更新用户关联角色请求参数
 */
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUserRoleReq {
     pub user_id: i64,       //用户主键
     pub role_ids: Vec<i64>, //角色主键
 }
 
+/*
+解锁用户请求参数
+*/
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UnlockUserReq {
+    pub id: i64,                //用户主键
+    pub ipaddr: Option<String>, //同时解锁的IP地址(可选)
+}
+
 /*
 重置密码
 */
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ResetUserPwdReq {
     pub id: i64,          //用户主键
     pub password: String, //用户密码
 }
 
+impl fmt::Debug for ResetUserPwdReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResetUserPwdReq").field("id", &self.id).field("password", &MASKED).finish()
+    }
+}
+
 /*
 重置密码
 */
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUserPwdReq {
     pub pwd: String,    //用户密码
     pub re_pwd: String, //用户密码
 }
+
+impl fmt::Debug for UpdateUserPwdReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpdateUserPwdReq").field("pwd", &MASKED).field("re_pwd", &MASKED).finish()
+    }
+}